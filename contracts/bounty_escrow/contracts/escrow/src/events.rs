@@ -1,4 +1,4 @@
-use soroban_sdk::{contracttype, symbol_short, Address, Env};
+use soroban_sdk::{contracttype, symbol_short, Address, BytesN, Env, Vec};
 
 pub const EVENT_VERSION_V2: u32 = 2;
 
@@ -23,6 +23,7 @@ pub struct FundsLocked {
     pub bounty_id: u64,
     pub amount: i128,
     pub depositor: Address,
+    pub token: Address,
     pub deadline: u64,
 }
 
@@ -31,6 +32,41 @@ pub fn emit_funds_locked(env: &Env, event: FundsLocked) {
     env.events().publish(topics, event.clone());
 }
 
+#[contracttype]
+#[derive(Clone, Debug)]
+pub struct FundsToppedUp {
+    pub version: u32,
+    pub bounty_id: u64,
+    pub funder: Address,
+    pub amount: i128,
+    pub new_total: i128,
+    pub timestamp: u64,
+}
+
+pub fn emit_funds_topped_up(env: &Env, event: FundsToppedUp) {
+    let topics = (symbol_short!("f_topup"), event.bounty_id);
+    env.events().publish(topics, event.clone());
+}
+
+#[contracttype]
+#[derive(Clone, Debug)]
+pub struct EscrowAmended {
+    pub version: u32,
+    pub bounty_id: u64,
+    pub old_amount: i128,
+    pub new_amount: i128,
+    pub old_deadline: u64,
+    pub new_deadline: u64,
+    pub depositor: Address,
+    pub admin_cosigned: bool,
+    pub timestamp: u64,
+}
+
+pub fn emit_escrow_amended(env: &Env, event: EscrowAmended) {
+    let topics = (symbol_short!("amend"), event.bounty_id);
+    env.events().publish(topics, event.clone());
+}
+
 #[contracttype]
 #[derive(Clone, Debug)]
 pub struct FundsReleased {
@@ -71,6 +107,8 @@ pub enum FeeOperationType {
 #[contracttype]
 #[derive(Clone, Debug)]
 pub struct FeeCollected {
+    pub version: u32,
+    pub bounty_id: u64,
     pub operation_type: FeeOperationType,
     pub amount: i128,
     pub fee_rate: i128,
@@ -79,7 +117,7 @@ pub struct FeeCollected {
 }
 
 pub fn emit_fee_collected(env: &Env, event: FeeCollected) {
-    let topics = (symbol_short!("fee"),);
+    let topics = (symbol_short!("fee"), event.bounty_id);
     env.events().publish(topics, event.clone());
 }
 
@@ -129,6 +167,7 @@ pub fn emit_batch_funds_released(env: &Env, event: BatchFundsReleased) {
 pub struct ApprovalAdded {
     pub bounty_id: u64,
     pub contributor: Address,
+    pub amount: i128,
     pub approver: Address,
     pub timestamp: u64,
 }
@@ -138,6 +177,20 @@ pub fn emit_approval_added(env: &Env, event: ApprovalAdded) {
     env.events().publish(topics, event.clone());
 }
 
+#[contracttype]
+#[derive(Clone, Debug)]
+pub struct ApprovalRevoked {
+    pub bounty_id: u64,
+    pub contributor: Address,
+    pub approver: Address,
+    pub timestamp: u64,
+}
+
+pub fn emit_approval_revoked(env: &Env, event: ApprovalRevoked) {
+    let topics = (symbol_short!("appr_rev"), event.bounty_id);
+    env.events().publish(topics, event.clone());
+}
+
 #[contracttype]
 #[derive(Clone, Debug, Eq, PartialEq)]
 pub struct ClaimCreated {
@@ -166,6 +219,98 @@ pub struct ClaimCancelled {
     pub cancelled_by: Address,
 }
 
+#[contracttype]
+#[derive(Clone, Debug)]
+pub struct TokenAllowlistUpdated {
+    pub version: u32,
+    pub token: Address,
+    pub allowed: bool,
+    pub admin: Address,
+    pub timestamp: u64,
+}
+
+pub fn emit_token_allowlist_updated(env: &Env, event: TokenAllowlistUpdated) {
+    let topics = (symbol_short!("token"), event.token.clone());
+    env.events().publish(topics, event.clone());
+}
+
+#[contracttype]
+#[derive(Clone, Debug)]
+pub struct ArbitersUpdated {
+    pub version: u32,
+    pub arbiters: Vec<Address>,
+    pub admin: Address,
+    pub timestamp: u64,
+}
+
+pub fn emit_arbiters_updated(env: &Env, event: ArbitersUpdated) {
+    let topics = (symbol_short!("arbiters"),);
+    env.events().publish(topics, event.clone());
+}
+
+#[contracttype]
+#[derive(Clone, Debug)]
+pub struct DisputeOpened {
+    pub version: u32,
+    pub bounty_id: u64,
+    pub opened_by: Address,
+    pub evidence_hash: BytesN<32>,
+    pub timestamp: u64,
+}
+
+pub fn emit_dispute_opened(env: &Env, event: DisputeOpened) {
+    let topics = (symbol_short!("disp_open"), event.bounty_id);
+    env.events().publish(topics, event.clone());
+}
+
+#[contracttype]
+#[derive(Clone, Debug)]
+pub struct DisputeResolved {
+    pub version: u32,
+    pub bounty_id: u64,
+    pub arbiter: Address,
+    pub contributor: Address,
+    pub contributor_amount: i128,
+    pub depositor_amount: i128,
+    pub timestamp: u64,
+}
+
+pub fn emit_dispute_resolved(env: &Env, event: DisputeResolved) {
+    let topics = (symbol_short!("disp_res"), event.bounty_id);
+    env.events().publish(topics, event.clone());
+}
+
+#[contracttype]
+#[derive(Clone, Debug)]
+pub struct DisputeExpired {
+    pub version: u32,
+    pub bounty_id: u64,
+    pub restored_status: crate::EscrowStatus,
+    pub timestamp: u64,
+}
+
+pub fn emit_dispute_expired(env: &Env, event: DisputeExpired) {
+    let topics = (symbol_short!("disp_exp"), event.bounty_id);
+    env.events().publish(topics, event.clone());
+}
+
+#[contracttype]
+#[derive(Clone, Debug)]
+pub struct MilestoneSettled {
+    pub version: u32,
+    pub bounty_id: u64,
+    pub milestone_index: u32,
+    pub status: crate::MilestoneStatus,
+    pub amount: i128,
+    pub recipient: Address,
+    pub timestamp: u64,
+}
+
+pub fn emit_milestone_settled(env: &Env, event: MilestoneSettled) {
+    let topics = (symbol_short!("milestone"), event.bounty_id);
+    env.events().publish(topics, event.clone());
+}
+
 pub fn emit_pause_state_changed(env: &Env, event: crate::PauseStateChanged) {
     let topics = (symbol_short!("pause"), event.operation.clone());
     env.events().publish(topics, event);
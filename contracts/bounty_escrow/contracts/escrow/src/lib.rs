@@ -4,14 +4,15 @@ mod events;
 mod test_bounty_escrow;
 
 use events::{
-    emit_batch_funds_locked, emit_batch_funds_released, emit_bounty_initialized, emit_funds_locked,
-    emit_funds_refunded, emit_funds_released, BatchFundsLocked, BatchFundsReleased,
-    BountyEscrowInitialized, ClaimCancelled, ClaimCreated, ClaimExecuted, FundsLocked,
-    FundsRefunded, FundsReleased, EVENT_VERSION_V2,
+    emit_batch_funds_locked, emit_batch_funds_released, emit_bounty_initialized,
+    emit_fee_collected, emit_funds_locked, emit_funds_refunded, emit_funds_released,
+    BatchFundsLocked, BatchFundsReleased, BountyEscrowInitialized, ClaimCancelled, ClaimCreated,
+    ClaimExecuted, FeeCollected, FeeOperationType, FundsLocked, FundsRefunded, FundsReleased,
+    EVENT_VERSION_V2,
 };
 use soroban_sdk::{
-    contract, contracterror, contractimpl, contracttype, symbol_short, token, vec, Address, Env,
-    Symbol, Vec,
+    contract, contracterror, contractimpl, contracttype, symbol_short, token, vec, Address, BytesN,
+    Env, Symbol, Vec,
 };
 
 // ==================== MONITORING MODULE ====================
@@ -340,6 +341,9 @@ mod anti_abuse {
 const BASIS_POINTS: i128 = 10_000;
 const MAX_FEE_RATE: i128 = 5_000; // 50% max fee
 const MAX_BATCH_SIZE: u32 = 20;
+const MAX_MILESTONES: u32 = 20;
+const MAX_REFUND_SWEEP: u32 = 50;
+const DEFAULT_DISPUTE_WINDOW: u64 = 7 * 24 * 60 * 60; // 7 days
 
 #[contracterror]
 #[derive(Copy, Clone, Debug, Eq, PartialEq, PartialOrd, Ord)]
@@ -370,6 +374,36 @@ pub enum Error {
     AmountBelowMinimum = 19,
     /// Returned when lock amount is above the configured policy maximum (Issue #62)
     AmountAboveMaximum = 20,
+    /// Returned when the token is not on the admin-managed allowlist
+    TokenNotAllowed = 21,
+    /// Returned when a release above the multisig threshold lacks enough signer approvals
+    InsufficientApprovals = 22,
+    /// Returned when a release approval is bound to a different contributor
+    ApprovalContributorMismatch = 23,
+    /// Returned when release, refund or claim is attempted while a dispute is open
+    EscrowDisputed = 24,
+    /// Returned when no open dispute exists for the bounty
+    DisputeNotFound = 25,
+    /// Returned when milestone amounts do not add up to the locked amount
+    MilestoneAmountMismatch = 26,
+    /// Returned when the milestone index is out of range for the bounty
+    MilestoneNotFound = 27,
+    /// Returned when the milestone has already been released or refunded
+    MilestoneNotPending = 28,
+    /// Returned when a whole-escrow payout is attempted on a milestone bounty
+    MilestoneReleaseRequired = 29,
+    /// Returned when split shares are empty, duplicated, or do not sum to 10,000 basis points
+    InvalidSplit = 30,
+    /// Returned when a release approval was given for a different amount
+    ApprovalAmountMismatch = 31,
+    /// Returned when a dispute is opened but no arbiters are configured
+    NoArbiters = 32,
+    /// Returned when an arbiter tries to resolve a dispute after its deadline
+    DisputeExpired = 33,
+    /// Returned when a dispute payout names someone other than a claim recipient
+    DisputePayeeMismatch = 34,
+    /// Returned when a refund is attempted while an authorized claim is still unclaimed
+    ClaimPending = 35,
 }
 
 #[contracttype]
@@ -379,13 +413,20 @@ pub enum EscrowStatus {
     Released,
     Refunded,
     PartiallyRefunded,
+    /// Frozen by an open dispute until an arbiter resolves it
+    Disputed,
+    /// Split claims are authorized; only `claim_share`, `cancel_split_claims`
+    /// and disputes apply until they settle
+    PendingSplit,
 }
 
 #[contracttype]
 #[derive(Clone, Debug, Eq, PartialEq)]
 pub struct Escrow {
     pub depositor: Address,
-    /// Total amount originally locked into this escrow.
+    /// Token contract the bounty is funded and paid out in.
+    pub token: Address,
+    /// Total amount originally locked into this escrow, net of any lock fee.
     pub amount: i128,
     /// Amount still available for release; decremented on each partial_release.
     /// Reaches 0 when fully paid out, at which point status becomes Released.
@@ -393,12 +434,44 @@ pub struct Escrow {
     pub status: EscrowStatus,
     pub deadline: u64,
     pub refund_history: Vec<RefundRecord>,
+    /// Funding stages for milestone bounties; empty for single-payout bounties.
+    pub milestones: Vec<Milestone>,
+}
+
+#[contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub enum MilestoneStatus {
+    Pending,
+    Released,
+    Refunded,
+}
+
+/// Milestone definition supplied to `lock_funds_with_milestones`.
+#[contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct MilestoneSpec {
+    /// Hash of the off-chain deliverable description.
+    pub label_hash: BytesN<32>,
+    pub amount: i128,
+    /// Refund deadline for this stage; falls back to the escrow deadline.
+    pub deadline: Option<u64>,
+}
+
+#[contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct Milestone {
+    pub label_hash: BytesN<32>,
+    /// Amount held for this stage, net of any lock fee.
+    pub amount: i128,
+    pub deadline: Option<u64>,
+    pub status: MilestoneStatus,
 }
 
 #[contracttype]
 pub enum DataKey {
     Admin,
-    Token,
+    Token,                   // default token used by lock_funds
+    AllowedTokens,           // Vec<Address> of token contracts accepted for escrows
     Escrow(u64),             // bounty_id
     EscrowIndex,             // Vec<u64> of all bounty_ids
     DepositorIndex(Address), // Vec<u64> of bounty_ids by depositor
@@ -408,9 +481,14 @@ pub enum DataKey {
     MultisigConfig,
     ReleaseApproval(u64), // bounty_id -> ReleaseApproval
     PendingClaim(u64),    // bounty_id -> ClaimRecord
+    SplitClaims(u64),     // bounty_id -> Vec<ClaimRecord>, one per co-contributor
+    Contributions(u64),   // bounty_id -> Vec<Contribution>, set once a bounty is topped up
     ClaimWindow,          // u64 seconds (global config)
     PauseFlags,           // PauseFlags struct
     AmountPolicy, // Option<(i128, i128)> — (min_amount, max_amount) set by set_amount_policy
+    Arbiters,     // Vec<Address> allowed to resolve disputes
+    Dispute(u64), // bounty_id -> Dispute
+    DisputeWindow, // u64 seconds arbiters have to resolve a dispute
 }
 
 #[contracttype]
@@ -439,6 +517,19 @@ pub struct AggregateStats {
     pub count_refunded: u32,
 }
 
+/// Outcome of one `refund_expired` page.
+#[contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct RefundSweepSummary {
+    /// Number of index entries examined in this page.
+    pub scanned: u32,
+    pub refunded_ids: Vec<u64>,
+    pub total_refunded: i128,
+    /// Index position to pass as `offset` for the next page; equals the index
+    /// length once the sweep is complete.
+    pub next_offset: u32,
+}
+
 #[contracttype]
 #[derive(Clone, Debug, Eq, PartialEq)]
 pub struct PauseStateChanged {
@@ -469,6 +560,10 @@ pub struct MultisigConfig {
 pub struct ReleaseApproval {
     pub bounty_id: u64,
     pub contributor: Address,
+    /// Exact amount the signers approved for release.
+    pub amount: i128,
+    /// Co-contributor split the approval covers; empty for a single payee.
+    pub shares: Vec<SplitShare>,
     pub approvals: Vec<Address>,
 }
 
@@ -509,6 +604,19 @@ pub struct RefundRecord {
     pub mode: RefundMode,
 }
 
+#[contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct Dispute {
+    pub bounty_id: u64,
+    pub opened_by: Address,
+    pub evidence_hash: BytesN<32>,
+    pub opened_at: u64,
+    /// Arbiters must resolve by this time; afterwards the dispute can only lapse.
+    pub resolve_by: u64,
+    /// Status the escrow returns to if the dispute lapses unresolved.
+    pub prior_status: EscrowStatus,
+}
+
 #[contracttype]
 #[derive(Clone, Debug, Eq, PartialEq)]
 pub struct LockFundsItem {
@@ -525,6 +633,22 @@ pub struct ReleaseFundsItem {
     pub contributor: Address,
 }
 
+/// Tokens a single funder has put into a bounty, net of any lock fee.
+#[contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct Contribution {
+    pub funder: Address,
+    pub amount: i128,
+}
+
+/// One co-contributor's share of a split payout, in basis points of the total.
+#[contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct SplitShare {
+    pub recipient: Address,
+    pub basis_points: u32,
+}
+
 #[contract]
 pub struct BountyEscrowContract;
 
@@ -537,6 +661,9 @@ impl BountyEscrowContract {
         }
         env.storage().instance().set(&DataKey::Admin, &admin);
         env.storage().instance().set(&DataKey::Token, &token);
+        env.storage()
+            .instance()
+            .set(&DataKey::AllowedTokens, &vec![&env, token.clone()]);
 
         emit_bounty_initialized(
             &env,
@@ -564,6 +691,95 @@ impl BountyEscrowContract {
             .unwrap_or(0)
     }
 
+    /// Deduct the configured fee for `operation_type` from `amount`.
+    ///
+    /// The fee is transferred from the contract to the configured fee recipient
+    /// and a `FeeCollected` event is emitted. Returns the net amount left after
+    /// the fee; when fees are disabled or the rate is zero, `amount` is returned
+    /// unchanged and nothing is transferred.
+    fn collect_fee(
+        env: &Env,
+        client: &token::Client,
+        bounty_id: u64,
+        amount: i128,
+        operation_type: FeeOperationType,
+    ) -> i128 {
+        let fee_config = Self::get_fee_config_internal(env);
+        if !fee_config.fee_enabled {
+            return amount;
+        }
+
+        let fee_rate = match operation_type {
+            FeeOperationType::Lock => fee_config.lock_fee_rate,
+            FeeOperationType::Release => fee_config.release_fee_rate,
+        };
+        let fee = Self::calculate_fee(amount, fee_rate);
+        if fee <= 0 {
+            return amount;
+        }
+
+        client.transfer(
+            &env.current_contract_address(),
+            &fee_config.fee_recipient,
+            &fee,
+        );
+
+        emit_fee_collected(
+            env,
+            FeeCollected {
+                version: EVENT_VERSION_V2,
+                bounty_id,
+                operation_type,
+                amount: fee,
+                fee_rate,
+                recipient: fee_config.fee_recipient,
+                timestamp: env.ledger().timestamp(),
+            },
+        );
+
+        amount - fee
+    }
+
+    /// Divide `total` across `shares`, returning one amount per share.
+    ///
+    /// Shares must be non-empty, have distinct recipients, and sum to exactly
+    /// `BASIS_POINTS`. Each amount is rounded down; the rounding dust goes to
+    /// the first share so the amounts always add up to `total`.
+    fn split_amounts(env: &Env, shares: &Vec<SplitShare>, total: i128) -> Result<Vec<i128>, Error> {
+        if shares.is_empty() || shares.len() > MAX_BATCH_SIZE {
+            return Err(Error::InvalidSplit);
+        }
+
+        let mut bps_total: i128 = 0;
+        let mut seen: Vec<Address> = Vec::new(env);
+        for share in shares.iter() {
+            if share.basis_points == 0 || seen.contains(&share.recipient) {
+                return Err(Error::InvalidSplit);
+            }
+            seen.push_back(share.recipient.clone());
+            bps_total += share.basis_points as i128;
+        }
+        if bps_total != BASIS_POINTS {
+            return Err(Error::InvalidSplit);
+        }
+
+        let mut amounts: Vec<i128> = Vec::new(env);
+        let mut allocated: i128 = 0;
+        for share in shares.iter() {
+            let amount = total
+                .checked_mul(share.basis_points as i128)
+                .and_then(|x| x.checked_div(BASIS_POINTS))
+                .ok_or(Error::InvalidAmount)?;
+            allocated += amount;
+            amounts.push_back(amount);
+        }
+        let dust = total - allocated;
+        if dust > 0 {
+            amounts.set(0, amounts.get(0).unwrap() + dust);
+        }
+        Ok(amounts)
+    }
+
     /// Get fee configuration (internal helper)
     fn get_fee_config_internal(env: &Env) -> FeeConfig {
         env.storage()
@@ -715,6 +931,79 @@ impl BountyEscrowContract {
         false
     }
 
+    /// Add a token contract to the allowlist (admin only).
+    /// Bounties can only be locked in allowlisted tokens.
+    pub fn add_allowed_token(env: Env, token: Address) -> Result<(), Error> {
+        if !env.storage().instance().has(&DataKey::Admin) {
+            return Err(Error::NotInitialized);
+        }
+        let admin: Address = env.storage().instance().get(&DataKey::Admin).unwrap();
+        admin.require_auth();
+
+        let mut tokens = Self::get_allowed_tokens(env.clone());
+        if !tokens.contains(&token) {
+            tokens.push_back(token.clone());
+            env.storage()
+                .instance()
+                .set(&DataKey::AllowedTokens, &tokens);
+        }
+
+        events::emit_token_allowlist_updated(
+            &env,
+            events::TokenAllowlistUpdated {
+                version: EVENT_VERSION_V2,
+                token,
+                allowed: true,
+                admin,
+                timestamp: env.ledger().timestamp(),
+            },
+        );
+        Ok(())
+    }
+
+    /// Remove a token contract from the allowlist (admin only).
+    /// Existing escrows in that token can still be released and refunded.
+    pub fn remove_allowed_token(env: Env, token: Address) -> Result<(), Error> {
+        if !env.storage().instance().has(&DataKey::Admin) {
+            return Err(Error::NotInitialized);
+        }
+        let admin: Address = env.storage().instance().get(&DataKey::Admin).unwrap();
+        admin.require_auth();
+
+        let mut tokens = Self::get_allowed_tokens(env.clone());
+        if let Some(index) = tokens.first_index_of(&token) {
+            tokens.remove(index);
+            env.storage()
+                .instance()
+                .set(&DataKey::AllowedTokens, &tokens);
+        }
+
+        events::emit_token_allowlist_updated(
+            &env,
+            events::TokenAllowlistUpdated {
+                version: EVENT_VERSION_V2,
+                token,
+                allowed: false,
+                admin,
+                timestamp: env.ledger().timestamp(),
+            },
+        );
+        Ok(())
+    }
+
+    /// Get all token contracts currently accepted for new escrows
+    pub fn get_allowed_tokens(env: Env) -> Vec<Address> {
+        env.storage()
+            .instance()
+            .get(&DataKey::AllowedTokens)
+            .unwrap_or(Vec::new(&env))
+    }
+
+    /// Check whether a token contract is accepted for new escrows
+    pub fn is_token_allowed(env: Env, token: Address) -> bool {
+        Self::get_allowed_tokens(env).contains(&token)
+    }
+
     /// Get current fee configuration (view function)
     pub fn get_fee_config(env: Env) -> FeeConfig {
         Self::get_fee_config_internal(&env)
@@ -764,16 +1053,66 @@ impl BountyEscrowContract {
     }
 
     /// Approve release for large amount (requires multisig)
+    ///
+    /// Approvals are bound to both `contributor` and `amount`; every signer
+    /// must approve the same pair.
     pub fn approve_large_release(
         env: Env,
         bounty_id: u64,
         contributor: Address,
+        amount: i128,
+        approver: Address,
+    ) -> Result<(), Error> {
+        if !env.storage().instance().has(&DataKey::Admin) {
+            return Err(Error::NotInitialized);
+        }
+
+        if amount <= 0 {
+            return Err(Error::InvalidAmount);
+        }
+
+        Self::add_release_approval(
+            &env,
+            bounty_id,
+            contributor,
+            amount,
+            Vec::new(&env),
+            approver,
+        )
+    }
+
+    /// Approve a large split release (requires multisig)
+    ///
+    /// Approvals are bound to the whole set of `shares` and to `amount`, so the
+    /// recipients and their basis points cannot change after signing.
+    pub fn approve_split_release(
+        env: Env,
+        bounty_id: u64,
+        shares: Vec<SplitShare>,
+        amount: i128,
         approver: Address,
     ) -> Result<(), Error> {
         if !env.storage().instance().has(&DataKey::Admin) {
             return Err(Error::NotInitialized);
         }
 
+        if amount <= 0 {
+            return Err(Error::InvalidAmount);
+        }
+        Self::split_amounts(&env, &shares, amount)?;
+
+        let lead = shares.get(0).unwrap().recipient;
+        Self::add_release_approval(&env, bounty_id, lead, amount, shares, approver)
+    }
+
+    fn add_release_approval(
+        env: &Env,
+        bounty_id: u64,
+        contributor: Address,
+        amount: i128,
+        shares: Vec<SplitShare>,
+        approver: Address,
+    ) -> Result<(), Error> {
         let multisig_config: MultisigConfig = Self::get_multisig_config(env.clone());
 
         let mut is_signer = false;
@@ -798,9 +1137,19 @@ impl BountyEscrowContract {
             .unwrap_or(ReleaseApproval {
                 bounty_id,
                 contributor: contributor.clone(),
-                approvals: vec![&env],
+                amount,
+                shares: shares.clone(),
+                approvals: vec![env],
             });
 
+        // Approvals are bound to a single contributor or split; a different payee needs a fresh set
+        if approval.contributor != contributor || approval.shares != shares {
+            return Err(Error::ApprovalContributorMismatch);
+        }
+        if approval.amount != amount {
+            return Err(Error::ApprovalAmountMismatch);
+        }
+
         for existing in approval.approvals.iter() {
             if existing == approver {
                 return Ok(());
@@ -811,10 +1160,55 @@ impl BountyEscrowContract {
         env.storage().persistent().set(&approval_key, &approval);
 
         events::emit_approval_added(
-            &env,
+            env,
             events::ApprovalAdded {
                 bounty_id,
                 contributor: contributor.clone(),
+                amount,
+                approver,
+                timestamp: env.ledger().timestamp(),
+            },
+        );
+
+        Ok(())
+    }
+
+    /// Withdraw a signer's approval for a large release before it executes.
+    pub fn revoke_release_approval(
+        env: Env,
+        bounty_id: u64,
+        approver: Address,
+    ) -> Result<(), Error> {
+        if !env.storage().instance().has(&DataKey::Admin) {
+            return Err(Error::NotInitialized);
+        }
+
+        approver.require_auth();
+
+        let approval_key = DataKey::ReleaseApproval(bounty_id);
+        let mut approval: ReleaseApproval = env
+            .storage()
+            .persistent()
+            .get(&approval_key)
+            .ok_or(Error::BountyNotFound)?;
+
+        let index = approval
+            .approvals
+            .first_index_of(&approver)
+            .ok_or(Error::Unauthorized)?;
+        approval.approvals.remove(index);
+
+        if approval.approvals.is_empty() {
+            env.storage().persistent().remove(&approval_key);
+        } else {
+            env.storage().persistent().set(&approval_key, &approval);
+        }
+
+        events::emit_approval_revoked(
+            &env,
+            events::ApprovalRevoked {
+                bounty_id,
+                contributor: approval.contributor,
                 approver,
                 timestamp: env.ledger().timestamp(),
             },
@@ -823,13 +1217,137 @@ impl BountyEscrowContract {
         Ok(())
     }
 
-    /// Lock funds for a specific bounty.
+    /// View: get the collected approvals for a large release, if any.
+    pub fn get_release_approval(env: Env, bounty_id: u64) -> Option<ReleaseApproval> {
+        env.storage()
+            .persistent()
+            .get(&DataKey::ReleaseApproval(bounty_id))
+    }
+
+    /// Amount already paid out of an escrow by earlier releases.
+    ///
+    /// For milestone bounties this is the sum of released milestones; otherwise
+    /// it is what partial releases have taken from the locked amount.
+    fn released_total(escrow: &Escrow) -> i128 {
+        if escrow.milestones.is_empty() {
+            return escrow.amount - escrow.remaining_amount;
+        }
+        let mut released: i128 = 0;
+        for milestone in escrow.milestones.iter() {
+            if milestone.status == MilestoneStatus::Released {
+                released += milestone.amount;
+            }
+        }
+        released
+    }
+
+    /// Enforce multisig approval for releases above the configured threshold.
+    ///
+    /// The threshold applies to the running total released from the escrow, so
+    /// a large payout cannot be slipped through as several small slices. Once
+    /// `released_before + amount` exceeds `threshold_amount`, the release needs
+    /// `required_signatures` approvals from current signers, bound to
+    /// `contributor` and to exactly `amount`. The approval record is consumed
+    /// so it cannot be reused.
+    fn consume_release_approval(
+        env: &Env,
+        bounty_id: u64,
+        contributor: &Address,
+        amount: i128,
+        released_before: i128,
+    ) -> Result<(), Error> {
+        Self::consume_approval(
+            env,
+            bounty_id,
+            contributor,
+            &Vec::new(env),
+            amount,
+            released_before,
+        )
+    }
+
+    /// Like `consume_release_approval`, but the approval must cover exactly
+    /// these `shares`.
+    fn consume_split_release_approval(
+        env: &Env,
+        bounty_id: u64,
+        shares: &Vec<SplitShare>,
+        amount: i128,
+        released_before: i128,
+    ) -> Result<(), Error> {
+        let lead = shares.get(0).unwrap().recipient;
+        Self::consume_approval(env, bounty_id, &lead, shares, amount, released_before)
+    }
+
+    fn consume_approval(
+        env: &Env,
+        bounty_id: u64,
+        contributor: &Address,
+        shares: &Vec<SplitShare>,
+        amount: i128,
+        released_before: i128,
+    ) -> Result<(), Error> {
+        let multisig_config = Self::get_multisig_config(env.clone());
+        if released_before.saturating_add(amount) <= multisig_config.threshold_amount
+            || multisig_config.required_signatures == 0
+        {
+            return Ok(());
+        }
+
+        let approval_key = DataKey::ReleaseApproval(bounty_id);
+        let approval: ReleaseApproval = env
+            .storage()
+            .persistent()
+            .get(&approval_key)
+            .ok_or(Error::InsufficientApprovals)?;
+
+        if approval.contributor != *contributor || approval.shares != *shares {
+            return Err(Error::ApprovalContributorMismatch);
+        }
+        if approval.amount != amount {
+            return Err(Error::ApprovalAmountMismatch);
+        }
+
+        // Only count approvers who are still configured signers
+        let mut valid_approvals = 0u32;
+        for approver in approval.approvals.iter() {
+            if multisig_config.signers.contains(&approver) {
+                valid_approvals += 1;
+            }
+        }
+        if valid_approvals < multisig_config.required_signatures {
+            return Err(Error::InsufficientApprovals);
+        }
+
+        env.storage().persistent().remove(&approval_key);
+        Ok(())
+    }
+
+    /// Lock funds for a specific bounty in the default token configured at `init`.
     pub fn lock_funds(
         env: Env,
         depositor: Address,
         bounty_id: u64,
         amount: i128,
         deadline: u64,
+    ) -> Result<(), Error> {
+        let token: Address = env
+            .storage()
+            .instance()
+            .get(&DataKey::Token)
+            .ok_or(Error::NotInitialized)?;
+        Self::lock_funds_with_token(env, depositor, bounty_id, amount, deadline, token)
+    }
+
+    /// Lock funds for a specific bounty in any allowlisted token.
+    /// The token is recorded on the escrow and used for every later payout or refund.
+    pub fn lock_funds_with_token(
+        env: Env,
+        depositor: Address,
+        bounty_id: u64,
+        amount: i128,
+        deadline: u64,
+        token: Address,
     ) -> Result<(), Error> {
         // Apply rate limiting
         anti_abuse::check_rate_limit(&env, depositor.clone());
@@ -852,6 +1370,10 @@ impl BountyEscrowContract {
             return Err(Error::BountyExists);
         }
 
+        if !Self::is_token_allowed(env.clone(), token.clone()) {
+            return Err(Error::TokenNotAllowed);
+        }
+
         // Enforce min/max amount policy if one has been configured (Issue #62).
         // When no policy is set this block is skipped entirely, preserving
         // backward-compatible behaviour for callers that never call set_amount_policy.
@@ -868,19 +1390,24 @@ impl BountyEscrowContract {
             }
         }
 
-        let token_addr: Address = env.storage().instance().get(&DataKey::Token).unwrap();
-        let client = token::Client::new(&env, &token_addr);
+        let client = token::Client::new(&env, &token);
 
         // Transfer funds from depositor to contract
         client.transfer(&depositor, &env.current_contract_address(), &amount);
 
+        // Route the lock fee (if any); the escrow only holds the net amount
+        let net_amount =
+            Self::collect_fee(&env, &client, bounty_id, amount, FeeOperationType::Lock);
+
         let escrow = Escrow {
             depositor: depositor.clone(),
-            amount,
+            token: token.clone(),
+            amount: net_amount,
             status: EscrowStatus::Locked,
             deadline,
             refund_history: vec![&env],
-            remaining_amount: amount,
+            remaining_amount: net_amount,
+            milestones: vec![&env],
         };
 
         // Extend the TTL of the storage entry to ensure it lives long enough
@@ -916,8 +1443,9 @@ impl BountyEscrowContract {
             FundsLocked {
                 version: EVENT_VERSION_V2,
                 bounty_id,
-                amount,
+                amount: net_amount,
                 depositor: depositor.clone(),
+                token,
                 deadline,
             },
         );
@@ -925,17 +1453,384 @@ impl BountyEscrowContract {
         Ok(())
     }
 
-    /// Release funds to the contributor.
-    /// Only the admin (backend) can authorize this.
-    pub fn release_funds(env: Env, bounty_id: u64, contributor: Address) -> Result<(), Error> {
-        if Self::check_paused(&env, symbol_short!("release")) {
-            return Err(Error::FundsPaused);
-        }
-        let _start = env.ledger().timestamp();
-
-        // Ensure contract is initialized
-        if env.storage().instance().has(&DataKey::ReentrancyGuard) {
-            panic!("Reentrancy detected");
+    /// Lock funds for a bounty that pays out in milestones, in the default token.
+    ///
+    /// The milestone amounts must add up to `amount` exactly. If a lock fee is
+    /// configured, each milestone is scaled down pro rata to the net amount, with
+    /// the rounding remainder assigned to the last milestone so the stages always
+    /// sum to the escrowed amount. Milestones are paid with `release_milestone`
+    /// and refunded with `refund_milestone`.
+    pub fn lock_funds_with_milestones(
+        env: Env,
+        depositor: Address,
+        bounty_id: u64,
+        amount: i128,
+        deadline: u64,
+        milestones: Vec<MilestoneSpec>,
+    ) -> Result<(), Error> {
+        if milestones.is_empty() || milestones.len() > MAX_MILESTONES {
+            return Err(Error::InvalidBatchSize);
+        }
+
+        let mut total: i128 = 0;
+        for spec in milestones.iter() {
+            if spec.amount <= 0 {
+                return Err(Error::InvalidAmount);
+            }
+            total = total.checked_add(spec.amount).ok_or(Error::InvalidAmount)?;
+        }
+        if total != amount {
+            return Err(Error::MilestoneAmountMismatch);
+        }
+
+        Self::lock_funds(env.clone(), depositor, bounty_id, amount, deadline)?;
+
+        let mut escrow: Escrow = env
+            .storage()
+            .persistent()
+            .get(&DataKey::Escrow(bounty_id))
+            .unwrap();
+
+        let last = milestones.len() - 1;
+        let mut allocated: i128 = 0;
+        for (index, spec) in milestones.iter().enumerate() {
+            let milestone_amount = if index as u32 == last {
+                escrow.amount - allocated
+            } else {
+                spec.amount
+                    .checked_mul(escrow.amount)
+                    .and_then(|x| x.checked_div(amount))
+                    .ok_or(Error::InvalidAmount)?
+            };
+            allocated += milestone_amount;
+            escrow.milestones.push_back(Milestone {
+                label_hash: spec.label_hash,
+                amount: milestone_amount,
+                deadline: spec.deadline,
+                status: MilestoneStatus::Pending,
+            });
+        }
+
+        env.storage()
+            .persistent()
+            .set(&DataKey::Escrow(bounty_id), &escrow);
+
+        Ok(())
+    }
+
+    /// Add funds to an existing bounty on behalf of any funder.
+    ///
+    /// Increases both `amount` and `remaining_amount` by the top-up net of the
+    /// lock fee. Each funder's contribution is recorded so that a deadline
+    /// refund is returned pro rata instead of entirely to the depositor.
+    pub fn top_up(env: Env, funder: Address, bounty_id: u64, amount: i128) -> Result<(), Error> {
+        anti_abuse::check_rate_limit(&env, funder.clone());
+
+        if Self::check_paused(&env, symbol_short!("lock")) {
+            return Err(Error::FundsPaused);
+        }
+
+        funder.require_auth();
+
+        if !env.storage().instance().has(&DataKey::Admin) {
+            return Err(Error::NotInitialized);
+        }
+
+        let mut escrow: Escrow = env
+            .storage()
+            .persistent()
+            .get(&DataKey::Escrow(bounty_id))
+            .ok_or(Error::BountyNotFound)?;
+
+        if escrow.status == EscrowStatus::Disputed {
+            return Err(Error::EscrowDisputed);
+        }
+
+        if escrow.status != EscrowStatus::Locked {
+            return Err(Error::FundsNotLocked);
+        }
+
+        // Milestone amounts must keep adding up to the escrowed total
+        if !escrow.milestones.is_empty() {
+            return Err(Error::MilestoneAmountMismatch);
+        }
+
+        if amount <= 0 {
+            return Err(Error::InvalidAmount);
+        }
+
+        // The policy maximum caps the bounty's total, including top-ups
+        if let Some((_, max_amount)) = env
+            .storage()
+            .instance()
+            .get::<DataKey, (i128, i128)>(&DataKey::AmountPolicy)
+        {
+            if escrow.amount.saturating_add(amount) > max_amount {
+                return Err(Error::AmountAboveMaximum);
+            }
+        }
+
+        let client = token::Client::new(&env, &escrow.token);
+        client.transfer(&funder, &env.current_contract_address(), &amount);
+        let net_amount =
+            Self::collect_fee(&env, &client, bounty_id, amount, FeeOperationType::Lock);
+
+        let contributions_key = DataKey::Contributions(bounty_id);
+        let mut contributions: Vec<Contribution> = env
+            .storage()
+            .persistent()
+            .get(&contributions_key)
+            .unwrap_or(vec![
+                &env,
+                Contribution {
+                    funder: escrow.depositor.clone(),
+                    amount: escrow.amount,
+                },
+            ]);
+        match contributions.iter().position(|c| c.funder == funder) {
+            Some(index) => {
+                let mut contribution = contributions.get(index as u32).unwrap();
+                contribution.amount += net_amount;
+                contributions.set(index as u32, contribution);
+            }
+            None => contributions.push_back(Contribution {
+                funder: funder.clone(),
+                amount: net_amount,
+            }),
+        }
+        env.storage()
+            .persistent()
+            .set(&contributions_key, &contributions);
+
+        escrow.amount += net_amount;
+        escrow.remaining_amount += net_amount;
+        env.storage()
+            .persistent()
+            .set(&DataKey::Escrow(bounty_id), &escrow);
+
+        events::emit_funds_topped_up(
+            &env,
+            events::FundsToppedUp {
+                version: EVENT_VERSION_V2,
+                bounty_id,
+                funder,
+                amount: net_amount,
+                new_total: escrow.amount,
+                timestamp: env.ledger().timestamp(),
+            },
+        );
+
+        Ok(())
+    }
+
+    /// View: get each funder's contribution to a bounty.
+    /// Bounties that were never topped up report the depositor as the sole funder.
+    pub fn get_contributions(env: Env, bounty_id: u64) -> Result<Vec<Contribution>, Error> {
+        let escrow: Escrow = env
+            .storage()
+            .persistent()
+            .get(&DataKey::Escrow(bounty_id))
+            .ok_or(Error::BountyNotFound)?;
+        Ok(env
+            .storage()
+            .persistent()
+            .get(&DataKey::Contributions(bounty_id))
+            .unwrap_or(vec![
+                &env,
+                Contribution {
+                    funder: escrow.depositor,
+                    amount: escrow.amount,
+                },
+            ]))
+    }
+
+    /// Amend a bounty's deadline and/or amount (depositor only).
+    ///
+    /// - The deadline may move in either direction but never before `now`.
+    /// - Raising the amount pulls the difference from the depositor (net of the
+    ///   lock fee); lowering it returns the difference to the depositor as a
+    ///   partial refund, recorded in `refund_history`.
+    /// - Raising is blocked while locks are paused, lowering while refunds are.
+    /// - The new amount must satisfy the `AmountPolicy` bounds and cannot drop
+    ///   below what is owed to pending claims.
+    /// - Once a contributor has been authorized to claim, the admin must co-sign.
+    pub fn amend_escrow(
+        env: Env,
+        bounty_id: u64,
+        new_deadline: Option<u64>,
+        new_amount: Option<i128>,
+    ) -> Result<(), Error> {
+        if !env.storage().instance().has(&DataKey::Admin) {
+            return Err(Error::NotInitialized);
+        }
+
+        let mut escrow: Escrow = env
+            .storage()
+            .persistent()
+            .get(&DataKey::Escrow(bounty_id))
+            .ok_or(Error::BountyNotFound)?;
+
+        escrow.depositor.require_auth();
+
+        if escrow.status == EscrowStatus::Disputed {
+            return Err(Error::EscrowDisputed);
+        }
+
+        if escrow.status != EscrowStatus::Locked {
+            return Err(Error::FundsNotLocked);
+        }
+
+        // Funds already promised to a contributor
+        let mut claimable: i128 = 0;
+        if let Some(claim) = env
+            .storage()
+            .persistent()
+            .get::<DataKey, ClaimRecord>(&DataKey::PendingClaim(bounty_id))
+        {
+            if !claim.claimed {
+                claimable += claim.amount;
+            }
+        }
+        if let Some(claims) = env
+            .storage()
+            .persistent()
+            .get::<DataKey, Vec<ClaimRecord>>(&DataKey::SplitClaims(bounty_id))
+        {
+            for claim in claims.iter() {
+                if !claim.claimed {
+                    claimable += claim.amount;
+                }
+            }
+        }
+
+        let admin_cosigned = claimable > 0;
+        if admin_cosigned {
+            let admin: Address = env.storage().instance().get(&DataKey::Admin).unwrap();
+            admin.require_auth();
+        }
+
+        let now = env.ledger().timestamp();
+        let old_deadline = escrow.deadline;
+        if let Some(deadline) = new_deadline {
+            if deadline < now {
+                return Err(Error::InvalidDeadline);
+            }
+            escrow.deadline = deadline;
+        }
+
+        let old_amount = escrow.amount;
+        if let Some(amount) = new_amount {
+            if !escrow.milestones.is_empty() {
+                return Err(Error::MilestoneAmountMismatch);
+            }
+            if amount <= 0 {
+                return Err(Error::InvalidAmount);
+            }
+            if let Some((min_amount, max_amount)) = env
+                .storage()
+                .instance()
+                .get::<DataKey, (i128, i128)>(&DataKey::AmountPolicy)
+            {
+                if amount < min_amount {
+                    return Err(Error::AmountBelowMinimum);
+                }
+                if amount > max_amount {
+                    return Err(Error::AmountAboveMaximum);
+                }
+            }
+
+            let client = token::Client::new(&env, &escrow.token);
+            let delta = if amount > escrow.amount {
+                if Self::check_paused(&env, symbol_short!("lock")) {
+                    return Err(Error::FundsPaused);
+                }
+                let increase = amount - escrow.amount;
+                client.transfer(
+                    &escrow.depositor,
+                    &env.current_contract_address(),
+                    &increase,
+                );
+                let net_increase =
+                    Self::collect_fee(&env, &client, bounty_id, increase, FeeOperationType::Lock);
+                escrow.remaining_amount += net_increase;
+                net_increase
+            } else {
+                if Self::check_paused(&env, symbol_short!("refund")) {
+                    return Err(Error::FundsPaused);
+                }
+                let decrease = escrow.amount - amount;
+                if escrow.remaining_amount - decrease < claimable {
+                    return Err(Error::InvalidAmount);
+                }
+                if decrease > 0 {
+                    let depositor = escrow.depositor.clone();
+                    Self::pay_refund(
+                        &env,
+                        &client,
+                        bounty_id,
+                        &mut escrow,
+                        decrease,
+                        depositor,
+                        RefundMode::Partial,
+                    );
+                }
+                -decrease
+            };
+
+            // Keep the depositor's recorded contribution in step for pro-rata refunds
+            let contributions_key = DataKey::Contributions(bounty_id);
+            if let Some(mut contributions) = env
+                .storage()
+                .persistent()
+                .get::<DataKey, Vec<Contribution>>(&contributions_key)
+            {
+                let mut contribution = contributions.get(0).unwrap();
+                contribution.amount += delta;
+                if contribution.amount < 0 {
+                    return Err(Error::InvalidAmount);
+                }
+                contributions.set(0, contribution);
+                env.storage()
+                    .persistent()
+                    .set(&contributions_key, &contributions);
+            }
+
+            escrow.amount += delta;
+        }
+
+        env.storage()
+            .persistent()
+            .set(&DataKey::Escrow(bounty_id), &escrow);
+
+        events::emit_escrow_amended(
+            &env,
+            events::EscrowAmended {
+                version: EVENT_VERSION_V2,
+                bounty_id,
+                old_amount,
+                new_amount: escrow.amount,
+                old_deadline,
+                new_deadline: escrow.deadline,
+                depositor: escrow.depositor,
+                admin_cosigned,
+                timestamp: now,
+            },
+        );
+
+        Ok(())
+    }
+
+    /// Release funds to the contributor.
+    /// Only the admin (backend) can authorize this.
+    pub fn release_funds(env: Env, bounty_id: u64, contributor: Address) -> Result<(), Error> {
+        if Self::check_paused(&env, symbol_short!("release")) {
+            return Err(Error::FundsPaused);
+        }
+        let _start = env.ledger().timestamp();
+
+        // Ensure contract is initialized
+        if env.storage().instance().has(&DataKey::ReentrancyGuard) {
+            panic!("Reentrancy detected");
         }
         env.storage()
             .instance()
@@ -944,9 +1839,814 @@ impl BountyEscrowContract {
             return Err(Error::NotInitialized);
         }
 
-        let admin: Address = env.storage().instance().get(&DataKey::Admin).unwrap();
-        admin.require_auth();
-
+        let admin: Address = env.storage().instance().get(&DataKey::Admin).unwrap();
+        admin.require_auth();
+
+        if !env.storage().persistent().has(&DataKey::Escrow(bounty_id)) {
+            return Err(Error::BountyNotFound);
+        }
+
+        let mut escrow: Escrow = env
+            .storage()
+            .persistent()
+            .get(&DataKey::Escrow(bounty_id))
+            .unwrap();
+
+        if escrow.status == EscrowStatus::Disputed {
+            return Err(Error::EscrowDisputed);
+        }
+
+        if !escrow.milestones.is_empty() {
+            return Err(Error::MilestoneReleaseRequired);
+        }
+
+        if escrow.status != EscrowStatus::Locked {
+            return Err(Error::FundsNotLocked);
+        }
+
+        Self::consume_release_approval(
+            &env,
+            bounty_id,
+            &contributor,
+            escrow.remaining_amount,
+            Self::released_total(&escrow),
+        )?;
+
+        let client = token::Client::new(&env, &escrow.token);
+
+        // Route the release fee (if any), then pay the contributor the net amount
+        let net_amount = Self::collect_fee(
+            &env,
+            &client,
+            bounty_id,
+            escrow.remaining_amount,
+            FeeOperationType::Release,
+        );
+        client.transfer(&env.current_contract_address(), &contributor, &net_amount);
+
+        escrow.remaining_amount = 0;
+        escrow.status = EscrowStatus::Released;
+        env.storage()
+            .persistent()
+            .set(&DataKey::Escrow(bounty_id), &escrow);
+
+        emit_funds_released(
+            &env,
+            FundsReleased {
+                version: EVENT_VERSION_V2,
+                bounty_id,
+                amount: net_amount,
+                recipient: contributor.clone(),
+                timestamp: env.ledger().timestamp(),
+            },
+        );
+
+        // Clear reentrancy guard
+        env.storage().instance().remove(&DataKey::ReentrancyGuard);
+
+        Ok(())
+    }
+
+    /// Release the remaining funds to several co-contributors at once (admin only).
+    ///
+    /// `shares` are `(recipient, basis_points)` pairs summing to 10,000. The
+    /// release fee is taken once from the total, and the net amount is divided
+    /// by `split_amounts`; rounding dust goes to the first share. Large
+    /// releases need an `approve_split_release` approval for these exact shares.
+    /// Emits one `FundsReleased` per recipient.
+    pub fn release_funds_split(
+        env: Env,
+        bounty_id: u64,
+        shares: Vec<SplitShare>,
+    ) -> Result<(), Error> {
+        if Self::check_paused(&env, symbol_short!("release")) {
+            return Err(Error::FundsPaused);
+        }
+        if !env.storage().instance().has(&DataKey::Admin) {
+            return Err(Error::NotInitialized);
+        }
+
+        let admin: Address = env.storage().instance().get(&DataKey::Admin).unwrap();
+        admin.require_auth();
+
+        let mut escrow: Escrow = env
+            .storage()
+            .persistent()
+            .get(&DataKey::Escrow(bounty_id))
+            .ok_or(Error::BountyNotFound)?;
+
+        if escrow.status == EscrowStatus::Disputed {
+            return Err(Error::EscrowDisputed);
+        }
+
+        if !escrow.milestones.is_empty() {
+            return Err(Error::MilestoneReleaseRequired);
+        }
+
+        if escrow.status != EscrowStatus::Locked {
+            return Err(Error::FundsNotLocked);
+        }
+
+        // Validate the split before any funds move
+        Self::split_amounts(&env, &shares, escrow.remaining_amount)?;
+
+        Self::consume_split_release_approval(
+            &env,
+            bounty_id,
+            &shares,
+            escrow.remaining_amount,
+            Self::released_total(&escrow),
+        )?;
+
+        let client = token::Client::new(&env, &escrow.token);
+        let net_amount = Self::collect_fee(
+            &env,
+            &client,
+            bounty_id,
+            escrow.remaining_amount,
+            FeeOperationType::Release,
+        );
+        let amounts = Self::split_amounts(&env, &shares, net_amount)?;
+
+        let now = env.ledger().timestamp();
+        for (share, amount) in shares.iter().zip(amounts.iter()) {
+            if amount > 0 {
+                client.transfer(&env.current_contract_address(), &share.recipient, &amount);
+            }
+            emit_funds_released(
+                &env,
+                FundsReleased {
+                    version: EVENT_VERSION_V2,
+                    bounty_id,
+                    amount,
+                    recipient: share.recipient,
+                    timestamp: now,
+                },
+            );
+        }
+
+        escrow.remaining_amount = 0;
+        escrow.status = EscrowStatus::Released;
+        env.storage()
+            .persistent()
+            .set(&DataKey::Escrow(bounty_id), &escrow);
+
+        Ok(())
+    }
+
+    /// Set the claim window duration (admin only).
+    /// claim_window: seconds beneficiary has to claim after release is authorized.
+    pub fn set_claim_window(env: Env, claim_window: u64) -> Result<(), Error> {
+        if !env.storage().instance().has(&DataKey::Admin) {
+            return Err(Error::NotInitialized);
+        }
+        let admin: Address = env.storage().instance().get(&DataKey::Admin).unwrap();
+        admin.require_auth();
+        env.storage()
+            .instance()
+            .set(&DataKey::ClaimWindow, &claim_window);
+        Ok(())
+    }
+
+    /// Authorize a release as a pending claim instead of immediate transfer.
+    /// Admin calls this instead of release_funds when claim period is active.
+    /// Beneficiary must call claim() within the window to receive funds.
+    pub fn authorize_claim(env: Env, bounty_id: u64, recipient: Address) -> Result<(), Error> {
+        if Self::check_paused(&env, symbol_short!("release")) {
+            return Err(Error::FundsPaused);
+        }
+        if !env.storage().instance().has(&DataKey::Admin) {
+            return Err(Error::NotInitialized);
+        }
+        let admin: Address = env.storage().instance().get(&DataKey::Admin).unwrap();
+        admin.require_auth();
+
+        if !env.storage().persistent().has(&DataKey::Escrow(bounty_id)) {
+            return Err(Error::BountyNotFound);
+        }
+
+        let escrow: Escrow = env
+            .storage()
+            .persistent()
+            .get(&DataKey::Escrow(bounty_id))
+            .unwrap();
+
+        if escrow.status == EscrowStatus::Disputed {
+            return Err(Error::EscrowDisputed);
+        }
+
+        if !escrow.milestones.is_empty() {
+            return Err(Error::MilestoneReleaseRequired);
+        }
+
+        if escrow.status != EscrowStatus::Locked {
+            return Err(Error::FundsNotLocked);
+        }
+
+        Self::consume_release_approval(
+            &env,
+            bounty_id,
+            &recipient,
+            escrow.remaining_amount,
+            Self::released_total(&escrow),
+        )?;
+
+        let now = env.ledger().timestamp();
+        let claim_window: u64 = env
+            .storage()
+            .instance()
+            .get(&DataKey::ClaimWindow)
+            .unwrap_or(0);
+        let claim = ClaimRecord {
+            bounty_id,
+            recipient: recipient.clone(),
+            amount: escrow.remaining_amount,
+            expires_at: now.saturating_add(claim_window),
+            claimed: false,
+        };
+
+        env.storage()
+            .persistent()
+            .set(&DataKey::PendingClaim(bounty_id), &claim);
+
+        env.events().publish(
+            (symbol_short!("claim"), symbol_short!("created")),
+            ClaimCreated {
+                bounty_id,
+                recipient,
+                amount: claim.amount,
+                expires_at: claim.expires_at,
+            },
+        );
+        Ok(())
+    }
+
+    /// Beneficiary calls this to claim their authorized funds within the window.
+    pub fn claim(env: Env, bounty_id: u64) -> Result<(), Error> {
+        if Self::check_paused(&env, symbol_short!("release")) {
+            return Err(Error::FundsPaused);
+        }
+        if !env
+            .storage()
+            .persistent()
+            .has(&DataKey::PendingClaim(bounty_id))
+        {
+            return Err(Error::BountyNotFound);
+        }
+        let mut claim: ClaimRecord = env
+            .storage()
+            .persistent()
+            .get(&DataKey::PendingClaim(bounty_id))
+            .unwrap();
+
+        claim.recipient.require_auth();
+
+        let now = env.ledger().timestamp();
+        if now > claim.expires_at {
+            return Err(Error::DeadlineNotPassed); // reuse or add ClaimExpired error
+        }
+        if claim.claimed {
+            return Err(Error::FundsNotLocked);
+        }
+
+        let mut escrow: Escrow = env
+            .storage()
+            .persistent()
+            .get(&DataKey::Escrow(bounty_id))
+            .unwrap();
+
+        if escrow.status == EscrowStatus::Disputed {
+            return Err(Error::EscrowDisputed);
+        }
+        if escrow.status != EscrowStatus::Locked {
+            return Err(Error::FundsNotLocked);
+        }
+        if claim.amount > escrow.remaining_amount {
+            return Err(Error::InsufficientFunds);
+        }
+
+        let client = token::Client::new(&env, &escrow.token);
+        let net_amount = Self::collect_fee(
+            &env,
+            &client,
+            bounty_id,
+            claim.amount,
+            FeeOperationType::Release,
+        );
+        client.transfer(
+            &env.current_contract_address(),
+            &claim.recipient,
+            &net_amount,
+        );
+
+        // Update escrow status
+        escrow.remaining_amount -= claim.amount;
+        escrow.status = EscrowStatus::Released;
+        env.storage()
+            .persistent()
+            .set(&DataKey::Escrow(bounty_id), &escrow);
+
+        claim.claimed = true;
+        env.storage()
+            .persistent()
+            .set(&DataKey::PendingClaim(bounty_id), &claim);
+
+        env.events().publish(
+            (symbol_short!("claim"), symbol_short!("done")),
+            ClaimExecuted {
+                bounty_id,
+                recipient: claim.recipient.clone(),
+                amount: net_amount,
+                claimed_at: now,
+            },
+        );
+        Ok(())
+    }
+
+    /// Authorize a split release as one pending claim per co-contributor.
+    ///
+    /// Each recipient calls `claim_share` within the claim window to receive
+    /// their portion of `remaining_amount`, divided as in `release_funds_split`.
+    /// The escrow moves to `PendingSplit`, which blocks other releases, refunds
+    /// and a second authorization until the shares are claimed or cancelled.
+    pub fn authorize_split_claim(
+        env: Env,
+        bounty_id: u64,
+        shares: Vec<SplitShare>,
+    ) -> Result<(), Error> {
+        if Self::check_paused(&env, symbol_short!("release")) {
+            return Err(Error::FundsPaused);
+        }
+        if !env.storage().instance().has(&DataKey::Admin) {
+            return Err(Error::NotInitialized);
+        }
+        let admin: Address = env.storage().instance().get(&DataKey::Admin).unwrap();
+        admin.require_auth();
+
+        let mut escrow: Escrow = env
+            .storage()
+            .persistent()
+            .get(&DataKey::Escrow(bounty_id))
+            .ok_or(Error::BountyNotFound)?;
+
+        if escrow.status == EscrowStatus::Disputed {
+            return Err(Error::EscrowDisputed);
+        }
+
+        if !escrow.milestones.is_empty() {
+            return Err(Error::MilestoneReleaseRequired);
+        }
+
+        if escrow.status != EscrowStatus::Locked {
+            return Err(Error::FundsNotLocked);
+        }
+
+        let amounts = Self::split_amounts(&env, &shares, escrow.remaining_amount)?;
+
+        Self::consume_split_release_approval(
+            &env,
+            bounty_id,
+            &shares,
+            escrow.remaining_amount,
+            Self::released_total(&escrow),
+        )?;
+
+        let now = env.ledger().timestamp();
+        let claim_window: u64 = env
+            .storage()
+            .instance()
+            .get(&DataKey::ClaimWindow)
+            .unwrap_or(0);
+        let expires_at = now.saturating_add(claim_window);
+
+        let mut claims: Vec<ClaimRecord> = Vec::new(&env);
+        for (share, amount) in shares.iter().zip(amounts.iter()) {
+            claims.push_back(ClaimRecord {
+                bounty_id,
+                recipient: share.recipient.clone(),
+                amount,
+                expires_at,
+                claimed: false,
+            });
+
+            env.events().publish(
+                (symbol_short!("claim"), symbol_short!("created")),
+                ClaimCreated {
+                    bounty_id,
+                    recipient: share.recipient,
+                    amount,
+                    expires_at,
+                },
+            );
+        }
+
+        env.storage()
+            .persistent()
+            .set(&DataKey::SplitClaims(bounty_id), &claims);
+
+        escrow.status = EscrowStatus::PendingSplit;
+        env.storage()
+            .persistent()
+            .set(&DataKey::Escrow(bounty_id), &escrow);
+        Ok(())
+    }
+
+    /// Co-contributor calls this to claim their share of a split release.
+    /// The escrow becomes Released once every share has been claimed.
+    pub fn claim_share(env: Env, bounty_id: u64, recipient: Address) -> Result<(), Error> {
+        if Self::check_paused(&env, symbol_short!("release")) {
+            return Err(Error::FundsPaused);
+        }
+
+        let mut claims: Vec<ClaimRecord> = env
+            .storage()
+            .persistent()
+            .get(&DataKey::SplitClaims(bounty_id))
+            .ok_or(Error::BountyNotFound)?;
+
+        let index = claims
+            .iter()
+            .position(|claim| claim.recipient == recipient)
+            .ok_or(Error::Unauthorized)? as u32;
+        let mut claim = claims.get(index).unwrap();
+
+        recipient.require_auth();
+
+        let now = env.ledger().timestamp();
+        if now > claim.expires_at {
+            return Err(Error::DeadlineNotPassed);
+        }
+        if claim.claimed {
+            return Err(Error::FundsNotLocked);
+        }
+
+        let mut escrow: Escrow = env
+            .storage()
+            .persistent()
+            .get(&DataKey::Escrow(bounty_id))
+            .unwrap();
+
+        if escrow.status == EscrowStatus::Disputed {
+            return Err(Error::EscrowDisputed);
+        }
+
+        if escrow.status != EscrowStatus::PendingSplit {
+            return Err(Error::FundsNotLocked);
+        }
+
+        let client = token::Client::new(&env, &escrow.token);
+        let net_amount = Self::collect_fee(
+            &env,
+            &client,
+            bounty_id,
+            claim.amount,
+            FeeOperationType::Release,
+        );
+        client.transfer(&env.current_contract_address(), &recipient, &net_amount);
+
+        escrow.remaining_amount -= claim.amount;
+        if escrow.remaining_amount == 0 {
+            escrow.status = EscrowStatus::Released;
+        }
+        env.storage()
+            .persistent()
+            .set(&DataKey::Escrow(bounty_id), &escrow);
+
+        claim.claimed = true;
+        claims.set(index, claim);
+        env.storage()
+            .persistent()
+            .set(&DataKey::SplitClaims(bounty_id), &claims);
+
+        env.events().publish(
+            (symbol_short!("claim"), symbol_short!("done")),
+            ClaimExecuted {
+                bounty_id,
+                recipient: recipient.clone(),
+                amount: net_amount,
+                claimed_at: now,
+            },
+        );
+        emit_funds_released(
+            &env,
+            FundsReleased {
+                version: EVENT_VERSION_V2,
+                bounty_id,
+                amount: net_amount,
+                recipient,
+                timestamp: now,
+            },
+        );
+        Ok(())
+    }
+
+    /// Admin can cancel an expired or unwanted pending claim, returning escrow to Locked.
+    /// Not while a dispute is open.
+    pub fn cancel_pending_claim(env: Env, bounty_id: u64) -> Result<(), Error> {
+        if !env.storage().instance().has(&DataKey::Admin) {
+            return Err(Error::NotInitialized);
+        }
+        let admin: Address = env.storage().instance().get(&DataKey::Admin).unwrap();
+        admin.require_auth();
+
+        if !env
+            .storage()
+            .persistent()
+            .has(&DataKey::PendingClaim(bounty_id))
+        {
+            return Err(Error::BountyNotFound);
+        }
+        let claim: ClaimRecord = env
+            .storage()
+            .persistent()
+            .get(&DataKey::PendingClaim(bounty_id))
+            .unwrap();
+
+        if claim.claimed {
+            return Err(Error::FundsNotLocked);
+        }
+
+        // The arbiter may still award the claim to its recipient
+        let escrow: Escrow = env
+            .storage()
+            .persistent()
+            .get(&DataKey::Escrow(bounty_id))
+            .unwrap();
+        if escrow.status == EscrowStatus::Disputed {
+            return Err(Error::EscrowDisputed);
+        }
+
+        env.storage()
+            .persistent()
+            .remove(&DataKey::PendingClaim(bounty_id));
+
+        env.events().publish(
+            (symbol_short!("claim"), symbol_short!("cancel")),
+            ClaimCancelled {
+                bounty_id,
+                recipient: claim.recipient,
+                amount: claim.amount,
+                cancelled_at: env.ledger().timestamp(),
+                cancelled_by: admin,
+            },
+        );
+        Ok(())
+    }
+
+    /// Admin can cancel the unclaimed shares of a split release.
+    /// Shares already claimed stay paid; the rest remains locked in the escrow,
+    /// which returns to `Locked`.
+    pub fn cancel_split_claims(env: Env, bounty_id: u64) -> Result<(), Error> {
+        if !env.storage().instance().has(&DataKey::Admin) {
+            return Err(Error::NotInitialized);
+        }
+        let admin: Address = env.storage().instance().get(&DataKey::Admin).unwrap();
+        admin.require_auth();
+
+        let claims: Vec<ClaimRecord> = env
+            .storage()
+            .persistent()
+            .get(&DataKey::SplitClaims(bounty_id))
+            .ok_or(Error::BountyNotFound)?;
+
+        let mut escrow: Escrow = env
+            .storage()
+            .persistent()
+            .get(&DataKey::Escrow(bounty_id))
+            .unwrap();
+
+        // The dispute may lapse back to PendingSplit, so its claims must stay
+        if escrow.status == EscrowStatus::Disputed {
+            return Err(Error::EscrowDisputed);
+        }
+
+        env.storage()
+            .persistent()
+            .remove(&DataKey::SplitClaims(bounty_id));
+
+        if escrow.status == EscrowStatus::PendingSplit {
+            escrow.status = EscrowStatus::Locked;
+            env.storage()
+                .persistent()
+                .set(&DataKey::Escrow(bounty_id), &escrow);
+        }
+
+        let now = env.ledger().timestamp();
+        for claim in claims.iter() {
+            if claim.claimed {
+                continue;
+            }
+            env.events().publish(
+                (symbol_short!("claim"), symbol_short!("cancel")),
+                ClaimCancelled {
+                    bounty_id,
+                    recipient: claim.recipient,
+                    amount: claim.amount,
+                    cancelled_at: now,
+                    cancelled_by: admin.clone(),
+                },
+            );
+        }
+        Ok(())
+    }
+
+    /// View: get pending claim for a bounty.
+    pub fn get_pending_claim(env: Env, bounty_id: u64) -> Result<ClaimRecord, Error> {
+        env.storage()
+            .persistent()
+            .get(&DataKey::PendingClaim(bounty_id))
+            .ok_or(Error::BountyNotFound)
+    }
+
+    /// View: get the per-recipient claims of a split release.
+    pub fn get_split_claims(env: Env, bounty_id: u64) -> Result<Vec<ClaimRecord>, Error> {
+        env.storage()
+            .persistent()
+            .get(&DataKey::SplitClaims(bounty_id))
+            .ok_or(Error::BountyNotFound)
+    }
+
+    /// Approve a refund before deadline (admin only).
+    /// This allows early refunds with admin approval.
+    pub fn approve_refund(
+        env: Env,
+        bounty_id: u64,
+        amount: i128,
+        recipient: Address,
+        mode: RefundMode,
+    ) -> Result<(), Error> {
+        if !env.storage().instance().has(&DataKey::Admin) {
+            return Err(Error::NotInitialized);
+        }
+
+        let admin: Address = env.storage().instance().get(&DataKey::Admin).unwrap();
+        admin.require_auth();
+
+        if !env.storage().persistent().has(&DataKey::Escrow(bounty_id)) {
+            return Err(Error::BountyNotFound);
+        }
+
+        let escrow: Escrow = env
+            .storage()
+            .persistent()
+            .get(&DataKey::Escrow(bounty_id))
+            .unwrap();
+
+        if escrow.status == EscrowStatus::Disputed {
+            return Err(Error::EscrowDisputed);
+        }
+
+        if !escrow.milestones.is_empty() {
+            return Err(Error::MilestoneReleaseRequired);
+        }
+
+        if escrow.status != EscrowStatus::Locked && escrow.status != EscrowStatus::PartiallyRefunded
+        {
+            return Err(Error::FundsNotLocked);
+        }
+
+        if amount <= 0 || amount > escrow.remaining_amount {
+            return Err(Error::InvalidAmount);
+        }
+
+        let approval = RefundApproval {
+            bounty_id,
+            amount,
+            recipient: recipient.clone(),
+            mode: mode.clone(),
+            approved_by: admin.clone(),
+            approved_at: env.ledger().timestamp(),
+        };
+
+        env.storage()
+            .persistent()
+            .set(&DataKey::Escrow(bounty_id), &escrow);
+
+        env.storage()
+            .persistent()
+            .set(&DataKey::RefundApproval(bounty_id), &approval);
+
+        Ok(())
+    }
+
+    /// Release a partial amount of the locked funds to the contributor.
+    /// Only the admin (backend) can authorize this.
+    ///
+    /// - `payout_amount` must be > 0 and <= `remaining_amount`.
+    /// - `remaining_amount` is decremented by `payout_amount` after each call.
+    /// - When `remaining_amount` reaches 0 the escrow status is set to Released.
+    /// - The bounty stays Locked while any funds remain unreleased.
+    pub fn partial_release(
+        env: Env,
+        bounty_id: u64,
+        contributor: Address,
+        payout_amount: i128,
+    ) -> Result<(), Error> {
+        if !env.storage().instance().has(&DataKey::Admin) {
+            return Err(Error::NotInitialized);
+        }
+
+        let admin: Address = env.storage().instance().get(&DataKey::Admin).unwrap();
+        admin.require_auth();
+
+        if !env.storage().persistent().has(&DataKey::Escrow(bounty_id)) {
+            return Err(Error::BountyNotFound);
+        }
+
+        let mut escrow: Escrow = env
+            .storage()
+            .persistent()
+            .get(&DataKey::Escrow(bounty_id))
+            .unwrap();
+
+        if escrow.status == EscrowStatus::Disputed {
+            return Err(Error::EscrowDisputed);
+        }
+
+        if !escrow.milestones.is_empty() {
+            return Err(Error::MilestoneReleaseRequired);
+        }
+
+        if escrow.status != EscrowStatus::Locked {
+            return Err(Error::FundsNotLocked);
+        }
+
+        // Guard: zero or negative payout makes no sense and would corrupt state
+        if payout_amount <= 0 {
+            return Err(Error::InvalidAmount);
+        }
+
+        // Guard: prevent overpayment — payout cannot exceed what is still owed
+        if payout_amount > escrow.remaining_amount {
+            return Err(Error::InsufficientFunds);
+        }
+
+        Self::consume_release_approval(
+            &env,
+            bounty_id,
+            &contributor,
+            payout_amount,
+            Self::released_total(&escrow),
+        )?;
+
+        let client = token::Client::new(&env, &escrow.token);
+
+        // Route the release fee (if any), then transfer the net partial amount
+        let net_amount = Self::collect_fee(
+            &env,
+            &client,
+            bounty_id,
+            payout_amount,
+            FeeOperationType::Release,
+        );
+        client.transfer(&env.current_contract_address(), &contributor, &net_amount);
+
+        // Decrement remaining; this is always an exact integer subtraction — no rounding
+        escrow.remaining_amount -= payout_amount;
+
+        // Automatically transition to Released once fully paid out
+        if escrow.remaining_amount == 0 {
+            escrow.status = EscrowStatus::Released;
+        }
+
+        env.storage()
+            .persistent()
+            .set(&DataKey::Escrow(bounty_id), &escrow);
+
+        emit_funds_released(
+            &env,
+            FundsReleased {
+                version: EVENT_VERSION_V2,
+                bounty_id,
+                amount: net_amount,
+                recipient: contributor.clone(),
+                timestamp: env.ledger().timestamp(),
+            },
+        );
+
+        Ok(())
+    }
+
+    /// Refund funds from an escrow.
+    ///
+    /// - With an admin `RefundApproval` on record, the approved amount is paid to
+    ///   the approved recipient, even before the deadline. The approval is consumed.
+    /// - Otherwise the deadline must have passed and the full `remaining_amount`
+    ///   goes back to the depositor.
+    /// - For milestone bounties, only pending stages whose own deadline (or the
+    ///   escrow deadline, if they have none) has passed are refunded; later
+    ///   stages stay locked.
+    ///
+    /// No refund goes out while an authorized claim is unclaimed; cancel it
+    /// with `cancel_pending_claim` first.
+    ///
+    /// Every refund is appended to `refund_history`. The escrow becomes
+    /// `PartiallyRefunded` while funds remain, and `Refunded` once it is empty.
+    pub fn refund(env: Env, bounty_id: u64) -> Result<(), Error> {
+        if Self::check_paused(&env, symbol_short!("refund")) {
+            return Err(Error::FundsPaused);
+        }
+
         if !env.storage().persistent().has(&DataKey::Escrow(bounty_id)) {
             return Err(Error::BountyNotFound);
         }
@@ -957,304 +2657,644 @@ impl BountyEscrowContract {
             .get(&DataKey::Escrow(bounty_id))
             .unwrap();
 
-        if escrow.status != EscrowStatus::Locked {
+        if escrow.status == EscrowStatus::Disputed {
+            return Err(Error::EscrowDisputed);
+        }
+
+        if escrow.status != EscrowStatus::Locked && escrow.status != EscrowStatus::PartiallyRefunded
+        {
             return Err(Error::FundsNotLocked);
         }
 
-        let token_addr: Address = env.storage().instance().get(&DataKey::Token).unwrap();
-        let client = token::Client::new(&env, &token_addr);
+        // Funds promised to a contributor stay put until the claim is
+        // cancelled or taken
+        if Self::has_pending_claim(&env, bounty_id) {
+            return Err(Error::ClaimPending);
+        }
 
-        // Transfer funds to contributor
-        client.transfer(
-            &env.current_contract_address(),
-            &contributor,
-            &escrow.amount,
-        );
+        let now = env.ledger().timestamp();
+        let approval: Option<RefundApproval> = env
+            .storage()
+            .persistent()
+            .get(&DataKey::RefundApproval(bounty_id));
+
+        let client = token::Client::new(&env, &escrow.token);
+        match approval {
+            Some(approval) => {
+                // Funds may have been released since the approval was granted
+                if approval.amount > escrow.remaining_amount {
+                    return Err(Error::InvalidAmount);
+                }
+                env.storage()
+                    .persistent()
+                    .remove(&DataKey::RefundApproval(bounty_id));
+                Self::pay_refund(
+                    &env,
+                    &client,
+                    bounty_id,
+                    &mut escrow,
+                    approval.amount,
+                    approval.recipient,
+                    approval.mode,
+                );
+            }
+            None if !escrow.milestones.is_empty() => {
+                // Each stage is only refundable once its own deadline has passed
+                let mut refund_amount: i128 = 0;
+                for index in 0..escrow.milestones.len() {
+                    let mut milestone = escrow.milestones.get(index).unwrap();
+                    if milestone.status == MilestoneStatus::Pending
+                        && now >= milestone.deadline.unwrap_or(escrow.deadline)
+                    {
+                        refund_amount += milestone.amount;
+                        milestone.status = MilestoneStatus::Refunded;
+                        escrow.milestones.set(index, milestone);
+                    }
+                }
+                if refund_amount == 0 {
+                    return Err(Error::DeadlineNotPassed);
+                }
+                let mode = if refund_amount == escrow.remaining_amount {
+                    RefundMode::Full
+                } else {
+                    RefundMode::Partial
+                };
+                Self::refund_to_funders(&env, &client, bounty_id, &mut escrow, refund_amount, mode);
+            }
+            None => {
+                if now < escrow.deadline {
+                    return Err(Error::DeadlineNotPassed);
+                }
+                // Refund only what is still remaining (partial releases may have already gone out)
+                let refund_amount = escrow.remaining_amount;
+                Self::refund_to_funders(
+                    &env,
+                    &client,
+                    bounty_id,
+                    &mut escrow,
+                    refund_amount,
+                    RefundMode::Full,
+                );
+            }
+        }
 
-        escrow.status = EscrowStatus::Released;
+        escrow.status = if escrow.remaining_amount == 0 {
+            EscrowStatus::Refunded
+        } else {
+            EscrowStatus::PartiallyRefunded
+        };
         env.storage()
             .persistent()
             .set(&DataKey::Escrow(bounty_id), &escrow);
 
-        emit_funds_released(
-            &env,
-            FundsReleased {
+        Ok(())
+    }
+
+    /// Transfer `amount` of the escrow to `recipient`, recording it in the
+    /// refund history and emitting `FundsRefunded`.
+    fn pay_refund(
+        env: &Env,
+        client: &token::Client,
+        bounty_id: u64,
+        escrow: &mut Escrow,
+        amount: i128,
+        recipient: Address,
+        mode: RefundMode,
+    ) {
+        let now = env.ledger().timestamp();
+        client.transfer(&env.current_contract_address(), &recipient, &amount);
+
+        escrow.remaining_amount -= amount;
+        escrow.refund_history.push_back(RefundRecord {
+            amount,
+            recipient: recipient.clone(),
+            timestamp: now,
+            mode,
+        });
+
+        emit_funds_refunded(
+            env,
+            FundsRefunded {
                 version: EVENT_VERSION_V2,
                 bounty_id,
-                amount: escrow.amount,
-                recipient: contributor.clone(),
-                timestamp: env.ledger().timestamp(),
+                amount,
+                refund_to: recipient,
+                timestamp: now,
             },
         );
+    }
 
-        // Clear reentrancy guard
-        env.storage().instance().remove(&DataKey::ReentrancyGuard);
+    /// Refund `amount` to the bounty's funders.
+    ///
+    /// Without top-ups everything goes to the depositor. Otherwise each funder
+    /// receives a pro-rata share of `amount` by contribution, rounded down, with
+    /// the rounding dust going to the original depositor.
+    fn refund_to_funders(
+        env: &Env,
+        client: &token::Client,
+        bounty_id: u64,
+        escrow: &mut Escrow,
+        amount: i128,
+        mode: RefundMode,
+    ) {
+        let contributions: Vec<Contribution> = env
+            .storage()
+            .persistent()
+            .get(&DataKey::Contributions(bounty_id))
+            .unwrap_or(Vec::new(env));
+        if contributions.is_empty() {
+            let depositor = escrow.depositor.clone();
+            Self::pay_refund(env, client, bounty_id, escrow, amount, depositor, mode);
+            return;
+        }
 
-        Ok(())
+        let mut total_contributed: i128 = 0;
+        for contribution in contributions.iter() {
+            total_contributed += contribution.amount;
+        }
+
+        let mut shares: Vec<i128> = Vec::new(env);
+        let mut allocated: i128 = 0;
+        for contribution in contributions.iter() {
+            let share = amount
+                .checked_mul(contribution.amount)
+                .and_then(|x| x.checked_div(total_contributed))
+                .unwrap_or(0);
+            allocated += share;
+            shares.push_back(share);
+        }
+        // The depositor is always the first contributor
+        shares.set(0, shares.get(0).unwrap() + (amount - allocated));
+
+        for (contribution, share) in contributions.iter().zip(shares.iter()) {
+            if share > 0 {
+                Self::pay_refund(
+                    env,
+                    client,
+                    bounty_id,
+                    escrow,
+                    share,
+                    contribution.funder,
+                    mode.clone(),
+                );
+            }
+        }
     }
 
-    /// Set the claim window duration (admin only).
-    /// claim_window: seconds beneficiary has to claim after release is authorized.
-    pub fn set_claim_window(env: Env, claim_window: u64) -> Result<(), Error> {
-        if !env.storage().instance().has(&DataKey::Admin) {
-            return Err(Error::NotInitialized);
+    /// Refund every expired escrow in a page of `EscrowIndex`.
+    ///
+    /// Examines up to `limit` bounties starting at index position `offset`
+    /// (capped at `MAX_REFUND_SWEEP`) and runs the regular deadline `refund`
+    /// on each one that is Locked or PartiallyRefunded with funds left, past
+    /// its deadline, and has no pending claim or refund approval. Like
+    /// `refund`, anyone may call this.
+    pub fn refund_expired(env: Env, offset: u32, limit: u32) -> Result<RefundSweepSummary, Error> {
+        if Self::check_paused(&env, symbol_short!("refund")) {
+            return Err(Error::FundsPaused);
         }
-        let admin: Address = env.storage().instance().get(&DataKey::Admin).unwrap();
-        admin.require_auth();
-        env.storage()
-            .instance()
-            .set(&DataKey::ClaimWindow, &claim_window);
-        Ok(())
+
+        let index: Vec<u64> = env
+            .storage()
+            .persistent()
+            .get(&DataKey::EscrowIndex)
+            .unwrap_or(Vec::new(&env));
+        let now = env.ledger().timestamp();
+        let start = offset.min(index.len());
+        let end = start
+            .saturating_add(limit.min(MAX_REFUND_SWEEP))
+            .min(index.len());
+
+        let mut refunded_ids: Vec<u64> = Vec::new(&env);
+        let mut total_refunded: i128 = 0;
+        for i in start..end {
+            let bounty_id = index.get(i).unwrap();
+            let escrow: Escrow = match env.storage().persistent().get(&DataKey::Escrow(bounty_id)) {
+                Some(escrow) => escrow,
+                None => continue,
+            };
+
+            let due = if escrow.milestones.is_empty() {
+                now >= escrow.deadline
+            } else {
+                escrow.milestones.iter().any(|milestone| {
+                    milestone.status == MilestoneStatus::Pending
+                        && now >= milestone.deadline.unwrap_or(escrow.deadline)
+                })
+            };
+            // Milestone escrows stay partially refunded while later stages
+            // are still locked
+            let refundable = escrow.status == EscrowStatus::Locked
+                || (escrow.status == EscrowStatus::PartiallyRefunded
+                    && escrow.remaining_amount > 0);
+            if !refundable || !due {
+                continue;
+            }
+
+            if Self::has_pending_claim(&env, bounty_id)
+                || env
+                    .storage()
+                    .persistent()
+                    .has(&DataKey::RefundApproval(bounty_id))
+            {
+                continue;
+            }
+
+            Self::refund(env.clone(), bounty_id)?;
+            let refunded: Escrow = env
+                .storage()
+                .persistent()
+                .get(&DataKey::Escrow(bounty_id))
+                .unwrap();
+            refunded_ids.push_back(bounty_id);
+            total_refunded += escrow.remaining_amount - refunded.remaining_amount;
+        }
+
+        Ok(RefundSweepSummary {
+            scanned: end - start,
+            refunded_ids,
+            total_refunded,
+            next_offset: end,
+        })
     }
 
-    /// Authorize a release as a pending claim instead of immediate transfer.
-    /// Admin calls this instead of release_funds when claim period is active.
-    /// Beneficiary must call claim() within the window to receive funds.
-    pub fn authorize_claim(env: Env, bounty_id: u64, recipient: Address) -> Result<(), Error> {
+    /// Release a single milestone to the contributor (admin only).
+    ///
+    /// Pays the milestone amount net of the release fee. The escrow becomes
+    /// `Released` once every milestone has been settled.
+    pub fn release_milestone(
+        env: Env,
+        bounty_id: u64,
+        milestone_index: u32,
+        contributor: Address,
+    ) -> Result<(), Error> {
         if Self::check_paused(&env, symbol_short!("release")) {
             return Err(Error::FundsPaused);
         }
         if !env.storage().instance().has(&DataKey::Admin) {
             return Err(Error::NotInitialized);
         }
+
         let admin: Address = env.storage().instance().get(&DataKey::Admin).unwrap();
         admin.require_auth();
 
-        if !env.storage().persistent().has(&DataKey::Escrow(bounty_id)) {
-            return Err(Error::BountyNotFound);
-        }
-
-        let escrow: Escrow = env
+        let mut escrow: Escrow = env
             .storage()
             .persistent()
             .get(&DataKey::Escrow(bounty_id))
-            .unwrap();
+            .ok_or(Error::BountyNotFound)?;
 
-        if escrow.status != EscrowStatus::Locked {
+        if escrow.status == EscrowStatus::Disputed {
+            return Err(Error::EscrowDisputed);
+        }
+
+        if escrow.status != EscrowStatus::Locked && escrow.status != EscrowStatus::PartiallyRefunded
+        {
             return Err(Error::FundsNotLocked);
         }
 
-        let now = env.ledger().timestamp();
-        let claim_window: u64 = env
-            .storage()
-            .instance()
-            .get(&DataKey::ClaimWindow)
-            .unwrap_or(0);
-        let claim = ClaimRecord {
+        let mut milestone = escrow
+            .milestones
+            .get(milestone_index)
+            .ok_or(Error::MilestoneNotFound)?;
+        if milestone.status != MilestoneStatus::Pending {
+            return Err(Error::MilestoneNotPending);
+        }
+
+        Self::consume_release_approval(
+            &env,
             bounty_id,
-            recipient: recipient.clone(),
-            amount: escrow.amount,
-            expires_at: now.saturating_add(claim_window),
-            claimed: false,
-        };
+            &contributor,
+            milestone.amount,
+            Self::released_total(&escrow),
+        )?;
+
+        let client = token::Client::new(&env, &escrow.token);
+        let net_amount = Self::collect_fee(
+            &env,
+            &client,
+            bounty_id,
+            milestone.amount,
+            FeeOperationType::Release,
+        );
+        client.transfer(&env.current_contract_address(), &contributor, &net_amount);
 
+        escrow.remaining_amount -= milestone.amount;
+        if escrow.remaining_amount == 0 {
+            escrow.status = EscrowStatus::Released;
+        }
+        milestone.status = MilestoneStatus::Released;
+        escrow.milestones.set(milestone_index, milestone);
         env.storage()
             .persistent()
-            .set(&DataKey::PendingClaim(bounty_id), &claim);
+            .set(&DataKey::Escrow(bounty_id), &escrow);
 
-        env.events().publish(
-            (symbol_short!("claim"), symbol_short!("created")),
-            ClaimCreated {
+        let now = env.ledger().timestamp();
+        emit_funds_released(
+            &env,
+            FundsReleased {
+                version: EVENT_VERSION_V2,
                 bounty_id,
-                recipient,
-                amount: escrow.amount,
-                expires_at: claim.expires_at,
+                amount: net_amount,
+                recipient: contributor.clone(),
+                timestamp: now,
+            },
+        );
+        events::emit_milestone_settled(
+            &env,
+            events::MilestoneSettled {
+                version: EVENT_VERSION_V2,
+                bounty_id,
+                milestone_index,
+                status: MilestoneStatus::Released,
+                amount: net_amount,
+                recipient: contributor,
+                timestamp: now,
             },
         );
+
         Ok(())
     }
 
-    /// Beneficiary calls this to claim their authorized funds within the window.
-    pub fn claim(env: Env, bounty_id: u64) -> Result<(), Error> {
-        if Self::check_paused(&env, symbol_short!("release")) {
+    /// Refund a single milestone to the depositor once its deadline has passed.
+    ///
+    /// Milestones without their own deadline use the escrow deadline. Like
+    /// `refund`, anyone may trigger this once the deadline has passed.
+    pub fn refund_milestone(env: Env, bounty_id: u64, milestone_index: u32) -> Result<(), Error> {
+        if Self::check_paused(&env, symbol_short!("refund")) {
             return Err(Error::FundsPaused);
         }
-        if !env
+
+        let mut escrow: Escrow = env
             .storage()
             .persistent()
-            .has(&DataKey::PendingClaim(bounty_id))
+            .get(&DataKey::Escrow(bounty_id))
+            .ok_or(Error::BountyNotFound)?;
+
+        if escrow.status == EscrowStatus::Disputed {
+            return Err(Error::EscrowDisputed);
+        }
+
+        if escrow.status != EscrowStatus::Locked && escrow.status != EscrowStatus::PartiallyRefunded
         {
-            return Err(Error::BountyNotFound);
+            return Err(Error::FundsNotLocked);
         }
-        let mut claim: ClaimRecord = env
-            .storage()
-            .persistent()
-            .get(&DataKey::PendingClaim(bounty_id))
-            .unwrap();
 
-        claim.recipient.require_auth();
+        let mut milestone = escrow
+            .milestones
+            .get(milestone_index)
+            .ok_or(Error::MilestoneNotFound)?;
+        if milestone.status != MilestoneStatus::Pending {
+            return Err(Error::MilestoneNotPending);
+        }
 
         let now = env.ledger().timestamp();
-        if now > claim.expires_at {
-            return Err(Error::DeadlineNotPassed); // reuse or add ClaimExpired error
-        }
-        if claim.claimed {
-            return Err(Error::FundsNotLocked);
+        if now < milestone.deadline.unwrap_or(escrow.deadline) {
+            return Err(Error::DeadlineNotPassed);
         }
 
-        let token_addr: Address = env.storage().instance().get(&DataKey::Token).unwrap();
-        let client = token::Client::new(&env, &token_addr);
+        let client = token::Client::new(&env, &escrow.token);
         client.transfer(
             &env.current_contract_address(),
-            &claim.recipient,
-            &claim.amount,
+            &escrow.depositor,
+            &milestone.amount,
         );
 
-        // Update escrow status
-        let mut escrow: Escrow = env
-            .storage()
-            .persistent()
-            .get(&DataKey::Escrow(bounty_id))
-            .unwrap();
-        escrow.status = EscrowStatus::Released;
+        escrow.remaining_amount -= milestone.amount;
+        escrow.refund_history.push_back(RefundRecord {
+            amount: milestone.amount,
+            recipient: escrow.depositor.clone(),
+            timestamp: now,
+            mode: RefundMode::Partial,
+        });
+        escrow.status = if escrow.remaining_amount == 0 {
+            EscrowStatus::Refunded
+        } else {
+            EscrowStatus::PartiallyRefunded
+        };
+        let refunded = milestone.amount;
+        milestone.status = MilestoneStatus::Refunded;
+        escrow.milestones.set(milestone_index, milestone);
         env.storage()
             .persistent()
             .set(&DataKey::Escrow(bounty_id), &escrow);
 
-        claim.claimed = true;
-        env.storage()
-            .persistent()
-            .set(&DataKey::PendingClaim(bounty_id), &claim);
-
-        env.events().publish(
-            (symbol_short!("claim"), symbol_short!("done")),
-            ClaimExecuted {
+        emit_funds_refunded(
+            &env,
+            FundsRefunded {
+                version: EVENT_VERSION_V2,
                 bounty_id,
-                recipient: claim.recipient.clone(),
-                amount: claim.amount,
-                claimed_at: now,
+                amount: refunded,
+                refund_to: escrow.depositor.clone(),
+                timestamp: now,
+            },
+        );
+        events::emit_milestone_settled(
+            &env,
+            events::MilestoneSettled {
+                version: EVENT_VERSION_V2,
+                bounty_id,
+                milestone_index,
+                status: MilestoneStatus::Refunded,
+                amount: refunded,
+                recipient: escrow.depositor,
+                timestamp: now,
+            },
+        );
+
+        Ok(())
+    }
+
+    /// Set the addresses allowed to resolve disputes (admin only).
+    pub fn set_arbiters(env: Env, arbiters: Vec<Address>) -> Result<(), Error> {
+        if !env.storage().instance().has(&DataKey::Admin) {
+            return Err(Error::NotInitialized);
+        }
+        let admin: Address = env.storage().instance().get(&DataKey::Admin).unwrap();
+        admin.require_auth();
+
+        env.storage().instance().set(&DataKey::Arbiters, &arbiters);
+
+        events::emit_arbiters_updated(
+            &env,
+            events::ArbitersUpdated {
+                version: EVENT_VERSION_V2,
+                arbiters,
+                admin,
+                timestamp: env.ledger().timestamp(),
             },
         );
         Ok(())
     }
 
-    /// Admin can cancel an expired or unwanted pending claim, returning escrow to Locked.
-    pub fn cancel_pending_claim(env: Env, bounty_id: u64) -> Result<(), Error> {
+    /// Get the addresses allowed to resolve disputes
+    pub fn get_arbiters(env: Env) -> Vec<Address> {
+        env.storage()
+            .instance()
+            .get(&DataKey::Arbiters)
+            .unwrap_or(Vec::new(&env))
+    }
+
+    /// Set how long arbiters have to resolve a dispute (admin only).
+    pub fn set_dispute_window(env: Env, dispute_window: u64) -> Result<(), Error> {
         if !env.storage().instance().has(&DataKey::Admin) {
             return Err(Error::NotInitialized);
         }
         let admin: Address = env.storage().instance().get(&DataKey::Admin).unwrap();
         admin.require_auth();
+        env.storage()
+            .instance()
+            .set(&DataKey::DisputeWindow, &dispute_window);
+        Ok(())
+    }
 
-        if !env
-            .storage()
-            .persistent()
-            .has(&DataKey::PendingClaim(bounty_id))
-        {
-            return Err(Error::BountyNotFound);
-        }
-        let claim: ClaimRecord = env
-            .storage()
-            .persistent()
-            .get(&DataKey::PendingClaim(bounty_id))
-            .unwrap();
-
-        if claim.claimed {
-            return Err(Error::FundsNotLocked);
-        }
-
+    /// Whether an unclaimed pending or split claim is outstanding.
+    fn has_pending_claim(env: &Env, bounty_id: u64) -> bool {
         env.storage()
             .persistent()
-            .remove(&DataKey::PendingClaim(bounty_id));
-
-        env.events().publish(
-            (symbol_short!("claim"), symbol_short!("cancel")),
-            ClaimCancelled {
-                bounty_id,
-                recipient: claim.recipient,
-                amount: claim.amount,
-                cancelled_at: env.ledger().timestamp(),
-                cancelled_by: admin,
-            },
-        );
-        Ok(())
+            .get::<DataKey, ClaimRecord>(&DataKey::PendingClaim(bounty_id))
+            .map(|claim| !claim.claimed)
+            .unwrap_or(false)
+            || env
+                .storage()
+                .persistent()
+                .get::<DataKey, Vec<ClaimRecord>>(&DataKey::SplitClaims(bounty_id))
+                .map(|claims| claims.iter().any(|claim| !claim.claimed))
+                .unwrap_or(false)
     }
 
-    /// View: get pending claim for a bounty.
-    pub fn get_pending_claim(env: Env, bounty_id: u64) -> Result<ClaimRecord, Error> {
+    /// Whether `party` is named in an unclaimed pending or split claim.
+    fn is_claim_recipient(env: &Env, bounty_id: u64, party: &Address) -> bool {
         env.storage()
             .persistent()
-            .get(&DataKey::PendingClaim(bounty_id))
-            .ok_or(Error::BountyNotFound)
+            .get::<DataKey, ClaimRecord>(&DataKey::PendingClaim(bounty_id))
+            .map(|claim| !claim.claimed && claim.recipient == *party)
+            .unwrap_or(false)
+            || env
+                .storage()
+                .persistent()
+                .get::<DataKey, Vec<ClaimRecord>>(&DataKey::SplitClaims(bounty_id))
+                .map(|claims| {
+                    claims
+                        .iter()
+                        .any(|claim| !claim.claimed && claim.recipient == *party)
+                })
+                .unwrap_or(false)
     }
 
-    /// Approve a refund before deadline (admin only).
-    /// This allows early refunds with admin approval.
-    pub fn approve_refund(
+    /// Open a dispute on a bounty, freezing release, refund and claim.
+    ///
+    /// Either the depositor or a contributor named in a pending claim may
+    /// open a dispute. `evidence_hash` commits to off-chain evidence (e.g. the
+    /// SHA-256 of a document) for the arbiters to review. Arbiters must be
+    /// configured, and they have the dispute window (7 days by default) to
+    /// resolve it before it can lapse via `expire_dispute`.
+    pub fn open_dispute(
         env: Env,
         bounty_id: u64,
-        amount: i128,
-        recipient: Address,
-        mode: RefundMode,
+        party: Address,
+        evidence_hash: BytesN<32>,
     ) -> Result<(), Error> {
         if !env.storage().instance().has(&DataKey::Admin) {
             return Err(Error::NotInitialized);
         }
 
-        let admin: Address = env.storage().instance().get(&DataKey::Admin).unwrap();
-        admin.require_auth();
-
-        if !env.storage().persistent().has(&DataKey::Escrow(bounty_id)) {
-            return Err(Error::BountyNotFound);
-        }
-
-        let escrow: Escrow = env
+        let mut escrow: Escrow = env
             .storage()
             .persistent()
             .get(&DataKey::Escrow(bounty_id))
-            .unwrap();
+            .ok_or(Error::BountyNotFound)?;
 
-        if escrow.status != EscrowStatus::Locked && escrow.status != EscrowStatus::PartiallyRefunded
+        if escrow.status == EscrowStatus::Disputed {
+            return Err(Error::EscrowDisputed);
+        }
+
+        if escrow.status != EscrowStatus::Locked
+            && escrow.status != EscrowStatus::PartiallyRefunded
+            && escrow.status != EscrowStatus::PendingSplit
         {
             return Err(Error::FundsNotLocked);
         }
 
-        if amount <= 0 || amount > escrow.remaining_amount {
-            return Err(Error::InvalidAmount);
+        // Nobody could resolve it, so the funds would stay frozen
+        if Self::get_arbiters(env.clone()).is_empty() {
+            return Err(Error::NoArbiters);
         }
 
-        let approval = RefundApproval {
+        if party != escrow.depositor && !Self::is_claim_recipient(&env, bounty_id, &party) {
+            return Err(Error::Unauthorized);
+        }
+
+        party.require_auth();
+
+        let now = env.ledger().timestamp();
+        let dispute_window: u64 = env
+            .storage()
+            .instance()
+            .get(&DataKey::DisputeWindow)
+            .unwrap_or(DEFAULT_DISPUTE_WINDOW);
+        let dispute = Dispute {
             bounty_id,
-            amount,
-            recipient: recipient.clone(),
-            mode: mode.clone(),
-            approved_by: admin.clone(),
-            approved_at: env.ledger().timestamp(),
+            opened_by: party.clone(),
+            evidence_hash: evidence_hash.clone(),
+            opened_at: now,
+            resolve_by: now.saturating_add(dispute_window),
+            prior_status: escrow.status.clone(),
         };
-
         env.storage()
             .persistent()
-            .set(&DataKey::Escrow(bounty_id), &escrow);
+            .set(&DataKey::Dispute(bounty_id), &dispute);
 
+        escrow.status = EscrowStatus::Disputed;
         env.storage()
             .persistent()
-            .set(&DataKey::RefundApproval(bounty_id), &approval);
+            .set(&DataKey::Escrow(bounty_id), &escrow);
 
+        events::emit_dispute_opened(
+            &env,
+            events::DisputeOpened {
+                version: EVENT_VERSION_V2,
+                bounty_id,
+                opened_by: party,
+                evidence_hash,
+                timestamp: now,
+            },
+        );
         Ok(())
     }
 
-    /// Release a partial amount of the locked funds to the contributor.
-    /// Only the admin (backend) can authorize this.
+    /// Resolve an open dispute by splitting the remaining funds (arbiter only).
     ///
-    /// - `payout_amount` must be > 0 and <= `remaining_amount`.
-    /// - `remaining_amount` is decremented by `payout_amount` after each call.
-    /// - When `remaining_amount` reaches 0 the escrow status is set to Released.
-    /// - The bounty stays Locked while any funds remain unreleased.
-    pub fn partial_release(
+    /// `contributor_amount` of the `remaining_amount` goes to `contributor`
+    /// (subject to the release fee) and the rest is refunded to the funders.
+    /// A non-zero payout must go to a recipient of a pending claim on the
+    /// bounty; with no claim on record the arbiter can only refund. Must be
+    /// called before the dispute's `resolve_by`. Any pending claim or refund
+    /// approval on the bounty is discarded.
+    pub fn resolve_dispute(
         env: Env,
         bounty_id: u64,
+        arbiter: Address,
         contributor: Address,
-        payout_amount: i128,
+        contributor_amount: i128,
     ) -> Result<(), Error> {
         if !env.storage().instance().has(&DataKey::Admin) {
             return Err(Error::NotInitialized);
         }
 
-        let admin: Address = env.storage().instance().get(&DataKey::Admin).unwrap();
-        admin.require_auth();
+        if !Self::get_arbiters(env.clone()).contains(&arbiter) {
+            return Err(Error::Unauthorized);
+        }
+        arbiter.require_auth();
 
-        if !env.storage().persistent().has(&DataKey::Escrow(bounty_id)) {
-            return Err(Error::BountyNotFound);
+        let dispute: Dispute = env
+            .storage()
+            .persistent()
+            .get(&DataKey::Dispute(bounty_id))
+            .ok_or(Error::DisputeNotFound)?;
+
+        let now = env.ledger().timestamp();
+        if now > dispute.resolve_by {
+            return Err(Error::DisputeExpired);
         }
 
         let mut escrow: Escrow = env
@@ -1263,65 +3303,108 @@ impl BountyEscrowContract {
             .get(&DataKey::Escrow(bounty_id))
             .unwrap();
 
-        if escrow.status != EscrowStatus::Locked {
-            return Err(Error::FundsNotLocked);
-        }
-
-        // Guard: zero or negative payout makes no sense and would corrupt state
-        if payout_amount <= 0 {
+        if contributor_amount < 0 || contributor_amount > escrow.remaining_amount {
             return Err(Error::InvalidAmount);
         }
-
-        // Guard: prevent overpayment — payout cannot exceed what is still owed
-        if payout_amount > escrow.remaining_amount {
-            return Err(Error::InsufficientFunds);
+        if contributor_amount > 0 && !Self::is_claim_recipient(&env, bounty_id, &contributor) {
+            return Err(Error::DisputePayeeMismatch);
         }
+        let depositor_amount = escrow.remaining_amount - contributor_amount;
 
-        let token_addr: Address = env.storage().instance().get(&DataKey::Token).unwrap();
-        let client = token::Client::new(&env, &token_addr);
+        let client = token::Client::new(&env, &escrow.token);
 
-        // Transfer only the requested partial amount to the contributor
-        client.transfer(
-            &env.current_contract_address(),
-            &contributor,
-            &payout_amount,
-        );
+        if contributor_amount > 0 {
+            let net_amount = Self::collect_fee(
+                &env,
+                &client,
+                bounty_id,
+                contributor_amount,
+                FeeOperationType::Release,
+            );
+            client.transfer(&env.current_contract_address(), &contributor, &net_amount);
 
-        // Decrement remaining; this is always an exact integer subtraction — no rounding
-        escrow.remaining_amount -= payout_amount;
+            emit_funds_released(
+                &env,
+                FundsReleased {
+                    version: EVENT_VERSION_V2,
+                    bounty_id,
+                    amount: net_amount,
+                    recipient: contributor.clone(),
+                    timestamp: now,
+                },
+            );
+        }
 
-        // Automatically transition to Released once fully paid out
-        if escrow.remaining_amount == 0 {
-            escrow.status = EscrowStatus::Released;
+        escrow.remaining_amount -= contributor_amount;
+        if depositor_amount > 0 {
+            let mode = if contributor_amount == 0 {
+                RefundMode::Full
+            } else {
+                RefundMode::Partial
+            };
+            Self::refund_to_funders(
+                &env,
+                &client,
+                bounty_id,
+                &mut escrow,
+                depositor_amount,
+                mode,
+            );
         }
 
+        escrow.status = if contributor_amount > 0 {
+            EscrowStatus::Released
+        } else {
+            EscrowStatus::Refunded
+        };
         env.storage()
             .persistent()
             .set(&DataKey::Escrow(bounty_id), &escrow);
 
-        emit_funds_released(
+        env.storage()
+            .persistent()
+            .remove(&DataKey::Dispute(bounty_id));
+        env.storage()
+            .persistent()
+            .remove(&DataKey::PendingClaim(bounty_id));
+        env.storage()
+            .persistent()
+            .remove(&DataKey::SplitClaims(bounty_id));
+        env.storage()
+            .persistent()
+            .remove(&DataKey::RefundApproval(bounty_id));
+
+        events::emit_dispute_resolved(
             &env,
-            FundsReleased {
+            events::DisputeResolved {
                 version: EVENT_VERSION_V2,
                 bounty_id,
-                amount: payout_amount,
-                recipient: contributor.clone(),
-                timestamp: env.ledger().timestamp(),
+                arbiter,
+                contributor,
+                contributor_amount,
+                depositor_amount,
+                timestamp: now,
             },
         );
-
         Ok(())
     }
 
-    /// Refund funds to the original depositor if the deadline has passed.
-    /// Refunds the full remaining_amount (accounts for any prior partial releases).
-    pub fn refund(env: Env, bounty_id: u64) -> Result<(), Error> {
-        if Self::check_paused(&env, symbol_short!("refund")) {
-            return Err(Error::FundsPaused);
-        }
+    /// Let a dispute lapse once its `resolve_by` has passed without a ruling.
+    ///
+    /// The default outcome is no change: the escrow returns to the status it
+    /// had before the dispute, with any pending claim and refund approval
+    /// intact, and the normal release and refund rules apply again. Anyone may
+    /// call this.
+    pub fn expire_dispute(env: Env, bounty_id: u64) -> Result<(), Error> {
+        let dispute: Dispute = env
+            .storage()
+            .persistent()
+            .get(&DataKey::Dispute(bounty_id))
+            .ok_or(Error::DisputeNotFound)?;
 
-        if !env.storage().persistent().has(&DataKey::Escrow(bounty_id)) {
-            return Err(Error::BountyNotFound);
+        let now = env.ledger().timestamp();
+        if now <= dispute.resolve_by {
+            return Err(Error::DeadlineNotPassed);
         }
 
         let mut escrow: Escrow = env
@@ -1329,45 +3412,34 @@ impl BountyEscrowContract {
             .persistent()
             .get(&DataKey::Escrow(bounty_id))
             .unwrap();
-
-        if escrow.status != EscrowStatus::Locked {
-            return Err(Error::FundsNotLocked);
-        }
-
-        let now = env.ledger().timestamp();
-        if now < escrow.deadline {
-            return Err(Error::DeadlineNotPassed);
-        }
-
-        let token_addr: Address = env.storage().instance().get(&DataKey::Token).unwrap();
-        let client = token::Client::new(&env, &token_addr);
-
-        // Refund only what is still remaining (partial releases may have already gone out)
-        client.transfer(
-            &env.current_contract_address(),
-            &escrow.depositor,
-            &escrow.remaining_amount,
-        );
-
-        escrow.status = EscrowStatus::Refunded;
+        escrow.status = dispute.prior_status.clone();
         env.storage()
             .persistent()
             .set(&DataKey::Escrow(bounty_id), &escrow);
+        env.storage()
+            .persistent()
+            .remove(&DataKey::Dispute(bounty_id));
 
-        emit_funds_refunded(
+        events::emit_dispute_expired(
             &env,
-            FundsRefunded {
+            events::DisputeExpired {
                 version: EVENT_VERSION_V2,
                 bounty_id,
-                amount: escrow.remaining_amount,
-                refund_to: escrow.depositor,
-                timestamp: env.ledger().timestamp(),
+                restored_status: dispute.prior_status,
+                timestamp: now,
             },
         );
-
         Ok(())
     }
 
+    /// View: get the open dispute for a bounty.
+    pub fn get_dispute(env: Env, bounty_id: u64) -> Result<Dispute, Error> {
+        env.storage()
+            .persistent()
+            .get(&DataKey::Dispute(bounty_id))
+            .ok_or(Error::DisputeNotFound)
+    }
+
     /// view function to get escrow info
     pub fn get_escrow_info(env: Env, bounty_id: u64) -> Result<Escrow, Error> {
         if !env.storage().persistent().has(&DataKey::Escrow(bounty_id)) {
@@ -1380,7 +3452,7 @@ impl BountyEscrowContract {
             .unwrap())
     }
 
-    /// view function to get contract balance of the token
+    /// view function to get contract balance of the default token
     pub fn get_balance(env: Env) -> Result<i128, Error> {
         if !env.storage().instance().has(&DataKey::Token) {
             return Err(Error::NotInitialized);
@@ -1390,6 +3462,15 @@ impl BountyEscrowContract {
         Ok(client.balance(&env.current_contract_address()))
     }
 
+    /// view function to get contract balance of a specific token
+    pub fn get_token_balance(env: Env, token: Address) -> Result<i128, Error> {
+        if !env.storage().instance().has(&DataKey::Token) {
+            return Err(Error::NotInitialized);
+        }
+        let client = token::Client::new(&env, &token);
+        Ok(client.balance(&env.current_contract_address()))
+    }
+
     /// Query escrows with filtering and pagination
     /// Pass 0 for min values and i128::MAX/u64::MAX for max values to disable those filters
     pub fn query_escrows_by_status(
@@ -1542,13 +3623,27 @@ impl BountyEscrowContract {
         results
     }
 
-    /// Get aggregate statistics
+    /// Get aggregate statistics for escrows funded in the default token.
+    /// Amounts in different tokens cannot be added up; use
+    /// `get_aggregate_stats_by_token` for the others.
     pub fn get_aggregate_stats(env: Env) -> AggregateStats {
+        let token: Option<Address> = env.storage().instance().get(&DataKey::Token);
+        Self::compute_aggregate_stats(&env, token)
+    }
+
+    /// Get aggregate statistics for escrows funded in a specific token
+    pub fn get_aggregate_stats_by_token(env: Env, token: Address) -> AggregateStats {
+        Self::compute_aggregate_stats(&env, Some(token))
+    }
+
+    /// Sum escrow amounts by status for a single token. `None` only occurs
+    /// before `init`, when nothing has been locked yet.
+    fn compute_aggregate_stats(env: &Env, token: Option<Address>) -> AggregateStats {
         let index: Vec<u64> = env
             .storage()
             .persistent()
             .get(&DataKey::EscrowIndex)
-            .unwrap_or(Vec::new(&env));
+            .unwrap_or(Vec::new(env));
         let mut stats = AggregateStats {
             total_locked: 0,
             total_released: 0,
@@ -1565,8 +3660,13 @@ impl BountyEscrowContract {
                 .persistent()
                 .get::<DataKey, Escrow>(&DataKey::Escrow(bounty_id))
             {
+                if let Some(token) = &token {
+                    if escrow.token != *token {
+                        continue;
+                    }
+                }
                 match escrow.status {
-                    EscrowStatus::Locked => {
+                    EscrowStatus::Locked | EscrowStatus::Disputed | EscrowStatus::PendingSplit => {
                         stats.total_locked += escrow.amount;
                         stats.count_locked += 1;
                     }
@@ -1755,6 +3855,7 @@ impl BountyEscrowContract {
     ///
     /// # Arguments
     /// * `items` - Vector of LockFundsItem containing bounty_id, depositor, amount, and deadline
+    /// * `token` - Allowlisted token every item is locked in; the default token when `None`
     ///
     /// # Returns
     /// Number of successfully locked bounties
@@ -1763,10 +3864,15 @@ impl BountyEscrowContract {
     /// * InvalidBatchSize - if batch size exceeds MAX_BATCH_SIZE or is zero
     /// * BountyExists - if any bounty_id already exists
     /// * NotInitialized - if contract is not initialized
+    /// * TokenNotAllowed - if the token is not on the allowlist
     ///
     /// # Note
     /// This operation is atomic - if any item fails, the entire transaction reverts.
-    pub fn batch_lock_funds(env: Env, items: Vec<LockFundsItem>) -> Result<u32, Error> {
+    pub fn batch_lock_funds(
+        env: Env,
+        items: Vec<LockFundsItem>,
+        token: Option<Address>,
+    ) -> Result<u32, Error> {
         if Self::check_paused(&env, symbol_short!("lock")) {
             return Err(Error::FundsPaused);
         }
@@ -1783,7 +3889,14 @@ impl BountyEscrowContract {
             return Err(Error::NotInitialized);
         }
 
-        let token_addr: Address = env.storage().instance().get(&DataKey::Token).unwrap();
+        // One token per batch keeps the batch total meaningful
+        let token_addr: Address = match token {
+            Some(token) => token,
+            None => env.storage().instance().get(&DataKey::Token).unwrap(),
+        };
+        if !Self::is_token_allowed(env.clone(), token_addr.clone()) {
+            return Err(Error::TokenNotAllowed);
+        }
         let client = token::Client::new(&env, &token_addr);
         let contract_address = env.current_contract_address();
         let timestamp = env.ledger().timestamp();
@@ -1835,18 +3948,34 @@ impl BountyEscrowContract {
 
         // Process all items (atomic - all succeed or all fail)
         let mut locked_count = 0u32;
+        let mut total_amount: i128 = 0;
+        let mut index: Vec<u64> = env
+            .storage()
+            .persistent()
+            .get(&DataKey::EscrowIndex)
+            .unwrap_or(Vec::new(&env));
         for item in items.iter() {
             // Transfer funds from depositor to contract
             client.transfer(&item.depositor, &contract_address, &item.amount);
 
+            let net_amount = Self::collect_fee(
+                &env,
+                &client,
+                item.bounty_id,
+                item.amount,
+                FeeOperationType::Lock,
+            );
+
             // Create escrow record
             let escrow = Escrow {
                 depositor: item.depositor.clone(),
-                amount: item.amount,
+                token: token_addr.clone(),
+                amount: net_amount,
                 status: EscrowStatus::Locked,
                 deadline: item.deadline,
                 refund_history: vec![&env],
-                remaining_amount: item.amount,
+                remaining_amount: net_amount,
+                milestones: vec![&env],
             };
 
             // Store escrow
@@ -1854,27 +3983,46 @@ impl BountyEscrowContract {
                 .persistent()
                 .set(&DataKey::Escrow(item.bounty_id), &escrow);
 
+            // Update indexes so batch-locked bounties show up in queries and sweeps
+            index.push_back(item.bounty_id);
+            let mut depositor_index: Vec<u64> = env
+                .storage()
+                .persistent()
+                .get(&DataKey::DepositorIndex(item.depositor.clone()))
+                .unwrap_or(Vec::new(&env));
+            depositor_index.push_back(item.bounty_id);
+            env.storage().persistent().set(
+                &DataKey::DepositorIndex(item.depositor.clone()),
+                &depositor_index,
+            );
+
             // Emit individual event for each locked bounty
             emit_funds_locked(
                 &env,
                 FundsLocked {
                     version: EVENT_VERSION_V2,
                     bounty_id: item.bounty_id,
-                    amount: item.amount,
+                    amount: net_amount,
                     depositor: item.depositor.clone(),
+                    token: token_addr.clone(),
                     deadline: item.deadline,
                 },
             );
 
+            total_amount += net_amount;
             locked_count += 1;
         }
 
+        env.storage()
+            .persistent()
+            .set(&DataKey::EscrowIndex, &index);
+
         // Emit batch event
         emit_batch_funds_locked(
             &env,
             BatchFundsLocked {
                 count: locked_count,
-                total_amount: items.iter().map(|i| i.amount).sum(),
+                total_amount,
                 timestamp,
             },
         );
@@ -1919,8 +4067,6 @@ impl BountyEscrowContract {
         let admin: Address = env.storage().instance().get(&DataKey::Admin).unwrap();
         admin.require_auth();
 
-        let token_addr: Address = env.storage().instance().get(&DataKey::Token).unwrap();
-        let client = token::Client::new(&env, &token_addr);
         let contract_address = env.current_contract_address();
         let timestamp = env.ledger().timestamp();
 
@@ -1943,6 +4089,14 @@ impl BountyEscrowContract {
                 .unwrap();
 
             // Check if funds are locked
+            if escrow.status == EscrowStatus::Disputed {
+                return Err(Error::EscrowDisputed);
+            }
+
+            if !escrow.milestones.is_empty() {
+                return Err(Error::MilestoneReleaseRequired);
+            }
+
             if escrow.status != EscrowStatus::Locked {
                 return Err(Error::FundsNotLocked);
             }
@@ -1958,13 +4112,22 @@ impl BountyEscrowContract {
                 return Err(Error::DuplicateBountyId);
             }
 
+            Self::consume_release_approval(
+                &env,
+                item.bounty_id,
+                &item.contributor,
+                escrow.remaining_amount,
+                Self::released_total(&escrow),
+            )?;
+
             total_amount = total_amount
-                .checked_add(escrow.amount)
+                .checked_add(escrow.remaining_amount)
                 .ok_or(Error::InvalidAmount)?;
         }
 
         // Process all items (atomic - all succeed or all fail)
         let mut released_count = 0u32;
+        let mut total_net_amount: i128 = 0;
         for item in items.iter() {
             let mut escrow: Escrow = env
                 .storage()
@@ -1972,10 +4135,19 @@ impl BountyEscrowContract {
                 .get(&DataKey::Escrow(item.bounty_id))
                 .unwrap();
 
-            // Transfer funds to contributor
-            client.transfer(&contract_address, &item.contributor, &escrow.amount);
+            // Route the release fee (if any), then transfer the net amount
+            let client = token::Client::new(&env, &escrow.token);
+            let net_amount = Self::collect_fee(
+                &env,
+                &client,
+                item.bounty_id,
+                escrow.remaining_amount,
+                FeeOperationType::Release,
+            );
+            client.transfer(&contract_address, &item.contributor, &net_amount);
 
             // Update escrow status
+            escrow.remaining_amount = 0;
             escrow.status = EscrowStatus::Released;
             env.storage()
                 .persistent()
@@ -1987,12 +4159,13 @@ impl BountyEscrowContract {
                 FundsReleased {
                     version: EVENT_VERSION_V2,
                     bounty_id: item.bounty_id,
-                    amount: escrow.amount,
+                    amount: net_amount,
                     recipient: item.contributor.clone(),
                     timestamp,
                 },
             );
 
+            total_net_amount += net_amount;
             released_count += 1;
         }
 
@@ -2001,7 +4174,7 @@ impl BountyEscrowContract {
             &env,
             BatchFundsReleased {
                 count: released_count,
-                total_amount,
+                total_amount: total_net_amount,
                 timestamp,
             },
         );
@@ -2013,10 +4186,22 @@ impl BountyEscrowContract {
 #[cfg(test)]
 mod test;
 #[cfg(test)]
+mod test_amendment;
+#[cfg(test)]
 mod test_analytics_monitoring;
 #[cfg(test)]
 mod test_auto_refund_permissions;
 #[cfg(test)]
 mod test_expiration_and_dispute;
 #[cfg(test)]
+mod test_milestones;
+#[cfg(test)]
+mod test_multi_token;
+#[cfg(test)]
+mod test_multisig;
+#[cfg(test)]
 mod test_pause;
+#[cfg(test)]
+mod test_split_release;
+#[cfg(test)]
+mod test_top_up;
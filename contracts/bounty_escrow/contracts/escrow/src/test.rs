@@ -1,4 +1,4 @@
-﻿#![cfg(test)]
+#![cfg(test)]
 
 use super::*;
 use soroban_sdk::{
@@ -604,7 +604,7 @@ fn test_batch_lock_funds_success() {
     setup.token_admin.mint(&setup.depositor, &10_000);
 
     // Batch lock funds
-    let count = setup.escrow.batch_lock_funds(&items);
+    let count = setup.escrow.batch_lock_funds(&items, &None);
     assert_eq!(count, 3);
 
     // Verify all bounties are locked
@@ -622,7 +622,7 @@ fn test_batch_lock_funds_success() {
 fn test_batch_lock_funds_empty() {
     let setup = TestSetup::new();
     let items: Vec<LockFundsItem> = vec![&setup.env];
-    setup.escrow.batch_lock_funds(&items);
+    setup.escrow.batch_lock_funds(&items, &None);
 }
 
 #[test]
@@ -642,7 +642,7 @@ fn test_batch_lock_funds_single_item() {
     ];
 
     setup.token_admin.mint(&setup.depositor, &1000);
-    let count = setup.escrow.batch_lock_funds(&items);
+    let count = setup.escrow.batch_lock_funds(&items, &None);
     assert_eq!(count, 1);
 
     let escrow = setup.escrow.get_escrow_info(&1);
@@ -669,7 +669,7 @@ fn test_batch_lock_funds_exceeds_max_batch_size() {
     }
 
     setup.token_admin.mint(&setup.depositor, &10_000);
-    setup.escrow.batch_lock_funds(&items);
+    setup.escrow.batch_lock_funds(&items, &None);
 }
 
 #[test]
@@ -689,7 +689,7 @@ fn test_batch_lock_funds_at_max_batch_size() {
     }
 
     setup.token_admin.mint(&setup.depositor, &10_000);
-    let count = setup.escrow.batch_lock_funds(&items);
+    let count = setup.escrow.batch_lock_funds(&items, &None);
     assert_eq!(count, 20);
 }
 
@@ -723,7 +723,7 @@ fn test_batch_lock_funds_duplicate_bounty_id() {
         },
     ];
 
-    setup.escrow.batch_lock_funds(&items);
+    setup.escrow.batch_lock_funds(&items, &None);
 }
 
 #[test]
@@ -748,7 +748,7 @@ fn test_batch_lock_funds_duplicate_in_batch() {
         },
     ];
 
-    setup.escrow.batch_lock_funds(&items);
+    setup.escrow.batch_lock_funds(&items, &None);
 }
 
 #[test]
@@ -781,7 +781,7 @@ fn test_batch_lock_funds_triple_duplicate_in_batch() {
     ];
 
     setup.token_admin.mint(&setup.depositor, &10000);
-    setup.escrow.batch_lock_funds(&items);
+    setup.escrow.batch_lock_funds(&items, &None);
 }
 
 #[test]
@@ -814,7 +814,7 @@ fn test_batch_lock_funds_non_adjacent_duplicates() {
     ];
 
     setup.token_admin.mint(&setup.depositor, &10000);
-    setup.escrow.batch_lock_funds(&items);
+    setup.escrow.batch_lock_funds(&items, &None);
 }
 
 // --- INVALID AMOUNT TESTS ---
@@ -835,7 +835,7 @@ fn test_batch_lock_funds_zero_amount() {
         },
     ];
 
-    setup.escrow.batch_lock_funds(&items);
+    setup.escrow.batch_lock_funds(&items, &None);
 }
 
 #[test]
@@ -854,7 +854,7 @@ fn test_batch_lock_funds_negative_amount() {
         },
     ];
 
-    setup.escrow.batch_lock_funds(&items);
+    setup.escrow.batch_lock_funds(&items, &None);
 }
 
 #[test]
@@ -881,7 +881,7 @@ fn test_batch_lock_funds_mixed_valid_invalid_amounts() {
     ];
 
     setup.token_admin.mint(&setup.depositor, &2000);
-    setup.escrow.batch_lock_funds(&items);
+    setup.escrow.batch_lock_funds(&items, &None);
 }
 
 // --- MIXED VALIDITY TESTS ---
@@ -915,7 +915,7 @@ fn test_batch_lock_funds_first_valid_second_exists() {
     ];
 
     setup.token_admin.mint(&setup.depositor, &5000);
-    setup.escrow.batch_lock_funds(&items);
+    setup.escrow.batch_lock_funds(&items, &None);
 }
 
 #[test]
@@ -948,7 +948,7 @@ fn test_batch_operations_atomicity() {
     ];
 
     // This should panic and no bounties should be locked
-    setup.escrow.batch_lock_funds(&items);
+    setup.escrow.batch_lock_funds(&items, &None);
 }
 
 // --- BATCH RELEASE TESTS ---
@@ -1061,7 +1061,7 @@ fn test_batch_release_funds_exceeds_max_batch_size() {
         });
     }
     setup.token_admin.mint(&setup.depositor, &10_000);
-    setup.escrow.batch_lock_funds(&lock_items);
+    setup.escrow.batch_lock_funds(&lock_items, &None);
 
     // Try to release 21 items (including one that doesn't exist)
     let mut release_items = Vec::new(&setup.env);
@@ -1274,7 +1274,7 @@ fn test_batch_operations_large_batch() {
     setup.token_admin.mint(&setup.depositor, &10_000);
 
     // Batch lock
-    let count = setup.escrow.batch_lock_funds(&items);
+    let count = setup.escrow.batch_lock_funds(&items, &None);
     assert_eq!(count, 10);
 
     // Verify all are locked
@@ -1333,7 +1333,7 @@ fn test_batch_operations_multiple_depositors() {
         },
     ];
 
-    let count = setup.escrow.batch_lock_funds(&items);
+    let count = setup.escrow.batch_lock_funds(&items, &None);
     assert_eq!(count, 3);
 
     // Verify each bounty has correct depositor
@@ -1402,3 +1402,135 @@ fn test_batch_release_funds_to_multiple_contributors() {
     assert_eq!(setup.token.balance(&contributor3), 3000);
     assert_eq!(setup.escrow.get_balance(), 0);
 }
+
+// =============================================================================
+// Approved Refund Tests
+// =============================================================================
+
+/// An admin-approved full refund executes before the deadline.
+#[test]
+fn test_approved_full_refund_before_deadline() {
+    let setup = TestSetup::new();
+    let deadline = setup.env.ledger().timestamp() + 1000;
+
+    setup
+        .escrow
+        .lock_funds(&setup.depositor, &1, &1000, &deadline);
+    setup
+        .escrow
+        .approve_refund(&1, &1000, &setup.depositor, &RefundMode::Full);
+
+    let initial_depositor_balance = setup.token.balance(&setup.depositor);
+    setup.escrow.refund(&1);
+
+    let escrow = setup.escrow.get_escrow_info(&1);
+    assert_eq!(escrow.status, EscrowStatus::Refunded);
+    assert_eq!(escrow.remaining_amount, 0);
+    assert_eq!(
+        setup.token.balance(&setup.depositor),
+        initial_depositor_balance + 1000
+    );
+
+    let history = setup.escrow.get_refund_history(&1);
+    assert_eq!(history.len(), 1);
+    let record = history.get(0).unwrap();
+    assert_eq!(record.amount, 1000);
+    assert_eq!(record.recipient, setup.depositor);
+    assert_eq!(record.mode, RefundMode::Full);
+}
+
+/// A partial approval pays the approved amount to the approved recipient
+/// and leaves the rest in escrow.
+#[test]
+fn test_approved_partial_refund_to_custom_recipient() {
+    let setup = TestSetup::new();
+    let deadline = setup.env.ledger().timestamp() + 1000;
+    let recipient = Address::generate(&setup.env);
+
+    setup
+        .escrow
+        .lock_funds(&setup.depositor, &1, &1000, &deadline);
+    setup
+        .escrow
+        .approve_refund(&1, &300, &recipient, &RefundMode::Partial);
+    setup.escrow.refund(&1);
+
+    let escrow = setup.escrow.get_escrow_info(&1);
+    assert_eq!(escrow.status, EscrowStatus::PartiallyRefunded);
+    assert_eq!(escrow.remaining_amount, 700);
+    assert_eq!(setup.token.balance(&recipient), 300);
+    assert_eq!(setup.token.balance(&setup.escrow.address), 700);
+
+    let history = setup.escrow.get_refund_history(&1);
+    assert_eq!(history.len(), 1);
+    assert_eq!(history.get(0).unwrap().mode, RefundMode::Partial);
+}
+
+/// The approval is consumed: a second early refund is rejected.
+#[test]
+fn test_refund_approval_consumed_after_execution() {
+    let setup = TestSetup::new();
+    let deadline = setup.env.ledger().timestamp() + 1000;
+
+    setup
+        .escrow
+        .lock_funds(&setup.depositor, &1, &1000, &deadline);
+    setup
+        .escrow
+        .approve_refund(&1, &400, &setup.depositor, &RefundMode::Partial);
+    setup.escrow.refund(&1);
+
+    let (_, _, _, approval) = setup.escrow.get_refund_eligibility(&1);
+    assert!(approval.is_none());
+
+    let result = setup.escrow.try_refund(&1);
+    assert_eq!(result, Err(Ok(Error::DeadlineNotPassed)));
+}
+
+/// Once the deadline passes, the remainder of a partially refunded escrow
+/// goes back to the depositor and both refunds appear in the history.
+#[test]
+fn test_refund_remainder_after_partial_refund() {
+    let setup = TestSetup::new();
+    let deadline = setup.env.ledger().timestamp() + 1000;
+    let recipient = Address::generate(&setup.env);
+
+    setup
+        .escrow
+        .lock_funds(&setup.depositor, &1, &1000, &deadline);
+    setup
+        .escrow
+        .approve_refund(&1, &250, &recipient, &RefundMode::Partial);
+    setup.escrow.refund(&1);
+
+    setup.env.ledger().set_timestamp(deadline + 1);
+    let initial_depositor_balance = setup.token.balance(&setup.depositor);
+    setup.escrow.refund(&1);
+
+    let escrow = setup.escrow.get_escrow_info(&1);
+    assert_eq!(escrow.status, EscrowStatus::Refunded);
+    assert_eq!(escrow.remaining_amount, 0);
+    assert_eq!(
+        setup.token.balance(&setup.depositor),
+        initial_depositor_balance + 750
+    );
+    assert_eq!(setup.escrow.get_refund_history(&1).len(), 2);
+}
+
+/// An approval that exceeds what is left after later releases is rejected.
+#[test]
+fn test_refund_approval_exceeding_remaining_rejected() {
+    let setup = TestSetup::new();
+    let deadline = setup.env.ledger().timestamp() + 1000;
+
+    setup
+        .escrow
+        .lock_funds(&setup.depositor, &1, &1000, &deadline);
+    setup
+        .escrow
+        .approve_refund(&1, &800, &setup.depositor, &RefundMode::Partial);
+    setup.escrow.partial_release(&1, &setup.contributor, &500);
+
+    let result = setup.escrow.try_refund(&1);
+    assert_eq!(result, Err(Ok(Error::InvalidAmount)));
+}
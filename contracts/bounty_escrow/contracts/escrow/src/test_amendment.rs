@@ -0,0 +1,233 @@
+#![cfg(test)]
+
+use super::*;
+use soroban_sdk::{
+    testutils::{Address as _, AuthorizedFunction, Events, Ledger},
+    token, Address, Env, Symbol, TryFromVal,
+};
+
+fn create_token_contract<'a>(
+    e: &Env,
+    admin: &Address,
+) -> (token::Client<'a>, token::StellarAssetClient<'a>) {
+    let contract_address = e
+        .register_stellar_asset_contract_v2(admin.clone())
+        .address();
+    (
+        token::Client::new(e, &contract_address),
+        token::StellarAssetClient::new(e, &contract_address),
+    )
+}
+
+struct TestSetup<'a> {
+    env: Env,
+    admin: Address,
+    depositor: Address,
+    contributor: Address,
+    token: token::Client<'a>,
+    escrow: BountyEscrowContractClient<'a>,
+}
+
+impl<'a> TestSetup<'a> {
+    fn new() -> Self {
+        let env = Env::default();
+        env.mock_all_auths();
+
+        let admin = Address::generate(&env);
+        let depositor = Address::generate(&env);
+        let contributor = Address::generate(&env);
+
+        let (token, token_admin) = create_token_contract(&env, &admin);
+        let contract_id = env.register_contract(None, BountyEscrowContract);
+        let escrow = BountyEscrowContractClient::new(&env, &contract_id);
+        escrow.init(&admin, &token.address);
+
+        token_admin.mint(&depositor, &1_000_000);
+
+        Self {
+            env,
+            admin,
+            depositor,
+            contributor,
+            token,
+            escrow,
+        }
+    }
+
+    fn lock(&self, bounty_id: u64, amount: i128) -> u64 {
+        let deadline = self.env.ledger().timestamp() + 1000;
+        self.escrow
+            .lock_funds(&self.depositor, &bounty_id, &amount, &deadline);
+        deadline
+    }
+
+    /// Whether `address` authorized the most recent contract invocation.
+    fn authorized(&self, address: &Address) -> bool {
+        self.env.auths().iter().any(|(signer, invocation)| {
+            signer == address && matches!(invocation.function, AuthorizedFunction::Contract(_))
+        })
+    }
+}
+
+#[test]
+fn test_extend_deadline() {
+    let setup = TestSetup::new();
+    let deadline = setup.lock(1, 1_000);
+
+    setup
+        .escrow
+        .amend_escrow(&1, &Some(deadline + 5_000), &None);
+    assert!(setup.authorized(&setup.depositor));
+    assert!(!setup.authorized(&setup.admin));
+
+    let escrow = setup.escrow.get_escrow_info(&1);
+    assert_eq!(escrow.deadline, deadline + 5_000);
+    assert_eq!(escrow.amount, 1_000);
+
+    // Refund now waits for the extended deadline
+    setup.env.ledger().set_timestamp(deadline + 1);
+    assert_eq!(
+        setup.escrow.try_refund(&1),
+        Err(Ok(Error::DeadlineNotPassed))
+    );
+}
+
+#[test]
+fn test_deadline_cannot_move_into_the_past() {
+    let setup = TestSetup::new();
+    setup.lock(1, 1_000);
+    setup.env.ledger().set_timestamp(500);
+
+    let result = setup.escrow.try_amend_escrow(&1, &Some(499), &None);
+    assert_eq!(result, Err(Ok(Error::InvalidDeadline)));
+
+    // Shortening is fine as long as it stays in the future
+    setup.escrow.amend_escrow(&1, &Some(600), &None);
+    assert_eq!(setup.escrow.get_escrow_info(&1).deadline, 600);
+}
+
+#[test]
+fn test_increase_and_decrease_amount() {
+    let setup = TestSetup::new();
+    setup.lock(1, 1_000);
+
+    setup.escrow.amend_escrow(&1, &None, &Some(1_600));
+    let escrow = setup.escrow.get_escrow_info(&1);
+    assert_eq!(escrow.amount, 1_600);
+    assert_eq!(escrow.remaining_amount, 1_600);
+    assert_eq!(setup.token.balance(&setup.escrow.address), 1_600);
+
+    setup.escrow.amend_escrow(&1, &None, &Some(400));
+    let escrow = setup.escrow.get_escrow_info(&1);
+    assert_eq!(escrow.amount, 400);
+    assert_eq!(escrow.remaining_amount, 400);
+    assert_eq!(setup.token.balance(&setup.depositor), 1_000_000 - 400);
+}
+
+#[test]
+fn test_decrease_is_recorded_as_refund() {
+    let setup = TestSetup::new();
+    setup.lock(1, 1_000);
+
+    setup.escrow.amend_escrow(&1, &None, &Some(700));
+
+    let escrow = setup.escrow.get_escrow_info(&1);
+    assert_eq!(escrow.refund_history.len(), 1);
+    let record = escrow.refund_history.get(0).unwrap();
+    assert_eq!(record.amount, 300);
+    assert_eq!(record.recipient, setup.depositor);
+    assert_eq!(record.mode, RefundMode::Partial);
+
+    let refunded = setup.env.events().all().iter().any(|(_, topics, _)| {
+        topics
+            .get(0)
+            .and_then(|t| Symbol::try_from_val(&setup.env, &t).ok())
+            == Some(Symbol::new(&setup.env, "f_ref"))
+    });
+    assert!(refunded);
+}
+
+#[test]
+fn test_amount_change_respects_pause_flags() {
+    let setup = TestSetup::new();
+    let deadline = setup.lock(1, 1_000);
+
+    setup.escrow.set_paused(&Some(true), &None, &None);
+    assert_eq!(
+        setup.escrow.try_amend_escrow(&1, &None, &Some(1_500)),
+        Err(Ok(Error::FundsPaused))
+    );
+    setup.escrow.amend_escrow(&1, &None, &Some(500));
+
+    setup.escrow.set_paused(&Some(false), &None, &Some(true));
+    assert_eq!(
+        setup.escrow.try_amend_escrow(&1, &None, &Some(400)),
+        Err(Ok(Error::FundsPaused))
+    );
+    setup
+        .escrow
+        .amend_escrow(&1, &Some(deadline + 100), &Some(1_500));
+    assert_eq!(setup.escrow.get_escrow_info(&1).amount, 1_500);
+}
+
+#[test]
+fn test_amount_change_respects_policy() {
+    let setup = TestSetup::new();
+    setup.escrow.set_amount_policy(&setup.admin, &500, &2_000);
+    setup.lock(1, 1_000);
+
+    assert_eq!(
+        setup.escrow.try_amend_escrow(&1, &None, &Some(2_001)),
+        Err(Ok(Error::AmountAboveMaximum))
+    );
+    assert_eq!(
+        setup.escrow.try_amend_escrow(&1, &None, &Some(499)),
+        Err(Ok(Error::AmountBelowMinimum))
+    );
+}
+
+#[test]
+fn test_decrease_cannot_cut_into_released_funds() {
+    let setup = TestSetup::new();
+    setup.lock(1, 1_000);
+    setup.escrow.partial_release(&1, &setup.contributor, &700);
+
+    // Only 300 remains; dropping the total to 600 would need 400 back
+    assert_eq!(
+        setup.escrow.try_amend_escrow(&1, &None, &Some(600)),
+        Err(Ok(Error::InvalidAmount))
+    );
+
+    setup.escrow.amend_escrow(&1, &None, &Some(800));
+    assert_eq!(setup.escrow.get_escrow_info(&1).remaining_amount, 100);
+}
+
+#[test]
+fn test_pending_claim_requires_admin_cosignature() {
+    let setup = TestSetup::new();
+    let deadline = setup.lock(1, 1_000);
+    setup.escrow.set_claim_window(&500);
+    setup.escrow.authorize_claim(&1, &setup.contributor);
+
+    setup.escrow.amend_escrow(&1, &Some(deadline + 100), &None);
+    assert!(setup.authorized(&setup.depositor));
+    assert!(setup.authorized(&setup.admin));
+
+    // Cannot claw back funds promised to the claimant
+    assert_eq!(
+        setup.escrow.try_amend_escrow(&1, &None, &Some(999)),
+        Err(Ok(Error::InvalidAmount))
+    );
+}
+
+#[test]
+fn test_amend_settled_bounty_rejected() {
+    let setup = TestSetup::new();
+    setup.lock(1, 1_000);
+    setup.escrow.release_funds(&1, &setup.contributor);
+
+    assert_eq!(
+        setup.escrow.try_amend_escrow(&1, &Some(5_000), &None),
+        Err(Ok(Error::FundsNotLocked))
+    );
+}
@@ -279,3 +279,134 @@ fn test_auto_refund_different_users_same_result() {
         initial_balance + (amount * 2)
     );
 }
+
+#[test]
+fn test_refund_expired_sweeps_only_eligible_escrows() {
+    let setup = TestSetup::new();
+    let contributor = Address::generate(&setup.env);
+    let now = setup.env.ledger().timestamp();
+    let amount = 1000;
+
+    // 1: expired, 2: not yet expired, 3: expired but claim pending,
+    // 4: already released, 5: expired
+    setup
+        .escrow
+        .lock_funds(&setup.depositor, &1, &amount, &(now + 100));
+    setup
+        .escrow
+        .lock_funds(&setup.depositor, &2, &amount, &(now + 10_000));
+    setup
+        .escrow
+        .lock_funds(&setup.depositor, &3, &amount, &(now + 100));
+    setup
+        .escrow
+        .lock_funds(&setup.depositor, &4, &amount, &(now + 100));
+    setup
+        .escrow
+        .lock_funds(&setup.depositor, &5, &amount, &(now + 100));
+
+    setup.escrow.set_claim_window(&100_000);
+    setup.escrow.authorize_claim(&3, &contributor);
+    setup.escrow.release_funds(&4, &contributor);
+
+    setup.env.ledger().set_timestamp(now + 101);
+    let initial_balance = setup.token.balance(&setup.depositor);
+
+    let summary = setup.escrow.refund_expired(&0, &10);
+    assert_eq!(summary.scanned, 5);
+    assert_eq!(
+        summary.refunded_ids,
+        soroban_sdk::vec![&setup.env, 1u64, 5u64]
+    );
+    assert_eq!(summary.total_refunded, amount * 2);
+    assert_eq!(summary.next_offset, 5);
+
+    assert_eq!(
+        setup.token.balance(&setup.depositor),
+        initial_balance + amount * 2
+    );
+    assert_eq!(
+        setup.escrow.get_escrow_info(&1).status,
+        EscrowStatus::Refunded
+    );
+    assert_eq!(
+        setup.escrow.get_escrow_info(&2).status,
+        EscrowStatus::Locked
+    );
+    assert_eq!(
+        setup.escrow.get_escrow_info(&3).status,
+        EscrowStatus::Locked
+    );
+}
+
+#[test]
+fn test_refund_expired_paginates_over_index() {
+    let setup = TestSetup::new();
+    let deadline = setup.env.ledger().timestamp() + 100;
+
+    for bounty_id in 1..=5u64 {
+        setup
+            .escrow
+            .lock_funds(&setup.depositor, &bounty_id, &100, &deadline);
+    }
+    setup.env.ledger().set_timestamp(deadline + 1);
+
+    let first = setup.escrow.refund_expired(&0, &2);
+    assert_eq!(
+        first.refunded_ids,
+        soroban_sdk::vec![&setup.env, 1u64, 2u64]
+    );
+    assert_eq!(first.next_offset, 2);
+
+    let second = setup.escrow.refund_expired(&first.next_offset, &2);
+    assert_eq!(
+        second.refunded_ids,
+        soroban_sdk::vec![&setup.env, 3u64, 4u64]
+    );
+
+    let third = setup.escrow.refund_expired(&second.next_offset, &2);
+    assert_eq!(third.refunded_ids, soroban_sdk::vec![&setup.env, 5u64]);
+    assert_eq!(third.next_offset, 5);
+
+    // Past the end of the index there is nothing left to do
+    let done = setup.escrow.refund_expired(&third.next_offset, &2);
+    assert_eq!(done.scanned, 0);
+    assert_eq!(done.total_refunded, 0);
+}
+
+#[test]
+fn test_refund_expired_includes_batch_locked_escrows() {
+    let setup = TestSetup::new();
+    let deadline = setup.env.ledger().timestamp() + 100;
+
+    let items = soroban_sdk::vec![
+        &setup.env,
+        LockFundsItem {
+            bounty_id: 7,
+            depositor: setup.depositor.clone(),
+            amount: 300,
+            deadline,
+        },
+        LockFundsItem {
+            bounty_id: 8,
+            depositor: setup.depositor.clone(),
+            amount: 400,
+            deadline,
+        },
+    ];
+    setup.escrow.batch_lock_funds(&items, &None);
+    setup.env.ledger().set_timestamp(deadline + 1);
+
+    let summary = setup.escrow.refund_expired(&0, &10);
+    assert_eq!(summary.total_refunded, 700);
+    assert_eq!(setup.token.balance(&setup.escrow.address), 0);
+}
+
+#[test]
+fn test_refund_expired_blocked_when_refunds_paused() {
+    let setup = TestSetup::new();
+    setup.escrow.set_paused(&None, &None, &Some(true));
+
+    let result = setup.escrow.try_refund_expired(&0, &10);
+    assert_eq!(result, Err(Ok(Error::FundsPaused)));
+}
@@ -1,5 +1,8 @@
 #![cfg(test)]
-use crate::{BountyEscrowContract, BountyEscrowContractClient, Error as ContractError};
+use crate::{
+    BountyEscrowContract, BountyEscrowContractClient, Error as ContractError, LockFundsItem,
+    ReleaseFundsItem,
+};
 use soroban_sdk::testutils::Events;
 use soroban_sdk::{
     testutils::{Address as _, Ledger},
@@ -904,6 +907,303 @@ fn test_update_fee_config_rejects_over_100_percent_release_fee() {
     );
 }
 
+// ── Fee Collection Tests ──────────────────────────────────────────────────────
+
+/// The lock fee is routed to the fee recipient and only the net amount is escrowed.
+#[test]
+fn test_lock_funds_collects_lock_fee() {
+    let (env, client, _) = create_test_env();
+    let admin = Address::generate(&env);
+    let depositor = Address::generate(&env);
+    let fee_recipient = Address::generate(&env);
+    let deadline = env.ledger().timestamp() + 100;
+
+    env.mock_all_auths();
+
+    let token_admin = Address::generate(&env);
+    let (token, token_client, token_admin_client) = create_token_contract(&env, &token_admin);
+    client.init(&admin, &token);
+    token_admin_client.mint(&depositor, &10_000);
+
+    // 1% lock fee
+    client.update_fee_config(
+        &Some(100),
+        &Some(0),
+        &Some(fee_recipient.clone()),
+        &Some(true),
+    );
+    client.lock_funds(&depositor, &1, &10_000, &deadline);
+
+    let escrow = client.get_escrow_info(&1);
+    assert_eq!(escrow.amount, 9_900);
+    assert_eq!(escrow.remaining_amount, 9_900);
+    assert_eq!(token_client.balance(&fee_recipient), 100);
+    assert_eq!(token_client.balance(&client.address), 9_900);
+    assert_eq!(token_client.balance(&depositor), 0);
+}
+
+/// The release fee is deducted from the payout and routed to the fee recipient.
+#[test]
+fn test_release_funds_collects_release_fee() {
+    let (env, client, _) = create_test_env();
+    let admin = Address::generate(&env);
+    let depositor = Address::generate(&env);
+    let contributor = Address::generate(&env);
+    let fee_recipient = Address::generate(&env);
+    let deadline = env.ledger().timestamp() + 100;
+
+    env.mock_all_auths();
+
+    let token_admin = Address::generate(&env);
+    let (token, token_client, token_admin_client) = create_token_contract(&env, &token_admin);
+    client.init(&admin, &token);
+    token_admin_client.mint(&depositor, &10_000);
+
+    // 2.5% release fee
+    client.update_fee_config(
+        &Some(0),
+        &Some(250),
+        &Some(fee_recipient.clone()),
+        &Some(true),
+    );
+    client.lock_funds(&depositor, &1, &10_000, &deadline);
+    client.release_funds(&1, &contributor);
+
+    assert_eq!(token_client.balance(&contributor), 9_750);
+    assert_eq!(token_client.balance(&fee_recipient), 250);
+    assert_eq!(token_client.balance(&client.address), 0);
+
+    let escrow = client.get_escrow_info(&1);
+    assert_eq!(escrow.status, crate::EscrowStatus::Released);
+    assert_eq!(escrow.remaining_amount, 0);
+}
+
+/// Lock and release fees stack: the release fee applies to the net escrowed amount.
+#[test]
+fn test_lock_and_release_fees_combined() {
+    let (env, client, _) = create_test_env();
+    let admin = Address::generate(&env);
+    let depositor = Address::generate(&env);
+    let contributor = Address::generate(&env);
+    let fee_recipient = Address::generate(&env);
+    let deadline = env.ledger().timestamp() + 100;
+
+    env.mock_all_auths();
+
+    let token_admin = Address::generate(&env);
+    let (token, token_client, token_admin_client) = create_token_contract(&env, &token_admin);
+    client.init(&admin, &token);
+    token_admin_client.mint(&depositor, &10_000);
+
+    client.update_fee_config(
+        &Some(1_000),
+        &Some(1_000),
+        &Some(fee_recipient.clone()),
+        &Some(true),
+    );
+    client.lock_funds(&depositor, &1, &10_000, &deadline);
+    client.release_funds(&1, &contributor);
+
+    // 10% of 10_000 on lock, then 10% of 9_000 on release
+    assert_eq!(token_client.balance(&fee_recipient), 1_900);
+    assert_eq!(token_client.balance(&contributor), 8_100);
+    assert_eq!(token_client.balance(&client.address), 0);
+}
+
+/// Each partial release pays its own release fee on the slice being released.
+#[test]
+fn test_partial_release_collects_release_fee() {
+    let (env, client, _) = create_test_env();
+    let admin = Address::generate(&env);
+    let depositor = Address::generate(&env);
+    let contributor = Address::generate(&env);
+    let fee_recipient = Address::generate(&env);
+    let deadline = env.ledger().timestamp() + 100;
+
+    env.mock_all_auths();
+
+    let token_admin = Address::generate(&env);
+    let (token, token_client, token_admin_client) = create_token_contract(&env, &token_admin);
+    client.init(&admin, &token);
+    token_admin_client.mint(&depositor, &10_000);
+
+    client.update_fee_config(
+        &Some(0),
+        &Some(500),
+        &Some(fee_recipient.clone()),
+        &Some(true),
+    );
+    client.lock_funds(&depositor, &1, &10_000, &deadline);
+    client.partial_release(&1, &contributor, &4_000);
+
+    assert_eq!(token_client.balance(&contributor), 3_800);
+    assert_eq!(token_client.balance(&fee_recipient), 200);
+    assert_eq!(client.get_escrow_info(&1).remaining_amount, 6_000);
+
+    // Releasing the rest only pays out what is still owed
+    client.release_funds(&1, &contributor);
+    assert_eq!(token_client.balance(&contributor), 3_800 + 5_700);
+    assert_eq!(token_client.balance(&fee_recipient), 200 + 300);
+    assert_eq!(token_client.balance(&client.address), 0);
+}
+
+/// A claimed payout is charged the release fee at claim time.
+#[test]
+fn test_claim_collects_release_fee() {
+    let (env, client, _) = create_test_env();
+    let admin = Address::generate(&env);
+    let depositor = Address::generate(&env);
+    let contributor = Address::generate(&env);
+    let fee_recipient = Address::generate(&env);
+    let deadline = env.ledger().timestamp() + 100;
+
+    env.mock_all_auths();
+
+    let token_admin = Address::generate(&env);
+    let (token, token_client, token_admin_client) = create_token_contract(&env, &token_admin);
+    client.init(&admin, &token);
+    token_admin_client.mint(&depositor, &10_000);
+
+    client.update_fee_config(
+        &Some(0),
+        &Some(100),
+        &Some(fee_recipient.clone()),
+        &Some(true),
+    );
+    client.set_claim_window(&500);
+    client.lock_funds(&depositor, &1, &10_000, &deadline);
+    client.authorize_claim(&1, &contributor);
+    client.claim(&1);
+
+    assert_eq!(token_client.balance(&contributor), 9_900);
+    assert_eq!(token_client.balance(&fee_recipient), 100);
+    assert_eq!(client.get_escrow_info(&1).remaining_amount, 0);
+}
+
+/// Batch lock and batch release charge the same fees as their single variants.
+#[test]
+fn test_batch_operations_collect_fees() {
+    let (env, client, _) = create_test_env();
+    let admin = Address::generate(&env);
+    let depositor = Address::generate(&env);
+    let contributor = Address::generate(&env);
+    let fee_recipient = Address::generate(&env);
+    let deadline = env.ledger().timestamp() + 100;
+
+    env.mock_all_auths();
+
+    let token_admin = Address::generate(&env);
+    let (token, token_client, token_admin_client) = create_token_contract(&env, &token_admin);
+    client.init(&admin, &token);
+    token_admin_client.mint(&depositor, &20_000);
+
+    client.update_fee_config(
+        &Some(100),
+        &Some(100),
+        &Some(fee_recipient.clone()),
+        &Some(true),
+    );
+
+    let lock_items = soroban_sdk::vec![
+        &env,
+        LockFundsItem {
+            bounty_id: 1,
+            depositor: depositor.clone(),
+            amount: 10_000,
+            deadline,
+        },
+        LockFundsItem {
+            bounty_id: 2,
+            depositor: depositor.clone(),
+            amount: 10_000,
+            deadline,
+        },
+    ];
+    client.batch_lock_funds(&lock_items, &None);
+    assert_eq!(client.get_escrow_info(&1).amount, 9_900);
+    assert_eq!(client.get_escrow_info(&2).amount, 9_900);
+    assert_eq!(token_client.balance(&fee_recipient), 200);
+
+    let release_items = soroban_sdk::vec![
+        &env,
+        ReleaseFundsItem {
+            bounty_id: 1,
+            contributor: contributor.clone(),
+        },
+        ReleaseFundsItem {
+            bounty_id: 2,
+            contributor: contributor.clone(),
+        },
+    ];
+    client.batch_release_funds(&release_items);
+    assert_eq!(token_client.balance(&contributor), 2 * 9_801);
+    assert_eq!(token_client.balance(&fee_recipient), 200 + 2 * 99);
+    assert_eq!(token_client.balance(&client.address), 0);
+}
+
+/// Configured rates are ignored while `fee_enabled` is false.
+#[test]
+fn test_fees_not_collected_when_disabled() {
+    let (env, client, _) = create_test_env();
+    let admin = Address::generate(&env);
+    let depositor = Address::generate(&env);
+    let contributor = Address::generate(&env);
+    let fee_recipient = Address::generate(&env);
+    let deadline = env.ledger().timestamp() + 100;
+
+    env.mock_all_auths();
+
+    let token_admin = Address::generate(&env);
+    let (token, token_client, token_admin_client) = create_token_contract(&env, &token_admin);
+    client.init(&admin, &token);
+    token_admin_client.mint(&depositor, &10_000);
+
+    client.update_fee_config(
+        &Some(500),
+        &Some(500),
+        &Some(fee_recipient.clone()),
+        &Some(false),
+    );
+    client.lock_funds(&depositor, &1, &10_000, &deadline);
+    client.release_funds(&1, &contributor);
+
+    assert_eq!(token_client.balance(&contributor), 10_000);
+    assert_eq!(token_client.balance(&fee_recipient), 0);
+}
+
+/// Fee events carry the v2 version tag like every other current emitter.
+#[test]
+fn test_fee_collected_event_is_versioned() {
+    let (env, client, contract_id) = create_test_env();
+    let admin = Address::generate(&env);
+    let depositor = Address::generate(&env);
+    let fee_recipient = Address::generate(&env);
+    let deadline = env.ledger().timestamp() + 100;
+
+    env.mock_all_auths();
+
+    let token_admin = Address::generate(&env);
+    let (token, _token_client, token_admin_client) = create_token_contract(&env, &token_admin);
+    client.init(&admin, &token);
+    token_admin_client.mint(&depositor, &10_000);
+
+    client.update_fee_config(&Some(100), &Some(0), &Some(fee_recipient), &Some(true));
+    client.lock_funds(&depositor, &1, &10_000, &deadline);
+
+    let mut fee_events = 0;
+    for (contract, topics, data) in env.events().all().iter() {
+        if contract != contract_id {
+            continue;
+        }
+        let topic = Symbol::try_from_val(&env, &topics.get(0).unwrap());
+        if topic == Ok(Symbol::new(&env, "fee")) {
+            assert_event_data_has_v2_tag(&env, &data);
+            fee_events += 1;
+        }
+    }
+    assert_eq!(fee_events, 1);
+}
+
 // ── Min/Max Amount Policy Enforcement Tests ───────────────────────────────────
 
 /// Locking an amount strictly below the configured minimum must be rejected.
@@ -1,9 +1,9 @@
 #![cfg(test)]
 
-use crate::{BountyEscrowContract, BountyEscrowContractClient, EscrowStatus};
+use crate::{BountyEscrowContract, BountyEscrowContractClient, Error, EscrowStatus, RefundMode};
 use soroban_sdk::{
     testutils::{Address as _, Ledger},
-    token, Address, Env,
+    token, vec, Address, BytesN, Env,
 };
 
 fn create_token_contract<'a>(
@@ -27,6 +27,7 @@ struct TestSetup<'a> {
     admin: Address,
     depositor: Address,
     contributor: Address,
+    arbiter: Address,
     token: token::Client<'a>,
     token_admin: token::StellarAssetClient<'a>,
     escrow: BountyEscrowContractClient<'a>,
@@ -40,11 +41,13 @@ impl<'a> TestSetup<'a> {
         let admin = Address::generate(&env);
         let depositor = Address::generate(&env);
         let contributor = Address::generate(&env);
+        let arbiter = Address::generate(&env);
 
         let (token, token_admin) = create_token_contract(&env, &admin);
         let escrow = create_escrow_contract(&env);
 
         escrow.init(&admin, &token.address);
+        escrow.set_arbiters(&vec![&env, arbiter.clone()]);
 
         // Mint tokens to depositor
         token_admin.mint(&depositor, &10_000_000);
@@ -54,6 +57,7 @@ impl<'a> TestSetup<'a> {
             admin,
             depositor,
             contributor,
+            arbiter,
             token,
             token_admin,
             escrow,
@@ -61,9 +65,9 @@ impl<'a> TestSetup<'a> {
     }
 }
 
-// Vulnerability: pending claims don't block refunds
+// A pending claim blocks the deadline refund until it is taken or cancelled
 #[test]
-fn test_pending_claim_does_not_block_refund_vulnerability() {
+fn test_pending_claim_blocks_refund() {
     let setup = TestSetup::new();
     let bounty_id = 1;
     let amount = 1000;
@@ -78,7 +82,7 @@ fn test_pending_claim_does_not_block_refund_vulnerability() {
         .escrow
         .lock_funds(&setup.depositor, &bounty_id, &amount, &deadline);
 
-    // Admin opens dispute by authorizing claim (before deadline)
+    // Admin authorizes a claim before the deadline
     setup.escrow.authorize_claim(&bounty_id, &setup.contributor);
 
     // Verify claim is pending
@@ -89,16 +93,48 @@ fn test_pending_claim_does_not_block_refund_vulnerability() {
     // Advance time PAST deadline
     setup.env.ledger().set_timestamp(deadline + 100);
 
-    // VULNERABILITY: Refund succeeds even though claim is pending
-    // This allows depositor to bypass the dispute
-    setup.escrow.refund(&bounty_id);
+    let result = setup.escrow.try_refund(&bounty_id);
+    assert_eq!(result, Err(Ok(Error::ClaimPending)));
 
-    // Verify funds were refunded
     let escrow = setup.escrow.get_escrow_info(&bounty_id);
-    assert_eq!(escrow.status, EscrowStatus::Refunded);
-    assert_eq!(setup.token.balance(&setup.escrow.address), 0);
-    assert_eq!(setup.token.balance(&setup.depositor), 10_000_000);
-    assert_eq!(setup.token.balance(&setup.contributor), 0);
+    assert_eq!(escrow.status, EscrowStatus::Locked);
+    assert_eq!(setup.token.balance(&setup.escrow.address), amount);
+    assert_eq!(setup.token.balance(&setup.depositor), 10_000_000 - amount);
+}
+
+// An approved early refund cannot pull funds out from under a pending claim
+#[test]
+fn test_approved_refund_then_claim_pays_once() {
+    let setup = TestSetup::new();
+    let bounty_id = 12;
+    let amount = 1000;
+    let deadline = setup.env.ledger().timestamp() + 1000;
+
+    setup.escrow.set_claim_window(&500);
+    // Another bounty's funds sit in the same contract
+    setup
+        .escrow
+        .lock_funds(&setup.depositor, &13, &4000, &deadline);
+    setup
+        .escrow
+        .lock_funds(&setup.depositor, &bounty_id, &amount, &deadline);
+    setup.escrow.authorize_claim(&bounty_id, &setup.contributor);
+    setup
+        .escrow
+        .approve_refund(&bounty_id, &amount, &setup.depositor, &RefundMode::Full);
+
+    let result = setup.escrow.try_refund(&bounty_id);
+    assert_eq!(result, Err(Ok(Error::ClaimPending)));
+
+    setup.escrow.claim(&bounty_id);
+    assert_eq!(setup.token.balance(&setup.contributor), amount);
+    let escrow = setup.escrow.get_escrow_info(&bounty_id);
+    assert_eq!(escrow.status, EscrowStatus::Released);
+    assert_eq!(escrow.remaining_amount, 0);
+
+    // Nothing is left to refund, and the other bounty is untouched
+    assert!(setup.escrow.try_refund(&bounty_id).is_err());
+    assert_eq!(setup.token.balance(&setup.escrow.address), 4000);
 }
 
 // Beneficiary claims successfully within dispute window
@@ -406,3 +442,376 @@ fn test_claim_cancellation_restores_refund_eligibility() {
 
     assert_eq!(setup.token.balance(&setup.depositor), 10_000_000);
 }
+
+// =============================================================================
+// Dispute resolution
+// =============================================================================
+
+fn evidence(env: &Env, seed: u8) -> BytesN<32> {
+    BytesN::from_array(env, &[seed; 32])
+}
+
+// Depositor opens a dispute: release, refund and claim are all frozen
+#[test]
+fn test_open_dispute_freezes_release_refund_and_claim() {
+    let setup = TestSetup::new();
+    let bounty_id = 20;
+    let deadline = setup.env.ledger().timestamp() + 1000;
+
+    setup.escrow.set_claim_window(&500);
+    setup
+        .escrow
+        .lock_funds(&setup.depositor, &bounty_id, &2000, &deadline);
+    setup.escrow.authorize_claim(&bounty_id, &setup.contributor);
+
+    setup
+        .escrow
+        .open_dispute(&bounty_id, &setup.depositor, &evidence(&setup.env, 1));
+
+    let escrow = setup.escrow.get_escrow_info(&bounty_id);
+    assert_eq!(escrow.status, EscrowStatus::Disputed);
+    let dispute = setup.escrow.get_dispute(&bounty_id);
+    assert_eq!(dispute.opened_by, setup.depositor);
+    assert_eq!(dispute.evidence_hash, evidence(&setup.env, 1));
+
+    assert_eq!(
+        setup.escrow.try_claim(&bounty_id),
+        Err(Ok(Error::EscrowDisputed))
+    );
+    assert_eq!(
+        setup
+            .escrow
+            .try_release_funds(&bounty_id, &setup.contributor),
+        Err(Ok(Error::EscrowDisputed))
+    );
+    assert_eq!(
+        setup
+            .escrow
+            .try_partial_release(&bounty_id, &setup.contributor, &100),
+        Err(Ok(Error::EscrowDisputed))
+    );
+
+    setup.env.ledger().set_timestamp(deadline + 1);
+    assert_eq!(
+        setup.escrow.try_refund(&bounty_id),
+        Err(Ok(Error::EscrowDisputed))
+    );
+    assert_eq!(setup.token.balance(&setup.escrow.address), 2000);
+}
+
+// The contributor named in the pending claim can also open a dispute
+#[test]
+fn test_pending_claim_recipient_can_open_dispute() {
+    let setup = TestSetup::new();
+    let bounty_id = 21;
+    let deadline = setup.env.ledger().timestamp() + 1000;
+
+    setup.escrow.set_claim_window(&500);
+    setup
+        .escrow
+        .lock_funds(&setup.depositor, &bounty_id, &2000, &deadline);
+    setup.escrow.authorize_claim(&bounty_id, &setup.contributor);
+
+    setup
+        .escrow
+        .open_dispute(&bounty_id, &setup.contributor, &evidence(&setup.env, 2));
+
+    let dispute = setup.escrow.get_dispute(&bounty_id);
+    assert_eq!(dispute.opened_by, setup.contributor);
+}
+
+// The admin cannot strip the claim from under an open dispute
+#[test]
+fn test_pending_claim_cannot_be_cancelled_during_dispute() {
+    let setup = TestSetup::new();
+    let bounty_id = 24;
+    let deadline = setup.env.ledger().timestamp() + 1000;
+
+    setup.escrow.set_claim_window(&500);
+    setup
+        .escrow
+        .lock_funds(&setup.depositor, &bounty_id, &2000, &deadline);
+    setup.escrow.authorize_claim(&bounty_id, &setup.contributor);
+    setup
+        .escrow
+        .open_dispute(&bounty_id, &setup.contributor, &evidence(&setup.env, 4));
+
+    let result = setup.escrow.try_cancel_pending_claim(&bounty_id);
+    assert_eq!(result, Err(Ok(Error::EscrowDisputed)));
+
+    let claim = setup.escrow.get_pending_claim(&bounty_id);
+    assert_eq!(claim.recipient, setup.contributor);
+    assert!(!claim.claimed);
+}
+
+// Unrelated addresses cannot freeze a bounty
+#[test]
+fn test_third_party_cannot_open_dispute() {
+    let setup = TestSetup::new();
+    let bounty_id = 22;
+    let deadline = setup.env.ledger().timestamp() + 1000;
+    let stranger = Address::generate(&setup.env);
+
+    setup
+        .escrow
+        .lock_funds(&setup.depositor, &bounty_id, &2000, &deadline);
+
+    let result = setup
+        .escrow
+        .try_open_dispute(&bounty_id, &stranger, &evidence(&setup.env, 3));
+    assert_eq!(result, Err(Ok(Error::Unauthorized)));
+
+    let result =
+        setup
+            .escrow
+            .try_open_dispute(&bounty_id, &setup.contributor, &evidence(&setup.env, 3));
+    assert_eq!(result, Err(Ok(Error::Unauthorized)));
+}
+
+// A second dispute on the same bounty is rejected
+#[test]
+fn test_cannot_open_dispute_twice() {
+    let setup = TestSetup::new();
+    let bounty_id = 23;
+    let deadline = setup.env.ledger().timestamp() + 1000;
+
+    setup
+        .escrow
+        .lock_funds(&setup.depositor, &bounty_id, &2000, &deadline);
+    setup
+        .escrow
+        .open_dispute(&bounty_id, &setup.depositor, &evidence(&setup.env, 4));
+
+    let result =
+        setup
+            .escrow
+            .try_open_dispute(&bounty_id, &setup.depositor, &evidence(&setup.env, 5));
+    assert_eq!(result, Err(Ok(Error::EscrowDisputed)));
+}
+
+// Arbiter splits the remaining funds between contributor and depositor
+#[test]
+fn test_arbiter_resolves_dispute_with_split() {
+    let setup = TestSetup::new();
+    let bounty_id = 24;
+    let deadline = setup.env.ledger().timestamp() + 1000;
+    let arbiter = Address::generate(&setup.env);
+
+    setup
+        .escrow
+        .set_arbiters(&vec![&setup.env, arbiter.clone()]);
+    setup.escrow.set_claim_window(&500);
+    setup
+        .escrow
+        .lock_funds(&setup.depositor, &bounty_id, &2000, &deadline);
+    setup.escrow.authorize_claim(&bounty_id, &setup.contributor);
+    setup
+        .escrow
+        .open_dispute(&bounty_id, &setup.contributor, &evidence(&setup.env, 6));
+
+    setup
+        .escrow
+        .resolve_dispute(&bounty_id, &arbiter, &setup.contributor, &1500);
+
+    assert_eq!(setup.token.balance(&setup.contributor), 1500);
+    assert_eq!(setup.token.balance(&setup.depositor), 10_000_000 - 1500);
+    assert_eq!(setup.token.balance(&setup.escrow.address), 0);
+
+    let escrow = setup.escrow.get_escrow_info(&bounty_id);
+    assert_eq!(escrow.status, EscrowStatus::Released);
+    assert_eq!(escrow.remaining_amount, 0);
+    assert_eq!(escrow.refund_history.len(), 1);
+
+    // Dispute and the stale pending claim are cleared
+    assert_eq!(
+        setup.escrow.try_get_dispute(&bounty_id),
+        Err(Ok(Error::DisputeNotFound))
+    );
+    assert!(setup.escrow.try_get_pending_claim(&bounty_id).is_err());
+}
+
+// Resolving entirely in the depositor's favour refunds everything
+#[test]
+fn test_arbiter_resolves_dispute_in_favour_of_depositor() {
+    let setup = TestSetup::new();
+    let bounty_id = 25;
+    let deadline = setup.env.ledger().timestamp() + 1000;
+    let arbiter = Address::generate(&setup.env);
+
+    setup
+        .escrow
+        .set_arbiters(&vec![&setup.env, arbiter.clone()]);
+    setup
+        .escrow
+        .lock_funds(&setup.depositor, &bounty_id, &2000, &deadline);
+    setup
+        .escrow
+        .open_dispute(&bounty_id, &setup.depositor, &evidence(&setup.env, 7));
+
+    setup
+        .escrow
+        .resolve_dispute(&bounty_id, &arbiter, &setup.contributor, &0);
+
+    assert_eq!(setup.token.balance(&setup.depositor), 10_000_000);
+    assert_eq!(setup.token.balance(&setup.contributor), 0);
+    let escrow = setup.escrow.get_escrow_info(&bounty_id);
+    assert_eq!(escrow.status, EscrowStatus::Refunded);
+}
+
+// Only configured arbiters can resolve, and only within the remaining amount
+#[test]
+fn test_resolve_dispute_rejects_non_arbiter_and_overpayment() {
+    let setup = TestSetup::new();
+    let bounty_id = 26;
+    let deadline = setup.env.ledger().timestamp() + 1000;
+    let arbiter = Address::generate(&setup.env);
+
+    setup
+        .escrow
+        .set_arbiters(&vec![&setup.env, arbiter.clone()]);
+    setup
+        .escrow
+        .lock_funds(&setup.depositor, &bounty_id, &2000, &deadline);
+
+    // Nothing to resolve yet
+    let result = setup
+        .escrow
+        .try_resolve_dispute(&bounty_id, &arbiter, &setup.contributor, &100);
+    assert_eq!(result, Err(Ok(Error::DisputeNotFound)));
+
+    setup
+        .escrow
+        .open_dispute(&bounty_id, &setup.depositor, &evidence(&setup.env, 8));
+
+    let result =
+        setup
+            .escrow
+            .try_resolve_dispute(&bounty_id, &setup.admin, &setup.contributor, &100);
+    assert_eq!(result, Err(Ok(Error::Unauthorized)));
+
+    let result = setup
+        .escrow
+        .try_resolve_dispute(&bounty_id, &arbiter, &setup.contributor, &2001);
+    assert_eq!(result, Err(Ok(Error::InvalidAmount)));
+}
+
+// Without arbiters a dispute could never be resolved
+#[test]
+fn test_open_dispute_requires_arbiters() {
+    let setup = TestSetup::new();
+    let bounty_id = 27;
+    let deadline = setup.env.ledger().timestamp() + 1000;
+
+    setup.escrow.set_arbiters(&vec![&setup.env]);
+    setup
+        .escrow
+        .lock_funds(&setup.depositor, &bounty_id, &2000, &deadline);
+
+    let result =
+        setup
+            .escrow
+            .try_open_dispute(&bounty_id, &setup.depositor, &evidence(&setup.env, 9));
+    assert_eq!(result, Err(Ok(Error::NoArbiters)));
+}
+
+// The arbiter can only pay a contributor named in a pending claim
+#[test]
+fn test_resolve_dispute_payee_must_be_claim_recipient() {
+    let setup = TestSetup::new();
+    let bounty_id = 28;
+    let deadline = setup.env.ledger().timestamp() + 1000;
+    let stranger = Address::generate(&setup.env);
+
+    setup.escrow.set_claim_window(&500);
+    setup
+        .escrow
+        .lock_funds(&setup.depositor, &bounty_id, &2000, &deadline);
+    setup.escrow.authorize_claim(&bounty_id, &setup.contributor);
+    setup
+        .escrow
+        .open_dispute(&bounty_id, &setup.depositor, &evidence(&setup.env, 10));
+
+    let result = setup
+        .escrow
+        .try_resolve_dispute(&bounty_id, &setup.arbiter, &stranger, &1000);
+    assert_eq!(result, Err(Ok(Error::DisputePayeeMismatch)));
+
+    setup
+        .escrow
+        .resolve_dispute(&bounty_id, &setup.arbiter, &setup.contributor, &1000);
+    assert_eq!(setup.token.balance(&setup.contributor), 1000);
+    assert_eq!(setup.token.balance(&stranger), 0);
+}
+
+// With no claim on record the arbiter can only refund
+#[test]
+fn test_resolve_dispute_without_claim_only_refunds() {
+    let setup = TestSetup::new();
+    let bounty_id = 29;
+    let deadline = setup.env.ledger().timestamp() + 1000;
+
+    setup
+        .escrow
+        .lock_funds(&setup.depositor, &bounty_id, &2000, &deadline);
+    setup
+        .escrow
+        .open_dispute(&bounty_id, &setup.depositor, &evidence(&setup.env, 11));
+
+    let result =
+        setup
+            .escrow
+            .try_resolve_dispute(&bounty_id, &setup.arbiter, &setup.contributor, &500);
+    assert_eq!(result, Err(Ok(Error::DisputePayeeMismatch)));
+}
+
+// An unresolved dispute lapses after the window and the escrow is unfrozen
+#[test]
+fn test_dispute_lapses_after_window() {
+    let setup = TestSetup::new();
+    let bounty_id = 30;
+    let start = setup.env.ledger().timestamp();
+    let deadline = start + 10_000;
+
+    setup.escrow.set_claim_window(&50_000);
+    setup.escrow.set_dispute_window(&1000);
+    setup
+        .escrow
+        .lock_funds(&setup.depositor, &bounty_id, &2000, &deadline);
+    setup.escrow.authorize_claim(&bounty_id, &setup.contributor);
+    setup
+        .escrow
+        .open_dispute(&bounty_id, &setup.depositor, &evidence(&setup.env, 12));
+    assert_eq!(
+        setup.escrow.get_dispute(&bounty_id).resolve_by,
+        start + 1000
+    );
+
+    // Too early to lapse
+    assert_eq!(
+        setup.escrow.try_expire_dispute(&bounty_id),
+        Err(Ok(Error::DeadlineNotPassed))
+    );
+
+    setup.env.ledger().set_timestamp(start + 1001);
+
+    // The arbiter missed the window
+    let result =
+        setup
+            .escrow
+            .try_resolve_dispute(&bounty_id, &setup.arbiter, &setup.contributor, &0);
+    assert_eq!(result, Err(Ok(Error::DisputeExpired)));
+
+    setup.escrow.expire_dispute(&bounty_id);
+    assert_eq!(
+        setup.escrow.get_escrow_info(&bounty_id).status,
+        EscrowStatus::Locked
+    );
+    assert_eq!(
+        setup.escrow.try_get_dispute(&bounty_id),
+        Err(Ok(Error::DisputeNotFound))
+    );
+
+    // The pending claim survived and can now be paid out
+    setup.escrow.claim(&bounty_id);
+    assert_eq!(setup.token.balance(&setup.contributor), 2000);
+}
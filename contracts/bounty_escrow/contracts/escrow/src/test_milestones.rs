@@ -0,0 +1,367 @@
+#![cfg(test)]
+
+use super::*;
+use soroban_sdk::{
+    testutils::{Address as _, Ledger},
+    token, vec, Address, BytesN, Env,
+};
+
+fn create_token_contract<'a>(
+    e: &Env,
+    admin: &Address,
+) -> (token::Client<'a>, token::StellarAssetClient<'a>) {
+    let contract_address = e
+        .register_stellar_asset_contract_v2(admin.clone())
+        .address();
+    (
+        token::Client::new(e, &contract_address),
+        token::StellarAssetClient::new(e, &contract_address),
+    )
+}
+
+struct TestSetup<'a> {
+    env: Env,
+    depositor: Address,
+    contributor: Address,
+    token: token::Client<'a>,
+    escrow: BountyEscrowContractClient<'a>,
+}
+
+impl<'a> TestSetup<'a> {
+    fn new() -> Self {
+        let env = Env::default();
+        env.mock_all_auths();
+
+        let admin = Address::generate(&env);
+        let depositor = Address::generate(&env);
+        let contributor = Address::generate(&env);
+
+        let (token, token_admin) = create_token_contract(&env, &admin);
+        let contract_id = env.register_contract(None, BountyEscrowContract);
+        let escrow = BountyEscrowContractClient::new(&env, &contract_id);
+        escrow.init(&admin, &token.address);
+
+        token_admin.mint(&depositor, &1_000_000);
+
+        Self {
+            env,
+            depositor,
+            contributor,
+            token,
+            escrow,
+        }
+    }
+
+    fn spec(&self, seed: u8, amount: i128, deadline: Option<u64>) -> MilestoneSpec {
+        MilestoneSpec {
+            label_hash: BytesN::from_array(&self.env, &[seed; 32]),
+            amount,
+            deadline,
+        }
+    }
+
+    /// Lock a three-stage bounty of 1_000 + 2_000 + 3_000 with a deadline 1000s out.
+    fn lock_three_stages(&self, bounty_id: u64) -> u64 {
+        let deadline = self.env.ledger().timestamp() + 1000;
+        let milestones = vec![
+            &self.env,
+            self.spec(1, 1_000, Some(deadline - 500)),
+            self.spec(2, 2_000, None),
+            self.spec(3, 3_000, None),
+        ];
+        self.escrow.lock_funds_with_milestones(
+            &self.depositor,
+            &bounty_id,
+            &6_000,
+            &deadline,
+            &milestones,
+        );
+        deadline
+    }
+}
+
+#[test]
+fn test_lock_with_milestones_records_stages() {
+    let setup = TestSetup::new();
+    setup.lock_three_stages(1);
+
+    let escrow = setup.escrow.get_escrow_info(&1);
+    assert_eq!(escrow.amount, 6_000);
+    assert_eq!(escrow.milestones.len(), 3);
+    assert_eq!(escrow.milestones.get(1).unwrap().amount, 2_000);
+    for milestone in escrow.milestones.iter() {
+        assert_eq!(milestone.status, MilestoneStatus::Pending);
+    }
+    assert_eq!(setup.token.balance(&setup.escrow.address), 6_000);
+}
+
+#[test]
+fn test_lock_with_milestones_rejects_mismatched_total() {
+    let setup = TestSetup::new();
+    let deadline = setup.env.ledger().timestamp() + 1000;
+    let milestones = vec![
+        &setup.env,
+        setup.spec(1, 1_000, None),
+        setup.spec(2, 2_000, None),
+    ];
+
+    let result = setup.escrow.try_lock_funds_with_milestones(
+        &setup.depositor,
+        &1,
+        &5_000,
+        &deadline,
+        &milestones,
+    );
+    assert_eq!(result, Err(Ok(Error::MilestoneAmountMismatch)));
+
+    let empty: Vec<MilestoneSpec> = vec![&setup.env];
+    let result = setup.escrow.try_lock_funds_with_milestones(
+        &setup.depositor,
+        &1,
+        &5_000,
+        &deadline,
+        &empty,
+    );
+    assert_eq!(result, Err(Ok(Error::InvalidBatchSize)));
+
+    let zero = vec![
+        &setup.env,
+        setup.spec(1, 0, None),
+        setup.spec(2, 5_000, None),
+    ];
+    let result =
+        setup
+            .escrow
+            .try_lock_funds_with_milestones(&setup.depositor, &1, &5_000, &deadline, &zero);
+    assert_eq!(result, Err(Ok(Error::InvalidAmount)));
+}
+
+#[test]
+fn test_release_milestones_one_by_one() {
+    let setup = TestSetup::new();
+    setup.lock_three_stages(1);
+
+    setup.escrow.release_milestone(&1, &1, &setup.contributor);
+    assert_eq!(setup.token.balance(&setup.contributor), 2_000);
+
+    let escrow = setup.escrow.get_escrow_info(&1);
+    assert_eq!(escrow.remaining_amount, 4_000);
+    assert_eq!(escrow.status, EscrowStatus::Locked);
+    assert_eq!(
+        escrow.milestones.get(1).unwrap().status,
+        MilestoneStatus::Released
+    );
+
+    // A stage can only be paid once
+    let result = setup
+        .escrow
+        .try_release_milestone(&1, &1, &setup.contributor);
+    assert_eq!(result, Err(Ok(Error::MilestoneNotPending)));
+
+    setup.escrow.release_milestone(&1, &0, &setup.contributor);
+    setup.escrow.release_milestone(&1, &2, &setup.contributor);
+    assert_eq!(setup.token.balance(&setup.contributor), 6_000);
+
+    let escrow = setup.escrow.get_escrow_info(&1);
+    assert_eq!(escrow.remaining_amount, 0);
+    assert_eq!(escrow.status, EscrowStatus::Released);
+}
+
+#[test]
+fn test_release_milestone_out_of_range() {
+    let setup = TestSetup::new();
+    setup.lock_three_stages(1);
+
+    let result = setup
+        .escrow
+        .try_release_milestone(&1, &3, &setup.contributor);
+    assert_eq!(result, Err(Ok(Error::MilestoneNotFound)));
+}
+
+#[test]
+fn test_whole_escrow_release_rejected_for_milestone_bounty() {
+    let setup = TestSetup::new();
+    setup.lock_three_stages(1);
+
+    assert_eq!(
+        setup.escrow.try_release_funds(&1, &setup.contributor),
+        Err(Ok(Error::MilestoneReleaseRequired))
+    );
+    assert_eq!(
+        setup
+            .escrow
+            .try_partial_release(&1, &setup.contributor, &500),
+        Err(Ok(Error::MilestoneReleaseRequired))
+    );
+    assert_eq!(
+        setup.escrow.try_authorize_claim(&1, &setup.contributor),
+        Err(Ok(Error::MilestoneReleaseRequired))
+    );
+}
+
+#[test]
+fn test_refund_milestone_respects_stage_deadline() {
+    let setup = TestSetup::new();
+    let deadline = setup.lock_three_stages(1);
+
+    // First stage has its own, earlier deadline
+    let result = setup.escrow.try_refund_milestone(&1, &0);
+    assert_eq!(result, Err(Ok(Error::DeadlineNotPassed)));
+
+    setup.env.ledger().set_timestamp(deadline - 500);
+    setup.escrow.refund_milestone(&1, &0);
+    assert_eq!(setup.token.balance(&setup.depositor), 1_000_000 - 5_000);
+
+    // Later stages fall back to the escrow deadline
+    let result = setup.escrow.try_refund_milestone(&1, &1);
+    assert_eq!(result, Err(Ok(Error::DeadlineNotPassed)));
+
+    // Remaining stages can still be released
+    setup.escrow.release_milestone(&1, &1, &setup.contributor);
+
+    let escrow = setup.escrow.get_escrow_info(&1);
+    assert_eq!(escrow.status, EscrowStatus::PartiallyRefunded);
+    assert_eq!(escrow.remaining_amount, 3_000);
+    assert_eq!(escrow.refund_history.len(), 1);
+    assert_eq!(
+        escrow.milestones.get(0).unwrap().status,
+        MilestoneStatus::Refunded
+    );
+
+    setup.env.ledger().set_timestamp(deadline);
+    setup.escrow.refund_milestone(&1, &2);
+    let escrow = setup.escrow.get_escrow_info(&1);
+    assert_eq!(escrow.status, EscrowStatus::Refunded);
+    assert_eq!(escrow.remaining_amount, 0);
+}
+
+#[test]
+fn test_deadline_refund_returns_pending_milestones() {
+    let setup = TestSetup::new();
+    let deadline = setup.lock_three_stages(1);
+
+    setup.escrow.release_milestone(&1, &0, &setup.contributor);
+
+    setup.env.ledger().set_timestamp(deadline + 1);
+    setup.escrow.refund(&1);
+
+    assert_eq!(setup.token.balance(&setup.depositor), 1_000_000 - 1_000);
+    let escrow = setup.escrow.get_escrow_info(&1);
+    assert_eq!(escrow.status, EscrowStatus::Refunded);
+    assert_eq!(
+        escrow.milestones.get(0).unwrap().status,
+        MilestoneStatus::Released
+    );
+    assert_eq!(
+        escrow.milestones.get(1).unwrap().status,
+        MilestoneStatus::Refunded
+    );
+    assert_eq!(
+        escrow.milestones.get(2).unwrap().status,
+        MilestoneStatus::Refunded
+    );
+}
+
+#[test]
+fn test_deadline_refund_skips_stages_with_later_deadlines() {
+    let setup = TestSetup::new();
+    let deadline = setup.env.ledger().timestamp() + 1000;
+    let milestones = vec![
+        &setup.env,
+        setup.spec(1, 1_000, None),
+        setup.spec(2, 2_000, Some(deadline + 1000)),
+    ];
+    setup
+        .escrow
+        .lock_funds_with_milestones(&setup.depositor, &1, &3_000, &deadline, &milestones);
+
+    // Only the first stage is due at the escrow deadline
+    setup.env.ledger().set_timestamp(deadline);
+    setup.escrow.refund(&1);
+
+    assert_eq!(setup.token.balance(&setup.depositor), 1_000_000 - 2_000);
+    let escrow = setup.escrow.get_escrow_info(&1);
+    assert_eq!(escrow.status, EscrowStatus::PartiallyRefunded);
+    assert_eq!(escrow.remaining_amount, 2_000);
+    assert_eq!(
+        escrow.milestones.get(1).unwrap().status,
+        MilestoneStatus::Pending
+    );
+
+    // Nothing else is due yet, and the later stage can still be paid out
+    assert_eq!(
+        setup.escrow.try_refund(&1),
+        Err(Ok(Error::DeadlineNotPassed))
+    );
+    setup.escrow.release_milestone(&1, &1, &setup.contributor);
+    assert_eq!(setup.token.balance(&setup.contributor), 2_000);
+    assert_eq!(
+        setup.escrow.get_escrow_info(&1).status,
+        EscrowStatus::Released
+    );
+}
+
+#[test]
+fn test_refund_sweep_only_returns_due_stages() {
+    let setup = TestSetup::new();
+    let deadline = setup.lock_three_stages(1);
+
+    // Before the escrow deadline only the first stage is due
+    setup.env.ledger().set_timestamp(deadline - 500);
+    let summary = setup.escrow.refund_expired(&0, &10);
+    assert_eq!(summary.refunded_ids, vec![&setup.env, 1]);
+    assert_eq!(summary.total_refunded, 1_000);
+
+    let escrow = setup.escrow.get_escrow_info(&1);
+    assert_eq!(escrow.remaining_amount, 5_000);
+    assert_eq!(escrow.status, EscrowStatus::PartiallyRefunded);
+    assert_eq!(
+        escrow.milestones.get(1).unwrap().status,
+        MilestoneStatus::Pending
+    );
+
+    // The partially refunded escrow is swept again once the rest is due
+    setup.env.ledger().set_timestamp(deadline);
+    let summary = setup.escrow.refund_expired(&0, &10);
+    assert_eq!(summary.refunded_ids, vec![&setup.env, 1]);
+    assert_eq!(summary.total_refunded, 5_000);
+    let escrow = setup.escrow.get_escrow_info(&1);
+    assert_eq!(escrow.remaining_amount, 0);
+    assert_eq!(escrow.status, EscrowStatus::Refunded);
+    assert_eq!(setup.token.balance(&setup.depositor), 1_000_000);
+
+    // Nothing is left to sweep
+    let summary = setup.escrow.refund_expired(&0, &10);
+    assert_eq!(summary.refunded_ids.len(), 0);
+}
+
+#[test]
+fn test_milestones_scaled_by_lock_fee() {
+    let setup = TestSetup::new();
+    let fee_recipient = Address::generate(&setup.env);
+    // 3.33% lock fee
+    setup
+        .escrow
+        .update_fee_config(&Some(333), &None, &Some(fee_recipient.clone()), &Some(true));
+
+    let deadline = setup.env.ledger().timestamp() + 1000;
+    let milestones = vec![
+        &setup.env,
+        setup.spec(1, 1_000, None),
+        setup.spec(2, 1_000, None),
+        setup.spec(3, 1_000, None),
+    ];
+    setup
+        .escrow
+        .lock_funds_with_milestones(&setup.depositor, &1, &3_000, &deadline, &milestones);
+
+    // 3_000 - 99 fee = 2_901, split 967 / 967 / 967
+    let escrow = setup.escrow.get_escrow_info(&1);
+    assert_eq!(escrow.amount, 2_901);
+    let mut total = 0;
+    for milestone in escrow.milestones.iter() {
+        total += milestone.amount;
+    }
+    assert_eq!(total, escrow.amount);
+    assert_eq!(setup.token.balance(&fee_recipient), 99);
+}
@@ -0,0 +1,239 @@
+#![cfg(test)]
+
+use super::*;
+use soroban_sdk::{
+    testutils::{Address as _, Ledger},
+    token, vec, Address, Env,
+};
+
+fn create_token_contract<'a>(
+    e: &Env,
+    admin: &Address,
+) -> (token::Client<'a>, token::StellarAssetClient<'a>) {
+    let contract_address = e
+        .register_stellar_asset_contract_v2(admin.clone())
+        .address();
+    (
+        token::Client::new(e, &contract_address),
+        token::StellarAssetClient::new(e, &contract_address),
+    )
+}
+
+struct TestSetup<'a> {
+    env: Env,
+    depositor: Address,
+    contributor: Address,
+    usdc: token::Client<'a>,
+    xlm: token::Client<'a>,
+    escrow: BountyEscrowContractClient<'a>,
+}
+
+impl<'a> TestSetup<'a> {
+    /// Escrow initialized with `usdc` as the default token and `xlm` allowlisted.
+    fn new() -> Self {
+        let env = Env::default();
+        env.mock_all_auths();
+
+        let admin = Address::generate(&env);
+        let depositor = Address::generate(&env);
+        let contributor = Address::generate(&env);
+
+        let (usdc, usdc_admin) = create_token_contract(&env, &admin);
+        let (xlm, xlm_admin) = create_token_contract(&env, &admin);
+
+        let contract_id = env.register_contract(None, BountyEscrowContract);
+        let escrow = BountyEscrowContractClient::new(&env, &contract_id);
+        escrow.init(&admin, &usdc.address);
+        escrow.add_allowed_token(&xlm.address);
+
+        usdc_admin.mint(&depositor, &1_000_000);
+        xlm_admin.mint(&depositor, &1_000_000);
+
+        Self {
+            env,
+            depositor,
+            contributor,
+            usdc,
+            xlm,
+            escrow,
+        }
+    }
+}
+
+#[test]
+fn test_init_allowlists_default_token() {
+    let setup = TestSetup::new();
+
+    let allowed = setup.escrow.get_allowed_tokens();
+    assert_eq!(allowed.len(), 2);
+    assert!(setup.escrow.is_token_allowed(&setup.usdc.address));
+    assert!(setup.escrow.is_token_allowed(&setup.xlm.address));
+}
+
+#[test]
+fn test_lock_funds_records_token_per_escrow() {
+    let setup = TestSetup::new();
+    let deadline = setup.env.ledger().timestamp() + 1000;
+
+    setup
+        .escrow
+        .lock_funds(&setup.depositor, &1, &1_000, &deadline);
+    setup
+        .escrow
+        .lock_funds_with_token(&setup.depositor, &2, &2_500, &deadline, &setup.xlm.address);
+
+    assert_eq!(setup.escrow.get_escrow_info(&1).token, setup.usdc.address);
+    assert_eq!(setup.escrow.get_escrow_info(&2).token, setup.xlm.address);
+    assert_eq!(setup.escrow.get_balance(), 1_000);
+    assert_eq!(setup.escrow.get_token_balance(&setup.usdc.address), 1_000);
+    assert_eq!(setup.escrow.get_token_balance(&setup.xlm.address), 2_500);
+}
+
+#[test]
+fn test_lock_funds_rejects_token_not_allowlisted() {
+    let setup = TestSetup::new();
+    let deadline = setup.env.ledger().timestamp() + 1000;
+    let other = Address::generate(&setup.env);
+
+    let result =
+        setup
+            .escrow
+            .try_lock_funds_with_token(&setup.depositor, &1, &1_000, &deadline, &other);
+    assert_eq!(result, Err(Ok(Error::TokenNotAllowed)));
+}
+
+#[test]
+fn test_release_and_refund_pay_out_in_escrow_token() {
+    let setup = TestSetup::new();
+    let deadline = setup.env.ledger().timestamp() + 1000;
+
+    setup
+        .escrow
+        .lock_funds_with_token(&setup.depositor, &1, &3_000, &deadline, &setup.xlm.address);
+    setup
+        .escrow
+        .lock_funds_with_token(&setup.depositor, &2, &4_000, &deadline, &setup.xlm.address);
+    setup
+        .escrow
+        .lock_funds(&setup.depositor, &3, &5_000, &deadline);
+
+    setup.escrow.release_funds(&1, &setup.contributor);
+    assert_eq!(setup.xlm.balance(&setup.contributor), 3_000);
+    assert_eq!(setup.usdc.balance(&setup.contributor), 0);
+
+    setup.env.ledger().set_timestamp(deadline + 1);
+    setup.escrow.refund(&2);
+    assert_eq!(setup.xlm.balance(&setup.depositor), 1_000_000 - 3_000);
+    assert_eq!(setup.escrow.get_token_balance(&setup.xlm.address), 0);
+    assert_eq!(setup.escrow.get_token_balance(&setup.usdc.address), 5_000);
+}
+
+#[test]
+fn test_removed_token_still_settles_existing_escrows() {
+    let setup = TestSetup::new();
+    let deadline = setup.env.ledger().timestamp() + 1000;
+
+    setup
+        .escrow
+        .lock_funds_with_token(&setup.depositor, &1, &1_000, &deadline, &setup.xlm.address);
+    setup.escrow.remove_allowed_token(&setup.xlm.address);
+    assert!(!setup.escrow.is_token_allowed(&setup.xlm.address));
+
+    let result = setup.escrow.try_lock_funds_with_token(
+        &setup.depositor,
+        &2,
+        &1_000,
+        &deadline,
+        &setup.xlm.address,
+    );
+    assert_eq!(result, Err(Ok(Error::TokenNotAllowed)));
+
+    setup.escrow.release_funds(&1, &setup.contributor);
+    assert_eq!(setup.xlm.balance(&setup.contributor), 1_000);
+}
+
+#[test]
+fn test_aggregate_stats_by_token() {
+    let setup = TestSetup::new();
+    let deadline = setup.env.ledger().timestamp() + 1000;
+
+    setup
+        .escrow
+        .lock_funds(&setup.depositor, &1, &1_000, &deadline);
+    setup
+        .escrow
+        .lock_funds(&setup.depositor, &2, &2_000, &deadline);
+    setup
+        .escrow
+        .lock_funds_with_token(&setup.depositor, &3, &7_000, &deadline, &setup.xlm.address);
+    setup.escrow.release_funds(&2, &setup.contributor);
+
+    let usdc_stats = setup
+        .escrow
+        .get_aggregate_stats_by_token(&setup.usdc.address);
+    assert_eq!(usdc_stats.total_locked, 1_000);
+    assert_eq!(usdc_stats.count_locked, 1);
+    assert_eq!(usdc_stats.total_released, 2_000);
+    assert_eq!(usdc_stats.count_released, 1);
+
+    let xlm_stats = setup
+        .escrow
+        .get_aggregate_stats_by_token(&setup.xlm.address);
+    assert_eq!(xlm_stats.total_locked, 7_000);
+    assert_eq!(xlm_stats.count_locked, 1);
+    assert_eq!(xlm_stats.count_released, 0);
+
+    // The unfiltered stats cover the default token only; amounts in
+    // different tokens are never added together
+    assert_eq!(setup.escrow.get_aggregate_stats(), usdc_stats);
+}
+
+#[test]
+fn test_batch_lock_funds_in_allowlisted_token() {
+    let setup = TestSetup::new();
+    let deadline = setup.env.ledger().timestamp() + 1000;
+    let items = vec![
+        &setup.env,
+        LockFundsItem {
+            bounty_id: 1,
+            depositor: setup.depositor.clone(),
+            amount: 1_000,
+            deadline,
+        },
+        LockFundsItem {
+            bounty_id: 2,
+            depositor: setup.depositor.clone(),
+            amount: 2_000,
+            deadline,
+        },
+    ];
+
+    setup
+        .escrow
+        .batch_lock_funds(&items, &Some(setup.xlm.address.clone()));
+    assert_eq!(setup.xlm.balance(&setup.escrow.address), 3_000);
+    assert_eq!(setup.usdc.balance(&setup.escrow.address), 0);
+    assert_eq!(setup.escrow.get_escrow_info(&1).token, setup.xlm.address);
+
+    let xlm_stats = setup
+        .escrow
+        .get_aggregate_stats_by_token(&setup.xlm.address);
+    assert_eq!(xlm_stats.total_locked, 3_000);
+    assert_eq!(xlm_stats.count_locked, 2);
+
+    // Tokens outside the allowlist are refused for the whole batch
+    let stranger = Address::generate(&setup.env);
+    let items = vec![
+        &setup.env,
+        LockFundsItem {
+            bounty_id: 3,
+            depositor: setup.depositor.clone(),
+            amount: 1_000,
+            deadline,
+        },
+    ];
+    assert_eq!(
+        setup.escrow.try_batch_lock_funds(&items, &Some(stranger)),
+        Err(Ok(Error::TokenNotAllowed))
+    );
+}
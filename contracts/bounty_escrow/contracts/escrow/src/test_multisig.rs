@@ -0,0 +1,366 @@
+#![cfg(test)]
+
+use super::*;
+use soroban_sdk::{testutils::Address as _, token, vec, Address, Env};
+
+fn create_token_contract<'a>(
+    e: &Env,
+    admin: &Address,
+) -> (token::Client<'a>, token::StellarAssetClient<'a>) {
+    let contract_address = e
+        .register_stellar_asset_contract_v2(admin.clone())
+        .address();
+    (
+        token::Client::new(e, &contract_address),
+        token::StellarAssetClient::new(e, &contract_address),
+    )
+}
+
+struct TestSetup<'a> {
+    env: Env,
+    depositor: Address,
+    contributor: Address,
+    signer1: Address,
+    signer2: Address,
+    signer3: Address,
+    token: token::Client<'a>,
+    escrow: BountyEscrowContractClient<'a>,
+}
+
+impl<'a> TestSetup<'a> {
+    /// Escrow with a 2-of-3 signer set guarding releases above 1_000.
+    fn new() -> Self {
+        let env = Env::default();
+        env.mock_all_auths();
+
+        let admin = Address::generate(&env);
+        let depositor = Address::generate(&env);
+        let contributor = Address::generate(&env);
+        let signer1 = Address::generate(&env);
+        let signer2 = Address::generate(&env);
+        let signer3 = Address::generate(&env);
+
+        let (token, token_admin) = create_token_contract(&env, &admin);
+        let contract_id = env.register_contract(None, BountyEscrowContract);
+        let escrow = BountyEscrowContractClient::new(&env, &contract_id);
+        escrow.init(&admin, &token.address);
+        escrow.update_multisig_config(
+            &1_000,
+            &vec![&env, signer1.clone(), signer2.clone(), signer3.clone()],
+            &2,
+        );
+
+        token_admin.mint(&depositor, &1_000_000);
+
+        Self {
+            env,
+            depositor,
+            contributor,
+            signer1,
+            signer2,
+            signer3,
+            token,
+            escrow,
+        }
+    }
+
+    fn lock(&self, bounty_id: u64, amount: i128) {
+        let deadline = self.env.ledger().timestamp() + 1000;
+        self.escrow
+            .lock_funds(&self.depositor, &bounty_id, &amount, &deadline);
+    }
+}
+
+#[test]
+fn test_release_below_threshold_needs_no_approval() {
+    let setup = TestSetup::new();
+    setup.lock(1, 1_000);
+
+    setup.escrow.release_funds(&1, &setup.contributor);
+    assert_eq!(setup.token.balance(&setup.contributor), 1_000);
+}
+
+#[test]
+fn test_large_release_without_approvals_rejected() {
+    let setup = TestSetup::new();
+    setup.lock(1, 5_000);
+
+    let result = setup.escrow.try_release_funds(&1, &setup.contributor);
+    assert_eq!(result, Err(Ok(Error::InsufficientApprovals)));
+}
+
+#[test]
+fn test_large_release_with_single_approval_rejected() {
+    let setup = TestSetup::new();
+    setup.lock(1, 5_000);
+
+    setup
+        .escrow
+        .approve_large_release(&1, &setup.contributor, &5_000, &setup.signer1);
+
+    let result = setup.escrow.try_release_funds(&1, &setup.contributor);
+    assert_eq!(result, Err(Ok(Error::InsufficientApprovals)));
+}
+
+#[test]
+fn test_large_release_with_required_approvals_succeeds_and_consumes() {
+    let setup = TestSetup::new();
+    setup.lock(1, 5_000);
+
+    setup
+        .escrow
+        .approve_large_release(&1, &setup.contributor, &5_000, &setup.signer1);
+    setup
+        .escrow
+        .approve_large_release(&1, &setup.contributor, &5_000, &setup.signer3);
+
+    setup.escrow.release_funds(&1, &setup.contributor);
+    assert_eq!(setup.token.balance(&setup.contributor), 5_000);
+    assert_eq!(setup.escrow.get_release_approval(&1), None);
+}
+
+#[test]
+fn test_duplicate_approval_counts_once() {
+    let setup = TestSetup::new();
+    setup.lock(1, 5_000);
+
+    setup
+        .escrow
+        .approve_large_release(&1, &setup.contributor, &5_000, &setup.signer1);
+    setup
+        .escrow
+        .approve_large_release(&1, &setup.contributor, &5_000, &setup.signer1);
+
+    let approval = setup.escrow.get_release_approval(&1).unwrap();
+    assert_eq!(approval.approvals.len(), 1);
+
+    let result = setup.escrow.try_release_funds(&1, &setup.contributor);
+    assert_eq!(result, Err(Ok(Error::InsufficientApprovals)));
+}
+
+#[test]
+fn test_approvals_bound_to_contributor() {
+    let setup = TestSetup::new();
+    setup.lock(1, 5_000);
+    let other = Address::generate(&setup.env);
+
+    setup
+        .escrow
+        .approve_large_release(&1, &setup.contributor, &5_000, &setup.signer1);
+    setup
+        .escrow
+        .approve_large_release(&1, &setup.contributor, &5_000, &setup.signer2);
+
+    // Approvals cannot be redirected to another payee
+    let result = setup.escrow.try_release_funds(&1, &other);
+    assert_eq!(result, Err(Ok(Error::ApprovalContributorMismatch)));
+
+    // Nor can a signer add an approval for a different contributor
+    let result = setup
+        .escrow
+        .try_approve_large_release(&1, &other, &5_000, &setup.signer3);
+    assert_eq!(result, Err(Ok(Error::ApprovalContributorMismatch)));
+}
+
+#[test]
+fn test_revoked_approval_blocks_release() {
+    let setup = TestSetup::new();
+    setup.lock(1, 5_000);
+
+    setup
+        .escrow
+        .approve_large_release(&1, &setup.contributor, &5_000, &setup.signer1);
+    setup
+        .escrow
+        .approve_large_release(&1, &setup.contributor, &5_000, &setup.signer2);
+    setup.escrow.revoke_release_approval(&1, &setup.signer2);
+
+    let approval = setup.escrow.get_release_approval(&1).unwrap();
+    assert_eq!(approval.approvals.len(), 1);
+
+    let result = setup.escrow.try_release_funds(&1, &setup.contributor);
+    assert_eq!(result, Err(Ok(Error::InsufficientApprovals)));
+
+    // Revoking the last approval clears the record entirely
+    setup.escrow.revoke_release_approval(&1, &setup.signer1);
+    assert_eq!(setup.escrow.get_release_approval(&1), None);
+}
+
+#[test]
+fn test_revoke_by_non_approver_rejected() {
+    let setup = TestSetup::new();
+    setup.lock(1, 5_000);
+
+    setup
+        .escrow
+        .approve_large_release(&1, &setup.contributor, &5_000, &setup.signer1);
+
+    let result = setup.escrow.try_revoke_release_approval(&1, &setup.signer2);
+    assert_eq!(result, Err(Ok(Error::Unauthorized)));
+}
+
+#[test]
+fn test_removed_signer_approval_no_longer_counts() {
+    let setup = TestSetup::new();
+    setup.lock(1, 5_000);
+
+    setup
+        .escrow
+        .approve_large_release(&1, &setup.contributor, &5_000, &setup.signer1);
+    setup
+        .escrow
+        .approve_large_release(&1, &setup.contributor, &5_000, &setup.signer2);
+
+    // signer2 is rotated out of the signer set
+    setup.escrow.update_multisig_config(
+        &1_000,
+        &vec![&setup.env, setup.signer1.clone(), setup.signer3.clone()],
+        &2,
+    );
+
+    let result = setup.escrow.try_release_funds(&1, &setup.contributor);
+    assert_eq!(result, Err(Ok(Error::InsufficientApprovals)));
+}
+
+#[test]
+fn test_large_partial_release_requires_fresh_approvals() {
+    let setup = TestSetup::new();
+    setup.lock(1, 5_000);
+
+    setup
+        .escrow
+        .approve_large_release(&1, &setup.contributor, &2_000, &setup.signer1);
+    setup
+        .escrow
+        .approve_large_release(&1, &setup.contributor, &2_000, &setup.signer2);
+    setup.escrow.partial_release(&1, &setup.contributor, &2_000);
+
+    // Approvals were consumed by the first large slice
+    let result = setup
+        .escrow
+        .try_partial_release(&1, &setup.contributor, &2_000);
+    assert_eq!(result, Err(Ok(Error::InsufficientApprovals)));
+
+    // Once the running total is past the threshold, small slices need approval too
+    let result = setup
+        .escrow
+        .try_partial_release(&1, &setup.contributor, &500);
+    assert_eq!(result, Err(Ok(Error::InsufficientApprovals)));
+    assert_eq!(setup.token.balance(&setup.contributor), 2_000);
+}
+
+#[test]
+fn test_partial_releases_count_toward_threshold() {
+    let setup = TestSetup::new();
+    setup.lock(1, 5_000);
+
+    // 600 + 400 stays at the threshold
+    setup.escrow.partial_release(&1, &setup.contributor, &600);
+    setup.escrow.partial_release(&1, &setup.contributor, &400);
+
+    // The next slice would take the total to 1_100
+    let result = setup
+        .escrow
+        .try_partial_release(&1, &setup.contributor, &100);
+    assert_eq!(result, Err(Ok(Error::InsufficientApprovals)));
+
+    setup
+        .escrow
+        .approve_large_release(&1, &setup.contributor, &100, &setup.signer1);
+    setup
+        .escrow
+        .approve_large_release(&1, &setup.contributor, &100, &setup.signer2);
+    setup.escrow.partial_release(&1, &setup.contributor, &100);
+    assert_eq!(setup.token.balance(&setup.contributor), 1_100);
+
+    // Releasing the rest also needs approval
+    let result = setup.escrow.try_release_funds(&1, &setup.contributor);
+    assert_eq!(result, Err(Ok(Error::InsufficientApprovals)));
+}
+
+#[test]
+fn test_approvals_bound_to_amount() {
+    let setup = TestSetup::new();
+    setup.lock(1, 5_000);
+
+    setup
+        .escrow
+        .approve_large_release(&1, &setup.contributor, &2_000, &setup.signer1);
+
+    // Every signer must approve the same amount
+    let result =
+        setup
+            .escrow
+            .try_approve_large_release(&1, &setup.contributor, &5_000, &setup.signer2);
+    assert_eq!(result, Err(Ok(Error::ApprovalAmountMismatch)));
+
+    setup
+        .escrow
+        .approve_large_release(&1, &setup.contributor, &2_000, &setup.signer2);
+    assert_eq!(setup.escrow.get_release_approval(&1).unwrap().amount, 2_000);
+
+    // An approval for 2_000 does not cover releasing the full 5_000
+    let result = setup.escrow.try_release_funds(&1, &setup.contributor);
+    assert_eq!(result, Err(Ok(Error::ApprovalAmountMismatch)));
+
+    // Nor a larger partial slice
+    let result = setup
+        .escrow
+        .try_partial_release(&1, &setup.contributor, &3_000);
+    assert_eq!(result, Err(Ok(Error::ApprovalAmountMismatch)));
+
+    setup.escrow.partial_release(&1, &setup.contributor, &2_000);
+    assert_eq!(setup.token.balance(&setup.contributor), 2_000);
+}
+
+#[test]
+fn test_batch_release_enforces_approvals_per_item() {
+    let setup = TestSetup::new();
+    setup.lock(1, 500);
+    setup.lock(2, 5_000);
+
+    let items = vec![
+        &setup.env,
+        ReleaseFundsItem {
+            bounty_id: 1,
+            contributor: setup.contributor.clone(),
+        },
+        ReleaseFundsItem {
+            bounty_id: 2,
+            contributor: setup.contributor.clone(),
+        },
+    ];
+
+    let result = setup.escrow.try_batch_release_funds(&items);
+    assert_eq!(result, Err(Ok(Error::InsufficientApprovals)));
+
+    setup
+        .escrow
+        .approve_large_release(&2, &setup.contributor, &5_000, &setup.signer1);
+    setup
+        .escrow
+        .approve_large_release(&2, &setup.contributor, &5_000, &setup.signer2);
+
+    setup.escrow.batch_release_funds(&items);
+    assert_eq!(setup.token.balance(&setup.contributor), 5_500);
+}
+
+#[test]
+fn test_large_authorize_claim_requires_approvals() {
+    let setup = TestSetup::new();
+    setup.lock(1, 5_000);
+    setup.escrow.set_claim_window(&500);
+
+    let result = setup.escrow.try_authorize_claim(&1, &setup.contributor);
+    assert_eq!(result, Err(Ok(Error::InsufficientApprovals)));
+
+    setup
+        .escrow
+        .approve_large_release(&1, &setup.contributor, &5_000, &setup.signer2);
+    setup
+        .escrow
+        .approve_large_release(&1, &setup.contributor, &5_000, &setup.signer3);
+    setup.escrow.authorize_claim(&1, &setup.contributor);
+    setup.escrow.claim(&1);
+    assert_eq!(setup.token.balance(&setup.contributor), 5_000);
+}
@@ -0,0 +1,406 @@
+#![cfg(test)]
+
+use super::*;
+use soroban_sdk::{
+    testutils::{Address as _, Events, Ledger},
+    token, vec, Address, Env, Symbol, TryFromVal,
+};
+
+fn create_token_contract<'a>(
+    e: &Env,
+    admin: &Address,
+) -> (token::Client<'a>, token::StellarAssetClient<'a>) {
+    let contract_address = e
+        .register_stellar_asset_contract_v2(admin.clone())
+        .address();
+    (
+        token::Client::new(e, &contract_address),
+        token::StellarAssetClient::new(e, &contract_address),
+    )
+}
+
+struct TestSetup<'a> {
+    env: Env,
+    depositor: Address,
+    alice: Address,
+    bob: Address,
+    carol: Address,
+    token: token::Client<'a>,
+    escrow: BountyEscrowContractClient<'a>,
+}
+
+impl<'a> TestSetup<'a> {
+    fn new() -> Self {
+        let env = Env::default();
+        env.mock_all_auths();
+
+        let admin = Address::generate(&env);
+        let depositor = Address::generate(&env);
+        let alice = Address::generate(&env);
+        let bob = Address::generate(&env);
+        let carol = Address::generate(&env);
+
+        let (token, token_admin) = create_token_contract(&env, &admin);
+        let contract_id = env.register_contract(None, BountyEscrowContract);
+        let escrow = BountyEscrowContractClient::new(&env, &contract_id);
+        escrow.init(&admin, &token.address);
+
+        token_admin.mint(&depositor, &1_000_000);
+
+        Self {
+            env,
+            depositor,
+            alice,
+            bob,
+            carol,
+            token,
+            escrow,
+        }
+    }
+
+    fn lock(&self, bounty_id: u64, amount: i128) {
+        let deadline = self.env.ledger().timestamp() + 1000;
+        self.escrow
+            .lock_funds(&self.depositor, &bounty_id, &amount, &deadline);
+    }
+
+    fn share(&self, recipient: &Address, basis_points: u32) -> SplitShare {
+        SplitShare {
+            recipient: recipient.clone(),
+            basis_points,
+        }
+    }
+}
+
+#[test]
+fn test_split_release_pays_all_recipients() {
+    let setup = TestSetup::new();
+    setup.lock(1, 10_000);
+
+    let shares = vec![
+        &setup.env,
+        setup.share(&setup.alice, 5_000),
+        setup.share(&setup.bob, 3_000),
+        setup.share(&setup.carol, 2_000),
+    ];
+    setup.escrow.release_funds_split(&1, &shares);
+
+    assert_eq!(setup.token.balance(&setup.alice), 5_000);
+    assert_eq!(setup.token.balance(&setup.bob), 3_000);
+    assert_eq!(setup.token.balance(&setup.carol), 2_000);
+
+    let escrow = setup.escrow.get_escrow_info(&1);
+    assert_eq!(escrow.status, EscrowStatus::Released);
+    assert_eq!(escrow.remaining_amount, 0);
+}
+
+#[test]
+fn test_split_release_dust_goes_to_first_share() {
+    let setup = TestSetup::new();
+    setup.lock(1, 100);
+
+    let shares = vec![
+        &setup.env,
+        setup.share(&setup.alice, 3_334),
+        setup.share(&setup.bob, 3_333),
+        setup.share(&setup.carol, 3_333),
+    ];
+    setup.escrow.release_funds_split(&1, &shares);
+
+    // 33 + 33 + 33 = 99; the 1 unit of dust goes to the first share
+    assert_eq!(setup.token.balance(&setup.alice), 34);
+    assert_eq!(setup.token.balance(&setup.bob), 33);
+    assert_eq!(setup.token.balance(&setup.carol), 33);
+    assert_eq!(setup.token.balance(&setup.escrow.address), 0);
+}
+
+#[test]
+fn test_split_release_emits_one_release_event_per_recipient() {
+    let setup = TestSetup::new();
+    setup.lock(1, 1_000);
+
+    let shares = vec![
+        &setup.env,
+        setup.share(&setup.alice, 6_000),
+        setup.share(&setup.bob, 4_000),
+    ];
+    setup.escrow.release_funds_split(&1, &shares);
+
+    let mut released = 0;
+    for (contract, topics, _data) in setup.env.events().all().iter() {
+        if contract != setup.escrow.address {
+            continue;
+        }
+        let topic = Symbol::try_from_val(&setup.env, &topics.get(0).unwrap());
+        if topic == Ok(symbol_short!("f_rel")) {
+            released += 1;
+        }
+    }
+    assert_eq!(released, 2);
+}
+
+#[test]
+fn test_split_release_rejects_invalid_shares() {
+    let setup = TestSetup::new();
+    setup.lock(1, 1_000);
+
+    let short = vec![
+        &setup.env,
+        setup.share(&setup.alice, 5_000),
+        setup.share(&setup.bob, 4_999),
+    ];
+    assert_eq!(
+        setup.escrow.try_release_funds_split(&1, &short),
+        Err(Ok(Error::InvalidSplit))
+    );
+
+    let duplicate = vec![
+        &setup.env,
+        setup.share(&setup.alice, 5_000),
+        setup.share(&setup.alice, 5_000),
+    ];
+    assert_eq!(
+        setup.escrow.try_release_funds_split(&1, &duplicate),
+        Err(Ok(Error::InvalidSplit))
+    );
+
+    let zero = vec![
+        &setup.env,
+        setup.share(&setup.alice, 10_000),
+        setup.share(&setup.bob, 0),
+    ];
+    assert_eq!(
+        setup.escrow.try_release_funds_split(&1, &zero),
+        Err(Ok(Error::InvalidSplit))
+    );
+
+    let empty: Vec<SplitShare> = vec![&setup.env];
+    assert_eq!(
+        setup.escrow.try_release_funds_split(&1, &empty),
+        Err(Ok(Error::InvalidSplit))
+    );
+
+    assert_eq!(setup.token.balance(&setup.escrow.address), 1_000);
+}
+
+#[test]
+fn test_split_release_applies_release_fee_once() {
+    let setup = TestSetup::new();
+    let fee_recipient = Address::generate(&setup.env);
+    setup.escrow.update_fee_config(
+        &None,
+        &Some(1_000),
+        &Some(fee_recipient.clone()),
+        &Some(true),
+    );
+    setup.lock(1, 10_000);
+
+    let shares = vec![
+        &setup.env,
+        setup.share(&setup.alice, 5_000),
+        setup.share(&setup.bob, 5_000),
+    ];
+    setup.escrow.release_funds_split(&1, &shares);
+
+    assert_eq!(setup.token.balance(&fee_recipient), 1_000);
+    assert_eq!(setup.token.balance(&setup.alice), 4_500);
+    assert_eq!(setup.token.balance(&setup.bob), 4_500);
+}
+
+#[test]
+fn test_each_co_contributor_claims_own_share() {
+    let setup = TestSetup::new();
+    setup.escrow.set_claim_window(&500);
+    setup.lock(1, 9_000);
+
+    let shares = vec![
+        &setup.env,
+        setup.share(&setup.alice, 5_000),
+        setup.share(&setup.bob, 5_000),
+    ];
+    setup.escrow.authorize_split_claim(&1, &shares);
+
+    let claims = setup.escrow.get_split_claims(&1);
+    assert_eq!(claims.len(), 2);
+    assert_eq!(claims.get(1).unwrap().amount, 4_500);
+
+    setup.escrow.claim_share(&1, &setup.alice);
+    assert_eq!(setup.token.balance(&setup.alice), 4_500);
+    assert_eq!(
+        setup.escrow.get_escrow_info(&1).status,
+        EscrowStatus::PendingSplit
+    );
+
+    // A share can only be claimed once, and only by its recipient
+    assert_eq!(
+        setup.escrow.try_claim_share(&1, &setup.alice),
+        Err(Ok(Error::FundsNotLocked))
+    );
+    assert_eq!(
+        setup.escrow.try_claim_share(&1, &setup.carol),
+        Err(Ok(Error::Unauthorized))
+    );
+
+    setup.escrow.claim_share(&1, &setup.bob);
+    assert_eq!(setup.token.balance(&setup.bob), 4_500);
+
+    let escrow = setup.escrow.get_escrow_info(&1);
+    assert_eq!(escrow.status, EscrowStatus::Released);
+    assert_eq!(escrow.remaining_amount, 0);
+}
+
+#[test]
+fn test_cancel_split_claims_keeps_unclaimed_funds_locked() {
+    let setup = TestSetup::new();
+    setup.escrow.set_claim_window(&500);
+    setup.lock(1, 1_000);
+
+    let shares = vec![
+        &setup.env,
+        setup.share(&setup.alice, 7_000),
+        setup.share(&setup.bob, 3_000),
+    ];
+    setup.escrow.authorize_split_claim(&1, &shares);
+    setup.escrow.claim_share(&1, &setup.alice);
+    setup.escrow.cancel_split_claims(&1);
+
+    assert_eq!(
+        setup.escrow.try_claim_share(&1, &setup.bob),
+        Err(Ok(Error::BountyNotFound))
+    );
+    let escrow = setup.escrow.get_escrow_info(&1);
+    assert_eq!(escrow.status, EscrowStatus::Locked);
+    assert_eq!(escrow.remaining_amount, 300);
+}
+
+#[test]
+fn test_pending_split_blocks_release_refund_and_reauthorization() {
+    let setup = TestSetup::new();
+    setup.escrow.set_claim_window(&5_000);
+    setup.lock(1, 1_000);
+
+    let shares = vec![
+        &setup.env,
+        setup.share(&setup.alice, 5_000),
+        setup.share(&setup.bob, 5_000),
+    ];
+    setup.escrow.authorize_split_claim(&1, &shares);
+    assert_eq!(
+        setup.escrow.get_escrow_info(&1).status,
+        EscrowStatus::PendingSplit
+    );
+
+    assert_eq!(
+        setup.escrow.try_release_funds(&1, &setup.carol),
+        Err(Ok(Error::FundsNotLocked))
+    );
+    assert_eq!(
+        setup.escrow.try_partial_release(&1, &setup.carol, &100),
+        Err(Ok(Error::FundsNotLocked))
+    );
+    let other = vec![&setup.env, setup.share(&setup.carol, 10_000)];
+    assert_eq!(
+        setup.escrow.try_authorize_split_claim(&1, &other),
+        Err(Ok(Error::FundsNotLocked))
+    );
+    assert_eq!(
+        setup.escrow.try_authorize_claim(&1, &setup.carol),
+        Err(Ok(Error::FundsNotLocked))
+    );
+
+    setup.env.ledger().set_timestamp(2_000);
+    assert_eq!(setup.escrow.try_refund(&1), Err(Ok(Error::FundsNotLocked)));
+
+    // Shares stay fully funded
+    assert_eq!(setup.token.balance(&setup.escrow.address), 1_000);
+    setup.escrow.claim_share(&1, &setup.alice);
+    setup.escrow.claim_share(&1, &setup.bob);
+    assert_eq!(setup.token.balance(&setup.alice), 500);
+    assert_eq!(setup.token.balance(&setup.bob), 500);
+}
+
+#[test]
+fn test_split_approval_bound_to_whole_split() {
+    let setup = TestSetup::new();
+    let signer1 = Address::generate(&setup.env);
+    let signer2 = Address::generate(&setup.env);
+    setup.escrow.update_multisig_config(
+        &1_000,
+        &vec![&setup.env, signer1.clone(), signer2.clone()],
+        &2,
+    );
+    setup.escrow.set_claim_window(&500);
+    setup.lock(1, 10_000);
+
+    let shares = vec![
+        &setup.env,
+        setup.share(&setup.alice, 5_000),
+        setup.share(&setup.bob, 5_000),
+    ];
+    setup
+        .escrow
+        .approve_split_release(&1, &shares, &10_000, &signer1);
+    setup
+        .escrow
+        .approve_split_release(&1, &shares, &10_000, &signer2);
+
+    // Same lead recipient, but the rest of the split was swapped
+    let swapped = vec![
+        &setup.env,
+        setup.share(&setup.alice, 5_000),
+        setup.share(&setup.carol, 5_000),
+    ];
+    assert_eq!(
+        setup.escrow.try_authorize_split_claim(&1, &swapped),
+        Err(Ok(Error::ApprovalContributorMismatch))
+    );
+    assert_eq!(
+        setup.escrow.try_release_funds_split(&1, &swapped),
+        Err(Ok(Error::ApprovalContributorMismatch))
+    );
+
+    // A single-payee release to the lead is not covered either
+    assert_eq!(
+        setup.escrow.try_release_funds(&1, &setup.alice),
+        Err(Ok(Error::ApprovalContributorMismatch))
+    );
+
+    setup.escrow.release_funds_split(&1, &shares);
+    assert_eq!(setup.token.balance(&setup.alice), 5_000);
+    assert_eq!(setup.token.balance(&setup.bob), 5_000);
+    assert_eq!(setup.escrow.get_release_approval(&1), None);
+}
+
+#[test]
+fn test_split_recipient_can_dispute_pending_split() {
+    let setup = TestSetup::new();
+    let arbiter = Address::generate(&setup.env);
+    setup
+        .escrow
+        .set_arbiters(&vec![&setup.env, arbiter.clone()]);
+    setup.escrow.set_claim_window(&500);
+    setup.lock(1, 1_000);
+
+    let shares = vec![
+        &setup.env,
+        setup.share(&setup.alice, 5_000),
+        setup.share(&setup.bob, 5_000),
+    ];
+    setup.escrow.authorize_split_claim(&1, &shares);
+    setup.escrow.open_dispute(
+        &1,
+        &setup.bob,
+        &soroban_sdk::BytesN::from_array(&setup.env, &[1; 32]),
+    );
+
+    // Claims must survive in case the dispute lapses back to PendingSplit
+    assert_eq!(
+        setup.escrow.try_cancel_split_claims(&1),
+        Err(Ok(Error::EscrowDisputed))
+    );
+
+    setup
+        .escrow
+        .resolve_dispute(&1, &arbiter, &setup.bob, &1_000);
+    assert_eq!(setup.token.balance(&setup.bob), 1_000);
+}
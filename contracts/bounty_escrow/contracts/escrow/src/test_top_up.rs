@@ -0,0 +1,195 @@
+#![cfg(test)]
+
+use super::*;
+use soroban_sdk::{
+    testutils::{Address as _, Ledger},
+    token, Address, BytesN, Env,
+};
+
+fn create_token_contract<'a>(
+    e: &Env,
+    admin: &Address,
+) -> (token::Client<'a>, token::StellarAssetClient<'a>) {
+    let contract_address = e
+        .register_stellar_asset_contract_v2(admin.clone())
+        .address();
+    (
+        token::Client::new(e, &contract_address),
+        token::StellarAssetClient::new(e, &contract_address),
+    )
+}
+
+struct TestSetup<'a> {
+    env: Env,
+    depositor: Address,
+    backer: Address,
+    contributor: Address,
+    token: token::Client<'a>,
+    escrow: BountyEscrowContractClient<'a>,
+}
+
+impl<'a> TestSetup<'a> {
+    fn new() -> Self {
+        let env = Env::default();
+        env.mock_all_auths();
+
+        let admin = Address::generate(&env);
+        let depositor = Address::generate(&env);
+        let backer = Address::generate(&env);
+        let contributor = Address::generate(&env);
+
+        let (token, token_admin) = create_token_contract(&env, &admin);
+        let contract_id = env.register_contract(None, BountyEscrowContract);
+        let escrow = BountyEscrowContractClient::new(&env, &contract_id);
+        escrow.init(&admin, &token.address);
+
+        token_admin.mint(&depositor, &1_000_000);
+        token_admin.mint(&backer, &1_000_000);
+
+        Self {
+            env,
+            depositor,
+            backer,
+            contributor,
+            token,
+            escrow,
+        }
+    }
+
+    fn lock(&self, bounty_id: u64, amount: i128) -> u64 {
+        let deadline = self.env.ledger().timestamp() + 1000;
+        self.escrow
+            .lock_funds(&self.depositor, &bounty_id, &amount, &deadline);
+        deadline
+    }
+}
+
+#[test]
+fn test_top_up_increases_amount_and_records_funders() {
+    let setup = TestSetup::new();
+    setup.lock(1, 3_000);
+
+    setup.escrow.top_up(&setup.backer, &1, &1_000);
+    setup.escrow.top_up(&setup.backer, &1, &500);
+
+    let escrow = setup.escrow.get_escrow_info(&1);
+    assert_eq!(escrow.amount, 4_500);
+    assert_eq!(escrow.remaining_amount, 4_500);
+    assert_eq!(setup.token.balance(&setup.escrow.address), 4_500);
+
+    let contributions = setup.escrow.get_contributions(&1);
+    assert_eq!(contributions.len(), 2);
+    assert_eq!(contributions.get(0).unwrap().funder, setup.depositor);
+    assert_eq!(contributions.get(0).unwrap().amount, 3_000);
+    assert_eq!(contributions.get(1).unwrap().funder, setup.backer);
+    assert_eq!(contributions.get(1).unwrap().amount, 1_500);
+}
+
+#[test]
+fn test_top_up_rejected_for_missing_or_settled_bounty() {
+    let setup = TestSetup::new();
+
+    assert_eq!(
+        setup.escrow.try_top_up(&setup.backer, &1, &1_000),
+        Err(Ok(Error::BountyNotFound))
+    );
+
+    setup.lock(1, 3_000);
+    assert_eq!(
+        setup.escrow.try_top_up(&setup.backer, &1, &0),
+        Err(Ok(Error::InvalidAmount))
+    );
+
+    setup.escrow.release_funds(&1, &setup.contributor);
+    assert_eq!(
+        setup.escrow.try_top_up(&setup.backer, &1, &1_000),
+        Err(Ok(Error::FundsNotLocked))
+    );
+}
+
+#[test]
+fn test_topped_up_bounty_releases_full_amount() {
+    let setup = TestSetup::new();
+    setup.lock(1, 3_000);
+    setup.escrow.top_up(&setup.backer, &1, &2_000);
+
+    setup.escrow.release_funds(&1, &setup.contributor);
+    assert_eq!(setup.token.balance(&setup.contributor), 5_000);
+}
+
+#[test]
+fn test_deadline_refund_is_pro_rata_across_funders() {
+    let setup = TestSetup::new();
+    let deadline = setup.lock(1, 3_000);
+    setup.escrow.top_up(&setup.backer, &1, &1_000);
+
+    // Half of the pool is paid out before the deadline
+    setup.escrow.partial_release(&1, &setup.contributor, &2_000);
+
+    setup.env.ledger().set_timestamp(deadline + 1);
+    setup.escrow.refund(&1);
+
+    // Remaining 2_000 is split 3:1
+    assert_eq!(
+        setup.token.balance(&setup.depositor),
+        1_000_000 - 3_000 + 1_500
+    );
+    assert_eq!(setup.token.balance(&setup.backer), 1_000_000 - 1_000 + 500);
+
+    let escrow = setup.escrow.get_escrow_info(&1);
+    assert_eq!(escrow.status, EscrowStatus::Refunded);
+    assert_eq!(escrow.refund_history.len(), 2);
+}
+
+#[test]
+fn test_pro_rata_refund_dust_goes_to_depositor() {
+    let setup = TestSetup::new();
+    let deadline = setup.lock(1, 1);
+    let second = Address::generate(&setup.env);
+    setup.escrow.top_up(&setup.backer, &1, &1);
+    setup.escrow.top_up(&setup.depositor, &1, &1);
+    setup.token.transfer(&setup.backer, &second, &1);
+    setup.escrow.top_up(&second, &1, &1);
+
+    // Pool of 4 owned 2:1:1; releasing 1 leaves 3 to refund
+    setup.escrow.partial_release(&1, &setup.contributor, &1);
+
+    setup.env.ledger().set_timestamp(deadline + 1);
+    setup.escrow.refund(&1);
+
+    // 3 * 2/4 = 1, 3 * 1/4 = 0, 3 * 1/4 = 0; dust of 2 goes to the depositor
+    assert_eq!(setup.token.balance(&setup.depositor), 1_000_000 - 2 + 3);
+    assert_eq!(setup.token.balance(&setup.escrow.address), 0);
+}
+
+#[test]
+fn test_dispute_refund_share_is_pro_rata() {
+    let setup = TestSetup::new();
+    setup.lock(1, 6_000);
+    setup.escrow.top_up(&setup.backer, &1, &2_000);
+    setup.escrow.set_claim_window(&500);
+    setup.escrow.authorize_claim(&1, &setup.contributor);
+
+    let arbiter = Address::generate(&setup.env);
+    setup
+        .escrow
+        .set_arbiters(&soroban_sdk::vec![&setup.env, arbiter.clone()]);
+    setup.escrow.open_dispute(
+        &1,
+        &setup.depositor,
+        &BytesN::from_array(&setup.env, &[9; 32]),
+    );
+    setup
+        .escrow
+        .resolve_dispute(&1, &arbiter, &setup.contributor, &4_000);
+
+    assert_eq!(setup.token.balance(&setup.contributor), 4_000);
+    assert_eq!(
+        setup.token.balance(&setup.depositor),
+        1_000_000 - 6_000 + 3_000
+    );
+    assert_eq!(
+        setup.token.balance(&setup.backer),
+        1_000_000 - 2_000 + 1_000
+    );
+}
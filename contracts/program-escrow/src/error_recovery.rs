@@ -115,8 +115,6 @@ pub struct CircuitBreakerStatus {
 pub const ERR_CIRCUIT_OPEN: u32 = 1001;
 /// Token transfer failed (transient).
 pub const ERR_TRANSFER_FAILED: u32 = 1002;
-/// Insufficient contract balance.
-pub const ERR_INSUFFICIENT_BALANCE: u32 = 1003;
 /// Operation succeeded — for logging.
 pub const ERR_NONE: u32 = 0;
 
@@ -57,14 +57,14 @@
 //! │                                                                  │
 //! │  Storage:                                                        │
 //! │  ┌──────────────────────────────────────────┐                  │
-//! │  │ ProgramData:                             │                  │
+//! │  │ ProgramData (one per program_id):        │                  │
 //! │  │  - program_id                            │                  │
 //! │  │  - total_funds                           │                  │
 //! │  │  - remaining_balance                     │                  │
 //! │  │  - authorized_payout_key                 │                  │
-//! │  │  - payout_history: [PayoutRecord]        │                  │
 //! │  │  - token_address                         │                  │
 //! │  └──────────────────────────────────────────┘                  │
+//! │  PayoutRecord (one per payout, keyed by program_id + payout_id)  │
 //! └─────────────────────────────────────────────────────────────────┘
 //! ```
 //!
@@ -77,7 +77,7 @@
 //! - **Contract**: Trustless; operates according to programmed rules
 //!
 //! ### Key Security Features
-//! 1. **Single Initialization**: Prevents program re-configuration
+//! 1. **Single Initialization**: Each program id can be registered only once
 //! 2. **Authorization Checks**: Only authorized key can trigger payouts
 //! 3. **Balance Validation**: Prevents overdrafts
 //! 4. **Atomic Transfers**: All-or-nothing batch operations
@@ -89,7 +89,7 @@
 //! ```rust
 //! use soroban_sdk::{Address, Env, String, vec};
 //!
-//! // 1. Initialize program (one-time setup)
+//! // 1. Register the program (one deployment can host many)
 //! let program_id = String::from_str(&env, "Hackathon2024");
 //! let backend = Address::from_string("GBACKEND...");
 //! let usdc_token = Address::from_string("CUSDC...");
@@ -100,9 +100,9 @@
 //!     &usdc_token
 //! );
 //!
-//! // 2. Lock prize pool (10,000 USDC)
+//! // 2. Lock prize pool (10,000 USDC) from the organizer's account
 //! let prize_pool = 10_000_0000000; // 10,000 USDC (7 decimals)
-//! escrow_client.lock_program_funds(&prize_pool);
+//! escrow_client.lock_program_funds(&program_id, &organizer, &prize_pool);
 //!
 //! // 3. After hackathon, distribute prizes
 //! let winners = vec![
@@ -119,7 +119,7 @@
 //!     2_000_0000000,  // 3rd place: 2,000 USDC
 //! ];
 //!
-//! escrow_client.batch_payout(&winners, &prizes);
+//! escrow_client.batch_payout(&program_id, &winners, &prizes);
 //! ```
 //!
 //! ## Event System
@@ -139,9 +139,6 @@
 //! 5. **Balance Checks**: Verify remaining balance matches expectations
 //! 6. **Token Approval**: Ensure contract has token allowance before locking funds
 
-// ── Step 1: Add module declarations near the top of lib.rs ──────────────
-// (after `mod anti_abuse;` and before the contract struct)
-
 mod error_recovery;
 mod reentrancy_guard;
 
@@ -157,139 +154,9 @@ mod reentrancy_guard_standalone_test;
 #[cfg(test)]
 mod malicious_reentrant;
 
-// ── Step 2: Add these public contract functions to the ProgramEscrowContract
-//    impl block (alongside the existing admin functions) ──────────────────
-
-// ========================================================================
-// Circuit Breaker Management
-// ========================================================================
-
-/// Register the circuit breaker admin. Can only be set once, or changed
-/// by the existing admin.
-///
-/// # Arguments
-/// * `new_admin` - Address to register as circuit breaker admin
-/// * `caller`    - Existing admin (None if setting for the first time)
-pub fn set_circuit_admin(env: Env, new_admin: Address, caller: Option<Address>) {
-    error_recovery::set_circuit_admin(&env, new_admin, caller);
-}
-
-/// Returns the registered circuit breaker admin, if any.
-pub fn get_circuit_admin(env: Env) -> Option<Address> {
-    error_recovery::get_circuit_admin(&env)
-}
-
-/// Returns the full circuit breaker status snapshot.
-///
-/// # Returns
-/// * `CircuitBreakerStatus` with state, failure/success counts, timestamps
-pub fn get_circuit_status(env: Env) -> error_recovery::CircuitBreakerStatus {
-    error_recovery::get_status(&env)
-}
-
-/// Admin resets the circuit breaker.
-///
-/// Transitions:
-/// - Open     → HalfOpen  (probe mode)
-/// - HalfOpen → Closed    (hard reset)
-/// - Closed   → Closed    (no-op reset)
-///
-/// # Panics
-/// * If caller is not the registered circuit breaker admin
-pub fn reset_circuit_breaker(env: Env, admin: Address) {
-    error_recovery::reset_circuit_breaker(&env, &admin);
-}
-
-/// Updates the circuit breaker configuration. Admin only.
-///
-/// # Arguments
-/// * `failure_threshold` - Consecutive failures needed to open circuit
-/// * `success_threshold` - Consecutive successes in HalfOpen to close it
-/// * `max_error_log`     - Maximum error log entries to retain
-pub fn configure_circuit_breaker(
-    env: Env,
-    admin: Address,
-    failure_threshold: u32,
-    success_threshold: u32,
-    max_error_log: u32,
-) {
-    let stored = error_recovery::get_circuit_admin(&env);
-    match stored {
-        Some(ref a) if a == &admin => {
-            admin.require_auth();
-        }
-        _ => panic!("Unauthorized: only circuit breaker admin can configure"),
-    }
-    error_recovery::set_config(
-        &env,
-        error_recovery::CircuitBreakerConfig {
-            failure_threshold,
-            success_threshold,
-            max_error_log,
-        },
-    );
-}
-
-/// Returns the error log (last N failures recorded by the circuit breaker).
-pub fn get_circuit_error_log(env: Env) -> soroban_sdk::Vec<error_recovery::ErrorEntry> {
-    error_recovery::get_error_log(&env)
-}
-
-/// Directly open the circuit (emergency lockout). Admin only.
-pub fn emergency_open_circuit(env: Env, admin: Address) {
-    let stored = error_recovery::get_circuit_admin(&env);
-    match stored {
-        Some(ref a) if a == &admin => {
-            admin.require_auth();
-        }
-        _ => panic!("Unauthorized"),
-    }
-    error_recovery::open_circuit(&env);
-}
-
-// ── Step 3: Wrap batch_payout and single_payout with circuit breaker ────
-//
-// In the existing batch_payout function, add at the very top (after getting
-// program_data but before the auth check):
-//
-//   use crate::error_recovery;
-//   if let Err(_) = error_recovery::check_and_allow(&env) {
-//       panic!("Circuit breaker is open: payout operations are temporarily disabled");
-//   }
-//
-// After a successful transfer loop, add:
-//   error_recovery::record_success(&env);
-//
-// If a transfer panics/fails, the circuit breaker failure should be recorded
-// via record_failure() before re-panicking.
-//
-// For a clean integration, wrap the token transfer call like this:
-//
-//   let transfer_ok = std::panic::catch_unwind(|| {
-//       token_client.transfer(&contract_address, &recipient.clone(), &net_amount);
-//   });
-//   match transfer_ok {
-//       Ok(_) => error_recovery::record_success(&env),
-//       Err(_) => {
-//           error_recovery::record_failure(
-//               &env,
-//               program_id.clone(),
-//               soroban_sdk::symbol_short!("batch_pay"),
-//               error_recovery::ERR_TRANSFER_FAILED,
-//           );
-//           panic!("Token transfer failed");
-//       }
-//   }
-//
-// Note: Soroban's environment panics abort the transaction, so in practice
-// you record the failure and re-panic. The circuit breaker state is committed
-// because Soroban persists storage writes made before the panic in tests
-// (but not in production transactions that abort). For full production
-// integration, use the `try_*` variants of client calls where available.
-
 use soroban_sdk::{
-    contract, contractimpl, contracttype, symbol_short, token, vec, Address, Env, String, Symbol,
-    Vec,
+    contract, contracterror, contractimpl, contracttype, symbol_short, token, vec, Address, Env,
+    Map, String, Symbol, Vec,
 };
 
 // Event types
@@ -297,17 +164,31 @@ const PROGRAM_INITIALIZED: Symbol = symbol_short!("PrgInit");
 const FUNDS_LOCKED: Symbol = symbol_short!("FndsLock");
 const BATCH_PAYOUT: Symbol = symbol_short!("BatchPay");
 const PAYOUT: Symbol = symbol_short!("Payout");
+const PAYOUT_FAILED: Symbol = symbol_short!("PayFail");
 const EVENT_VERSION_V2: u32 = 2;
 const PAUSE_STATE_CHANGED: Symbol = symbol_short!("PauseSt");
+const KEY_ROTATION_SCHEDULED: Symbol = symbol_short!("KeyRotPnd");
+const KEY_ROTATED: Symbol = symbol_short!("KeyRot");
+const KEY_REVOKED: Symbol = symbol_short!("KeyRevoke");
+const PROGRAM_CLOSED: Symbol = symbol_short!("PrgClosed");
+const PROGRAM_REFUND: Symbol = symbol_short!("PrgRefund");
+const PROGRAM_SETTLED: Symbol = symbol_short!("PrgSettld");
+const CLAIM_CREATED: Symbol = symbol_short!("ClaimCrt");
+const CLAIM_EXECUTED: Symbol = symbol_short!("ClaimDone");
+const CLAIM_CANCELLED: Symbol = symbol_short!("ClaimCncl");
+const CLAIM_EXPIRED: Symbol = symbol_short!("ClaimExp");
+const PAYOUT_APPROVED: Symbol = symbol_short!("PayAppr");
+const PAYOUT_APPROVAL_REVOKED: Symbol = symbol_short!("PayApRvk");
+const SCHEDULE_CANCELLED: Symbol = symbol_short!("SchCncl");
+const SCHEDULE_UPDATED: Symbol = symbol_short!("SchUpdt");
 
 // Storage keys
-const PROGRAM_DATA: Symbol = symbol_short!("ProgData");
-const SCHEDULES: Symbol = symbol_short!("Scheds");
-const RELEASE_HISTORY: Symbol = symbol_short!("RelHist");
-const NEXT_SCHEDULE_ID: Symbol = symbol_short!("NxtSched");
 const PROGRAM_INDEX: Symbol = symbol_short!("ProgIdx");
 const AUTH_KEY_INDEX: Symbol = symbol_short!("AuthIdx");
 
+// Width of a due-index bucket
+const DUE_BUCKET_SECONDS: u64 = 86_400;
+
 #[contracttype]
 #[derive(Clone, Debug, Eq, PartialEq)]
 pub struct PayoutRecord {
@@ -316,6 +197,23 @@ pub struct PayoutRecord {
     pub timestamp: u64,
 }
 
+/// A page of payout records and the cursor to pass for the next page,
+/// `None` once the query is exhausted.
+#[contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct PayoutPage {
+    pub records: Vec<PayoutRecord>,
+    pub next_cursor: Option<u64>,
+}
+
+/// A page of release history entries, paged like `PayoutPage`.
+#[contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct ReleaseHistoryPage {
+    pub records: Vec<ProgramReleaseHistory>,
+    pub next_cursor: Option<u64>,
+}
+
 #[contracttype]
 #[derive(Clone, Debug, Eq, PartialEq)]
 pub struct ProgramInitializedEvent {
@@ -331,6 +229,7 @@ pub struct ProgramInitializedEvent {
 pub struct FundsLockedEvent {
     pub version: u32,
     pub program_id: String,
+    pub funder: Address,
     pub amount: i128,
     pub remaining_balance: i128,
 }
@@ -345,6 +244,18 @@ pub struct BatchPayoutEvent {
     pub remaining_balance: i128,
 }
 
+/// Outcome for one recipient of `batch_payout_partial`. Entries that could
+/// not be paid carry the id of the pending claim created for them.
+#[contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct BatchPayoutResult {
+    pub recipient: Address,
+    pub amount: i128,
+    pub paid: bool,
+    pub claim_id: Option<u64>,
+    pub error_code: u32,
+}
+
 #[contracttype]
 #[derive(Clone, Debug, Eq, PartialEq)]
 pub struct PayoutEvent {
@@ -355,31 +266,228 @@ pub struct PayoutEvent {
     pub remaining_balance: i128,
 }
 
+#[contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct PayoutFailedEvent {
+    pub version: u32,
+    pub program_id: String,
+    pub operation: Symbol,
+    pub recipient: Address,
+    pub amount: i128,
+}
+
+#[contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct PayoutKeyRotationScheduledEvent {
+    pub version: u32,
+    pub program_id: String,
+    pub new_key: Address,
+    pub effective_at: u64,
+}
+
+#[contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct PayoutKeyRotatedEvent {
+    pub version: u32,
+    pub program_id: String,
+    pub old_key: Address,
+    pub new_key: Address,
+}
+
+#[contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct PayoutKeyRevokedEvent {
+    pub version: u32,
+    pub program_id: String,
+    pub revoked_key: Address,
+}
+
+#[contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct ProgramClosedEvent {
+    pub version: u32,
+    pub program_id: String,
+    pub cancelled_schedules: u32,
+    pub closed_at: u64,
+}
+
+#[contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct ProgramRefundEvent {
+    pub version: u32,
+    pub program_id: String,
+    pub funder: Address,
+    pub amount: i128,
+}
+
+#[contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct ScheduleCancelledEvent {
+    pub version: u32,
+    pub program_id: String,
+    pub schedule_id: u64,
+    pub recipient: Address,
+    pub cancelled_amount: i128,
+}
+
+#[contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct ScheduleUpdatedEvent {
+    pub version: u32,
+    pub program_id: String,
+    pub schedule_id: u64,
+    pub old_recipient: Address,
+    pub new_recipient: Address,
+    pub old_release_timestamp: u64,
+    pub new_release_timestamp: u64,
+}
+
+#[contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct ProgramSettledEvent {
+    pub version: u32,
+    pub program_id: String,
+    pub total_refunded: i128,
+}
+
+#[contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct ClaimCreatedEvent {
+    pub version: u32,
+    pub program_id: String,
+    pub claim_id: u64,
+    pub recipient: Address,
+    pub amount: i128,
+    pub expires_at: u64,
+}
+
+#[contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct ClaimExecutedEvent {
+    pub version: u32,
+    pub program_id: String,
+    pub claim_id: u64,
+    pub recipient: Address,
+    pub amount: i128,
+}
+
+#[contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct ClaimCancelledEvent {
+    pub version: u32,
+    pub program_id: String,
+    pub claim_id: u64,
+    pub recipient: Address,
+    pub amount: i128,
+}
+
+/// A payout set aside for `recipient` until they claim it or it expires.
+#[contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct ClaimRecord {
+    pub claim_id: u64,
+    pub recipient: Address,
+    pub amount: i128,
+    pub expires_at: u64,
+    pub claimed: bool,
+}
+
+#[contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct PayoutApprovalEvent {
+    pub version: u32,
+    pub program_id: String,
+    pub recipient: Address,
+    pub amount: i128,
+    pub approver: Address,
+}
+
+/// Per-program signer set guarding payouts above `threshold_amount`.
+#[contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct MultisigConfig {
+    pub threshold_amount: i128,
+    pub signers: Vec<Address>,
+    pub required_signatures: u32,
+}
+
+/// Signer approvals collected for paying `amount` to `recipient`.
+#[contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct PayoutApproval {
+    pub recipient: Address,
+    pub amount: i128,
+    pub approvals: Vec<Address>,
+}
+
+/// Lifecycle of a program.
+///
+/// `Active` programs accept funds and pay out. `Closed` programs accept
+/// neither, and `Settled` programs have returned their leftovers to funders.
+#[contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub enum ProgramStatus {
+    Active,
+    Closed,
+    Settled,
+}
+
 #[contracttype]
 #[derive(Clone, Debug, Eq, PartialEq)]
 pub struct ProgramData {
     pub program_id: String,
     pub total_funds: i128,
     pub remaining_balance: i128,
+    pub total_refunded: i128,
+    pub pending_claims_total: i128, // Part of remaining_balance held for unclaimed payouts
+    pub committed_balance: i128,    // Part of remaining_balance reserved by schedules
     pub authorized_payout_key: Address,
-    pub payout_history: Vec<PayoutRecord>,
-    pub token_address: Address, // Token contract address for transfers
+    pub payout_key_revoked: bool, // Payouts frozen until a new key is installed
+    pub status: ProgramStatus,
+    pub end_timestamp: Option<u64>, // After this anyone may close the program
+    pub token_address: Address,     // Token contract address for transfers
+}
+
+/// A payout key change that takes effect once `effective_at` is reached.
+#[contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct PendingKeyRotation {
+    pub new_key: Address,
+    pub effective_at: u64,
+}
+
+/// Cumulative amount a single funder has locked into the program.
+#[contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct ProgramDeposit {
+    pub funder: Address,
+    pub amount: i128,
 }
 
 /// Storage key type for individual programs
 #[contracttype]
 #[derive(Clone, Debug, Eq, PartialEq)]
 pub enum DataKey {
-    Program(String),                 // program_id -> ProgramData
-    Admin,                           // Contract Admin
-    ReleaseSchedule(String, u64),    // program_id, schedule_id -> ProgramReleaseSchedule
-    ReleaseHistory(String),          // program_id -> Vec<ProgramReleaseHistory>
-    NextScheduleId(String),          // program_id -> next schedule_id
-    MultisigConfig(String),          // program_id -> MultisigConfig
-    PayoutApproval(String, Address), // program_id, recipient -> PayoutApproval
-    PendingClaim(String, u64),       // (program_id, schedule_id) -> ClaimRecord
-    ClaimWindow,                     // u64 seconds (global config)
-    PauseFlags,                      // PauseFlags struct
+    Program(String),                     // program_id -> ProgramData
+    Admin,                               // Contract Admin
+    ReleaseSchedule(String, u64),        // program_id, schedule_id -> ProgramReleaseSchedule
+    DueDays(String),                     // program_id -> Vec<day>, sorted days with due entries
+    DueBucket(String, u64),              // program_id, day -> Vec<(unlock_at, schedule_id)>, sorted
+    Deposits(String),                    // program_id -> Vec<ProgramDeposit>
+    PendingKeyRotation(String),          // program_id -> PendingKeyRotation
+    ReleaseHistory(String, u64),         // program_id, entry_id -> ProgramReleaseHistory
+    ReleaseHistoryCount(String),         // program_id -> number of release history entries
+    NextScheduleId(String),              // program_id -> next schedule_id
+    MultisigConfig(String),              // program_id -> MultisigConfig
+    PayoutApproval(String, Address),     // program_id, recipient -> PayoutApproval
+    RecipientPaidTotal(String, Address), // program_id, recipient -> i128 counted against the multisig threshold
+    Payout(String, u64),                 // program_id, payout_id -> PayoutRecord
+    PayoutCount(String),                 // program_id -> number of payout records
+    RecipientPayouts(String, Address),   // program_id, recipient -> Vec<payout_id>
+    PendingClaim(String, u64),           // (program_id, claim_id) -> ClaimRecord
+    NextClaimId(String),                 // program_id -> next claim_id
+    ClaimWindow,                         // u64 seconds (global config)
+    PauseFlags,                          // PauseFlags struct
 }
 
 #[contracttype]
@@ -398,6 +506,20 @@ pub struct PauseStateChanged {
     pub admin: Address,
 }
 
+/// How a schedule's amount becomes available, starting at `release_timestamp`.
+#[contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub enum ReleaseKind {
+    /// Full amount at `release_timestamp`.
+    OneShot,
+    /// (cliff_seconds, duration_seconds): nothing before the cliff, then
+    /// linear vesting from `release_timestamp` until the duration ends.
+    CliffLinear(u64, u64),
+    /// (period_seconds, tranche_count): equal tranches, the first at
+    /// `release_timestamp` and one more every period.
+    Periodic(u64, u32),
+}
+
 #[contracttype]
 #[derive(Clone, Debug, Eq, PartialEq)]
 pub struct ProgramReleaseSchedule {
@@ -405,15 +527,57 @@ pub struct ProgramReleaseSchedule {
     pub recipient: Address,
     pub amount: i128,
     pub release_timestamp: u64,
+    pub kind: ReleaseKind,
+    pub released_amount: i128,
     pub released: bool,
+    pub cancelled: bool,
+}
+
+/// Position in the due index: the key of the last entry a release run
+/// examined. Schedule ids start at 1, so `START` comes before every entry.
+#[contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct ReleaseCursor {
+    pub unlock_at: u64,
+    pub schedule_id: u64,
 }
 
+impl ReleaseCursor {
+    pub const START: ReleaseCursor = ReleaseCursor {
+        unlock_at: 0,
+        schedule_id: 0,
+    };
+}
+
+/// Outcome of one bounded `trigger_program_releases` run.
+#[contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct ReleaseRunResult {
+    pub released_count: u32,
+    pub skipped_count: u32,
+    pub next_cursor: ReleaseCursor,
+    pub completed: bool,
+}
+
+/// What happened to a schedule in a release history entry.
+#[contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub enum ReleaseAction {
+    Released,
+    Cancelled,
+    Updated,
+}
+
+/// Audit entry for a schedule. `amount` is what was paid for `Released`,
+/// what was withdrawn from the schedule for `Cancelled`, and the amount
+/// still outstanding for `Updated`.
 #[contracttype]
 #[derive(Clone, Debug, Eq, PartialEq)]
 pub struct ProgramReleaseHistory {
     pub schedule_id: u64,
     pub recipient: Address,
     pub amount: i128,
+    pub action: ReleaseAction,
     pub released_at: u64,
 }
 
@@ -423,11 +587,56 @@ pub struct ProgramAggregateStats {
     pub total_funds: i128,
     pub remaining_balance: i128,
     pub total_paid_out: i128,
-    pub payout_count: u32,
+    pub payout_count: u64,
     pub scheduled_count: u32,
     pub released_count: u32,
 }
 
+#[contracterror]
+#[derive(Copy, Clone, Debug, Eq, PartialEq, PartialOrd, Ord)]
+#[repr(u32)]
+pub enum Error {
+    AlreadyInitialized = 1,
+    NotInitialized = 2,
+    ProgramExists = 3,
+    ProgramNotFound = 4,
+    InvalidProgramId = 5,
+    Unauthorized = 6,
+    /// Returned when an amount is zero or negative
+    InvalidAmount = 7,
+    /// Returned when a payout exceeds the uncommitted program balance
+    InsufficientBalance = 8,
+    /// Returned when a new schedule would commit more than is available
+    InsufficientAvailableBalance = 9,
+    FundsPaused = 10,
+    ProgramNotActive = 11,
+    PayoutKeyRevoked = 12,
+    PayoutKeyAlreadyRevoked = 13,
+    NoPendingKeyRotation = 14,
+    KeyRotationNotEffective = 15,
+    EmptyBatch = 16,
+    BatchSizeMismatch = 17,
+    AmountOverflow = 18,
+    NoFundsReceived = 19,
+    InvalidEndTimestamp = 21,
+    ClaimNotFound = 22,
+    ClaimAlreadyExecuted = 23,
+    ClaimExpired = 24,
+    ClaimNotExpired = 25,
+    /// Returned when a payout above the multisig threshold lacks approvals
+    InsufficientApprovals = 26,
+    NotMultisigSigner = 27,
+    ApprovalNotFound = 28,
+    ApprovalAmountMismatch = 29,
+    InvalidMultisigConfig = 30,
+    ScheduleNotFound = 31,
+    ScheduleNotPending = 32,
+    NothingVested = 33,
+    InvalidSchedulePeriod = 34,
+    /// Returned while the circuit breaker is open (matches `ERR_CIRCUIT_OPEN`)
+    CircuitOpen = 1001,
+}
+
 #[contract]
 pub struct ProgramEscrowContract;
 
@@ -435,6 +644,11 @@ pub struct ProgramEscrowContract;
 impl ProgramEscrowContract {
     /// Initialize a new program escrow
     ///
+    /// Any number of programs can be hosted by a single deployment; each one
+    /// is stored under its own `program_id` and listed in the program index.
+    /// The payout key must authorize the registration, and so must the
+    /// contract admin once one has been set with `initialize_contract`.
+    ///
     /// # Arguments
     /// * `program_id` - Unique identifier for the program/hackathon
     /// * `authorized_payout_key` - Address authorized to trigger payouts (backend)
@@ -447,30 +661,49 @@ impl ProgramEscrowContract {
         program_id: String,
         authorized_payout_key: Address,
         token_address: Address,
-    ) -> ProgramData {
-        // Check if program already exists
-        if env.storage().instance().has(&PROGRAM_DATA) {
-            panic!("Program already initialized");
+    ) -> Result<ProgramData, Error> {
+        if let Some(admin) = env.storage().instance().get::<_, Address>(&DataKey::Admin) {
+            admin.require_auth();
+        }
+        authorized_payout_key.require_auth();
+
+        if program_id.is_empty() {
+            return Err(Error::InvalidProgramId);
+        }
+
+        let program_key = DataKey::Program(program_id.clone());
+        if env.storage().persistent().has(&program_key) {
+            return Err(Error::ProgramExists);
         }
 
         let program_data = ProgramData {
             program_id: program_id.clone(),
             total_funds: 0,
             remaining_balance: 0,
+            committed_balance: 0,
+            total_refunded: 0,
+            pending_claims_total: 0,
             authorized_payout_key: authorized_payout_key.clone(),
-            payout_history: vec![&env],
+            payout_key_revoked: false,
+            status: ProgramStatus::Active,
+            end_timestamp: None,
             token_address: token_address.clone(),
         };
 
-        // Store program data
-        env.storage().instance().set(&PROGRAM_DATA, &program_data);
-        env.storage()
-            .instance()
-            .set(&SCHEDULES, &Vec::<ProgramReleaseSchedule>::new(&env));
+        // Store program data and register it in the index
+        env.storage().persistent().set(&program_key, &program_data);
         env.storage()
+            .persistent()
+            .set(&DataKey::NextScheduleId(program_id.clone()), &1_u64);
+
+        let mut index: Vec<String> = env
+            .storage()
             .instance()
-            .set(&RELEASE_HISTORY, &Vec::<ProgramReleaseHistory>::new(&env));
-        env.storage().instance().set(&NEXT_SCHEDULE_ID, &1_u64);
+            .get(&PROGRAM_INDEX)
+            .unwrap_or_else(|| Vec::new(&env));
+        index.push_back(program_id.clone());
+        env.storage().instance().set(&PROGRAM_INDEX, &index);
+        Self::index_payout_key(&env, &authorized_payout_key, &program_id);
 
         // Emit ProgramInitialized event
         env.events().publish(
@@ -484,7 +717,7 @@ impl ProgramEscrowContract {
             },
         );
 
-        program_data
+        Ok(program_data)
     }
 
     /// Check if a program exists
@@ -493,41 +726,101 @@ impl ProgramEscrowContract {
     /// * `bool` - True if program exists, false otherwise
     pub fn program_exists(env: Env, program_id: String) -> bool {
         let program_key = DataKey::Program(program_id);
-        env.storage().instance().has(&program_key)
+        env.storage().persistent().has(&program_key)
+    }
+
+    /// Get the number of programs hosted by this contract
+    pub fn get_program_count(env: Env) -> u32 {
+        Self::list_programs(env).len()
+    }
+
+    /// List the ids of all programs hosted by this contract, in creation order
+    pub fn list_programs(env: Env) -> Vec<String> {
+        env.storage()
+            .instance()
+            .get(&PROGRAM_INDEX)
+            .unwrap_or_else(|| Vec::new(&env))
     }
 
     // ========================================================================
     // Fund Management
     // ========================================================================
 
-    /// Lock initial funds into the program escrow
+    /// Lock funds into the program escrow
+    ///
+    /// Pulls `amount` tokens from `funder` into the contract and credits the
+    /// program with what actually arrived, so the recorded balance can never
+    /// run ahead of the tokens the contract holds.
     ///
     /// # Arguments
+    /// * `program_id` - Program to credit
+    /// * `funder` - Address providing the funds (must authorize)
     /// * `amount` - Amount of funds to lock (in native token units)
     ///
     /// # Returns
     /// Updated ProgramData with locked funds
-    pub fn lock_program_funds(env: Env, amount: i128) -> ProgramData {
+    pub fn lock_program_funds(
+        env: Env,
+        program_id: String,
+        funder: Address,
+        amount: i128,
+    ) -> Result<ProgramData, Error> {
         if Self::check_paused(&env, symbol_short!("lock")) {
-            panic!("Funds Paused");
+            return Err(Error::FundsPaused);
         }
 
         if amount <= 0 {
-            panic!("Amount must be greater than zero");
+            return Err(Error::InvalidAmount);
         }
 
-        let mut program_data: ProgramData = env
-            .storage()
-            .instance()
-            .get(&PROGRAM_DATA)
-            .unwrap_or_else(|| panic!("Program not initialized"));
+        let mut program_data = Self::load_program(&env, &program_id)?;
+
+        if program_data.status != ProgramStatus::Active {
+            return Err(Error::ProgramNotActive);
+        }
+
+        funder.require_auth();
+
+        // Transfer tokens in and measure what the contract actually received
+        let contract_address = env.current_contract_address();
+        let token_client = token::Client::new(&env, &program_data.token_address);
+        let balance_before = token_client.balance(&contract_address);
+        token_client.transfer(&funder, &contract_address, &amount);
+        let balance_after = token_client.balance(&contract_address);
+
+        let received = balance_after - balance_before;
+        if received <= 0 {
+            return Err(Error::NoFundsReceived);
+        }
 
         // Update balances
-        program_data.total_funds += amount;
-        program_data.remaining_balance += amount;
+        program_data.total_funds += received;
+        program_data.remaining_balance += received;
+
+        // Record the funder in the deposit ledger
+        let mut deposits = Self::get_program_deposits(env.clone(), program_id.clone());
+        let mut found = false;
+        for i in 0..deposits.len() {
+            let mut deposit = deposits.get(i).unwrap();
+            if deposit.funder == funder {
+                deposit.amount += received;
+                deposits.set(i, deposit);
+                found = true;
+                break;
+            }
+        }
+        if !found {
+            deposits.push_back(ProgramDeposit {
+                funder: funder.clone(),
+                amount: received,
+            });
+        }
 
         // Store updated data
-        env.storage().instance().set(&PROGRAM_DATA, &program_data);
+        Self::store_program(&env, &program_data);
+        env.storage()
+            .persistent()
+            .set(&DataKey::Deposits(program_id), &deposits);
 
         // Emit FundsLocked event
         env.events().publish(
@@ -535,12 +828,24 @@ impl ProgramEscrowContract {
             FundsLockedEvent {
                 version: EVENT_VERSION_V2,
                 program_id: program_data.program_id.clone(),
-                amount,
+                funder,
+                amount: received,
                 remaining_balance: program_data.remaining_balance,
             },
         );
 
-        program_data
+        Ok(program_data)
+    }
+
+    /// Get the deposit ledger
+    ///
+    /// # Returns
+    /// One entry per funder with the total amount they have locked
+    pub fn get_program_deposits(env: Env, program_id: String) -> Vec<ProgramDeposit> {
+        env.storage()
+            .persistent()
+            .get(&DataKey::Deposits(program_id))
+            .unwrap_or_else(|| Vec::new(&env))
     }
 
     // ========================================================================
@@ -549,17 +854,24 @@ impl ProgramEscrowContract {
 
     /// Initialize the contract with an admin.
     /// This must be called before any admin protected functions (like pause) can be used.
-    pub fn initialize_contract(env: Env, admin: Address) {
+    pub fn initialize_contract(env: Env, admin: Address) -> Result<(), Error> {
         if env.storage().instance().has(&DataKey::Admin) {
-            panic!("Already initialized");
+            return Err(Error::AlreadyInitialized);
         }
         env.storage().instance().set(&DataKey::Admin, &admin);
+
+        Ok(())
     }
 
     /// Update pause flags (admin only)
-    pub fn set_paused(env: Env, lock: Option<bool>, release: Option<bool>, refund: Option<bool>) {
+    pub fn set_paused(
+        env: Env,
+        lock: Option<bool>,
+        release: Option<bool>,
+        refund: Option<bool>,
+    ) -> Result<(), Error> {
         if !env.storage().instance().has(&DataKey::Admin) {
-            panic!("Not initialized");
+            return Err(Error::NotInitialized);
         }
 
         let admin: Address = env.storage().instance().get(&DataKey::Admin).unwrap();
@@ -592,6 +904,8 @@ impl ProgramEscrowContract {
         }
 
         env.storage().instance().set(&DataKey::PauseFlags, &flags);
+
+        Ok(())
     }
 
     /// Get current pause flags
@@ -619,1744 +933,2599 @@ impl ProgramEscrowContract {
         false
     }
 
-    // ========================================================================
-    // Payout Functions
-    // ========================================================================
-
-    /// Execute batch payouts to multiple recipients
-    ///
-    /// # Arguments
-    /// * `recipients` - Vector of recipient addresses
-    /// * `amounts` - Vector of amounts (must match recipients length)
-    ///
-    /// # Returns
-    /// Updated ProgramData after payouts
-    pub fn batch_payout(env: Env, recipients: Vec<Address>, amounts: Vec<i128>) -> ProgramData {
-        // Reentrancy guard: Check and set
-        reentrancy_guard::check_not_entered(&env);
-        reentrancy_guard::set_entered(&env);
+    fn load_program(env: &Env, program_id: &String) -> Result<ProgramData, Error> {
+        env.storage()
+            .persistent()
+            .get(&DataKey::Program(program_id.clone()))
+            .ok_or(Error::ProgramNotFound)
+    }
 
-        if Self::check_paused(&env, symbol_short!("release")) {
-            reentrancy_guard::clear_entered(&env);
-            panic!("Funds Paused");
+    fn store_program(env: &Env, program_data: &ProgramData) {
+        env.storage().persistent().set(
+            &DataKey::Program(program_data.program_id.clone()),
+            program_data,
+        );
+    }
+
+    /// Read every schedule of a program, in creation order. Only views use
+    /// this; state-changing paths go through the due index.
+    fn load_schedules(env: &Env, program_id: &String) -> Vec<ProgramReleaseSchedule> {
+        let next_id: u64 = env
+            .storage()
+            .persistent()
+            .get(&DataKey::NextScheduleId(program_id.clone()))
+            .unwrap_or(1_u64);
+        let mut schedules = Vec::new(env);
+        for schedule_id in 1..next_id {
+            if let Some(schedule) = env
+                .storage()
+                .persistent()
+                .get(&DataKey::ReleaseSchedule(program_id.clone(), schedule_id))
+            {
+                schedules.push_back(schedule);
+            }
         }
+        schedules
+    }
 
-        // Verify authorization
-        let program_data: ProgramData =
-            env.storage()
-                .instance()
-                .get(&PROGRAM_DATA)
-                .unwrap_or_else(|| {
-                    reentrancy_guard::clear_entered(&env);
-                    panic!("Program not initialized")
-                });
+    fn load_schedule(
+        env: &Env,
+        program_id: &String,
+        schedule_id: u64,
+    ) -> Result<ProgramReleaseSchedule, Error> {
+        env.storage()
+            .persistent()
+            .get(&DataKey::ReleaseSchedule(program_id.clone(), schedule_id))
+            .ok_or(Error::ScheduleNotFound)
+    }
 
-        program_data.authorized_payout_key.require_auth();
+    fn store_schedule(env: &Env, program_id: &String, schedule: &ProgramReleaseSchedule) {
+        env.storage().persistent().set(
+            &DataKey::ReleaseSchedule(program_id.clone(), schedule.schedule_id),
+            schedule,
+        );
+    }
 
-        // Validate input lengths match
-        if recipients.len() != amounts.len() {
-            reentrancy_guard::clear_entered(&env);
-            panic!("Recipients and amounts vectors must have the same length");
+    /// Days that hold pending schedules, oldest first. Together with the
+    /// per-day buckets this orders pending schedules by the time they first
+    /// unlock funds, without keeping them all in one storage entry.
+    fn load_due_days(env: &Env, program_id: &String) -> Vec<u64> {
+        env.storage()
+            .persistent()
+            .get(&DataKey::DueDays(program_id.clone()))
+            .unwrap_or_else(|| Vec::new(env))
+    }
+
+    fn store_due_days(env: &Env, program_id: &String, days: &Vec<u64>) {
+        let key = DataKey::DueDays(program_id.clone());
+        if days.is_empty() {
+            env.storage().persistent().remove(&key);
+        } else {
+            env.storage().persistent().set(&key, days);
         }
+    }
 
-        if recipients.len() == 0 {
-            reentrancy_guard::clear_entered(&env);
-            panic!("Cannot process empty batch");
+    /// Due-index entries unlocking on `day`, sorted.
+    fn load_due_bucket(env: &Env, program_id: &String, day: u64) -> Vec<(u64, u64)> {
+        env.storage()
+            .persistent()
+            .get(&DataKey::DueBucket(program_id.clone(), day))
+            .unwrap_or_else(|| Vec::new(env))
+    }
+
+    fn store_due_bucket(env: &Env, program_id: &String, day: u64, bucket: &Vec<(u64, u64)>) {
+        let key = DataKey::DueBucket(program_id.clone(), day);
+        if bucket.is_empty() {
+            env.storage().persistent().remove(&key);
+        } else {
+            env.storage().persistent().set(&key, bucket);
         }
+    }
 
-        // Calculate total payout amount
-        let mut total_payout: i128 = 0;
-        for amount in amounts.iter() {
-            if amount <= 0 {
-                reentrancy_guard::clear_entered(&env);
-                panic!("All amounts must be greater than zero");
+    /// Due-index entry of a schedule: when it first unlocks funds, then its id.
+    fn due_entry(schedule: &ProgramReleaseSchedule) -> (u64, u64) {
+        let unlock_at = match schedule.kind {
+            ReleaseKind::CliffLinear(cliff_seconds, _) => {
+                schedule.release_timestamp.saturating_add(cliff_seconds)
+            }
+            _ => schedule.release_timestamp,
+        };
+        (unlock_at, schedule.schedule_id)
+    }
+
+    fn index_schedule(env: &Env, program_id: &String, schedule: &ProgramReleaseSchedule) {
+        let entry = Self::due_entry(schedule);
+        let day = entry.0 / DUE_BUCKET_SECONDS;
+        let mut bucket = Self::load_due_bucket(env, program_id, day);
+        if bucket.is_empty() {
+            let mut days = Self::load_due_days(env, program_id);
+            if let Err(position) = days.binary_search(day) {
+                days.insert(position, day);
+                Self::store_due_days(env, program_id, &days);
             }
-            total_payout = total_payout.checked_add(amount).unwrap_or_else(|| {
-                reentrancy_guard::clear_entered(&env);
-                panic!("Payout amount overflow")
-            });
         }
+        let position = match bucket.binary_search(entry) {
+            Ok(position) | Err(position) => position,
+        };
+        bucket.insert(position, entry);
+        Self::store_due_bucket(env, program_id, day, &bucket);
+    }
 
-        // Validate sufficient balance
-        if total_payout > program_data.remaining_balance {
-            reentrancy_guard::clear_entered(&env);
-            panic!("Insufficient balance");
+    fn unindex_schedule(env: &Env, program_id: &String, schedule: &ProgramReleaseSchedule) {
+        let entry = Self::due_entry(schedule);
+        let day = entry.0 / DUE_BUCKET_SECONDS;
+        let mut bucket = Self::load_due_bucket(env, program_id, day);
+        if let Ok(position) = bucket.binary_search(entry) {
+            bucket.remove(position);
+            Self::store_due_bucket(env, program_id, day, &bucket);
+            if bucket.is_empty() {
+                let mut days = Self::load_due_days(env, program_id);
+                if let Ok(position) = days.binary_search(day) {
+                    days.remove(position);
+                    Self::store_due_days(env, program_id, &days);
+                }
+            }
         }
+    }
 
-        // Execute transfers
-        let mut updated_history = program_data.payout_history.clone();
-        let timestamp = env.ledger().timestamp();
-        let contract_address = env.current_contract_address();
-        let token_client = token::Client::new(&env, &program_data.token_address);
+    /// Number of release history entries of a program; also the next entry id.
+    fn release_history_count(env: &Env, program_id: &String) -> u64 {
+        env.storage()
+            .persistent()
+            .get(&DataKey::ReleaseHistoryCount(program_id.clone()))
+            .unwrap_or(0_u64)
+    }
 
-        for i in 0..recipients.len() {
-            let recipient = recipients.get(i).unwrap();
-            let amount = amounts.get(i).unwrap();
+    fn load_release_entry(env: &Env, program_id: &String, entry_id: u64) -> ProgramReleaseHistory {
+        env.storage()
+            .persistent()
+            .get(&DataKey::ReleaseHistory(program_id.clone(), entry_id))
+            .unwrap()
+    }
 
-            // Transfer funds from contract to recipient
-            token_client.transfer(&contract_address, &recipient, &amount);
+    /// Append an entry to a program's release history.
+    fn record_release(
+        env: &Env,
+        program_id: &String,
+        schedule: &ProgramReleaseSchedule,
+        amount: i128,
+        action: ReleaseAction,
+    ) {
+        let entry_id = Self::release_history_count(env, program_id);
+        env.storage().persistent().set(
+            &DataKey::ReleaseHistory(program_id.clone(), entry_id),
+            &ProgramReleaseHistory {
+                schedule_id: schedule.schedule_id,
+                recipient: schedule.recipient.clone(),
+                amount,
+                action,
+                released_at: env.ledger().timestamp(),
+            },
+        );
+        env.storage().persistent().set(
+            &DataKey::ReleaseHistoryCount(program_id.clone()),
+            &(entry_id + 1),
+        );
+    }
 
-            // Record payout
-            let payout_record = PayoutRecord {
+    /// Number of payout records of a program; also the next payout id.
+    fn payout_count(env: &Env, program_id: &String) -> u64 {
+        env.storage()
+            .persistent()
+            .get(&DataKey::PayoutCount(program_id.clone()))
+            .unwrap_or(0_u64)
+    }
+
+    fn load_payout(env: &Env, program_id: &String, payout_id: u64) -> PayoutRecord {
+        env.storage()
+            .persistent()
+            .get(&DataKey::Payout(program_id.clone(), payout_id))
+            .unwrap()
+    }
+
+    /// Ids of the payouts made to `recipient`, oldest first.
+    fn load_recipient_payouts(env: &Env, program_id: &String, recipient: &Address) -> Vec<u64> {
+        env.storage()
+            .persistent()
+            .get(&DataKey::RecipientPayouts(
+                program_id.clone(),
+                recipient.clone(),
+            ))
+            .unwrap_or_else(|| Vec::new(env))
+    }
+
+    /// Append a payout record and index it by recipient.
+    ///
+    /// Ids are handed out in ledger order, so the record log itself is
+    /// sorted by timestamp and doubles as the timestamp index.
+    fn record_payout(
+        env: &Env,
+        program_id: &String,
+        recipient: Address,
+        amount: i128,
+        timestamp: u64,
+    ) {
+        let payout_id = Self::payout_count(env, program_id);
+        let mut recipient_payouts = Self::load_recipient_payouts(env, program_id, &recipient);
+        recipient_payouts.push_back(payout_id);
+        env.storage().persistent().set(
+            &DataKey::RecipientPayouts(program_id.clone(), recipient.clone()),
+            &recipient_payouts,
+        );
+        env.storage().persistent().set(
+            &DataKey::Payout(program_id.clone(), payout_id),
+            &PayoutRecord {
                 recipient,
                 amount,
                 timestamp,
-            };
-            updated_history.push_back(payout_record);
-        }
+            },
+        );
+        env.storage()
+            .persistent()
+            .set(&DataKey::PayoutCount(program_id.clone()), &(payout_id + 1));
+    }
 
-        // Update program data
-        let mut updated_data = program_data.clone();
-        updated_data.remaining_balance -= total_payout;
-        updated_data.payout_history = updated_history;
+    /// Id of the first payout recorded at or after `timestamp`.
+    fn first_payout_at(env: &Env, program_id: &String, timestamp: u64) -> u64 {
+        let mut low = 0_u64;
+        let mut high = Self::payout_count(env, program_id);
+        while low < high {
+            let mid = low + (high - low) / 2;
+            if Self::load_payout(env, program_id, mid).timestamp < timestamp {
+                low = mid + 1;
+            } else {
+                high = mid;
+            }
+        }
+        low
+    }
 
-        // Store updated data
-        env.storage().instance().set(&PROGRAM_DATA, &updated_data);
+    // ========================================================================
+    // Program Lifecycle
+    // ========================================================================
 
-        // Emit BatchPayout event
-        env.events().publish(
-            (BATCH_PAYOUT,),
-            BatchPayoutEvent {
-                version: EVENT_VERSION_V2,
-                program_id: updated_data.program_id.clone(),
-                recipient_count: recipients.len() as u32,
-                total_amount: total_payout,
-                remaining_balance: updated_data.remaining_balance,
-            },
-        );
+    /// Set the end timestamp of an active program (payout key only)
+    ///
+    /// Once the end timestamp has passed anyone can close the program, so
+    /// leftovers cannot stay locked if the organizer disappears.
+    pub fn set_program_end_time(
+        env: Env,
+        program_id: String,
+        end_timestamp: u64,
+    ) -> Result<ProgramData, Error> {
+        let mut program_data = Self::load_program(&env, &program_id)?;
+        if program_data.status != ProgramStatus::Active {
+            return Err(Error::ProgramNotActive);
+        }
+        if program_data.payout_key_revoked {
+            return Err(Error::PayoutKeyRevoked);
+        }
+        program_data.authorized_payout_key.require_auth();
 
-        // Clear reentrancy guard before returning
-        reentrancy_guard::clear_entered(&env);
+        if end_timestamp < env.ledger().timestamp() {
+            return Err(Error::InvalidEndTimestamp);
+        }
 
-        updated_data
+        program_data.end_timestamp = Some(end_timestamp);
+        Self::store_program(&env, &program_data);
+        Ok(program_data)
     }
 
-    /// Execute a single payout to one recipient
+    /// Close a program and return its leftover balance to the funders
     ///
-    /// # Arguments
-    /// * `recipient` - Address of the recipient
-    /// * `amount` - Amount to transfer
+    /// Unreleased schedules are cancelled and the remaining balance is split
+    /// across the deposit ledger pro rata to what each funder locked; rounding
+    /// dust goes to the first funder. Before the end timestamp only the payout
+    /// key can close the program, and not while it is revoked; afterwards
+    /// anyone can.
+    ///
+    /// Authorized claims stay claimable. The program remains `Closed` until
+    /// they are claimed, cancelled or expired, and becomes `Settled` after.
+    /// Refunds go through the circuit breaker; a funder whose refund fails
+    /// gets a claim for their share instead.
     ///
     /// # Returns
-    /// Updated ProgramData after payout
-    pub fn single_payout(env: Env, recipient: Address, amount: i128) -> ProgramData {
-        // Reentrancy guard: Check and set
-        reentrancy_guard::check_not_entered(&env);
-        reentrancy_guard::set_entered(&env);
+    /// The settled ProgramData
+    pub fn close_program(env: Env, program_id: String) -> Result<ProgramData, Error> {
+        if Self::check_paused(&env, symbol_short!("refund")) {
+            return Err(Error::FundsPaused);
+        }
+        Self::require_circuit_closed(&env)?;
 
-        if Self::check_paused(&env, symbol_short!("release")) {
-            reentrancy_guard::clear_entered(&env);
-            panic!("Funds Paused");
+        let mut program_data = Self::load_program(&env, &program_id)?;
+        if program_data.status != ProgramStatus::Active {
+            return Err(Error::ProgramNotActive);
         }
 
-        // Verify authorization
-        let program_data: ProgramData =
-            env.storage()
-                .instance()
-                .get(&PROGRAM_DATA)
-                .unwrap_or_else(|| {
-                    reentrancy_guard::clear_entered(&env);
-                    panic!("Program not initialized")
-                });
+        let now = env.ledger().timestamp();
+        let ended = match program_data.end_timestamp {
+            Some(end) => now >= end,
+            None => false,
+        };
+        if !ended {
+            if program_data.payout_key_revoked {
+                return Err(Error::PayoutKeyRevoked);
+            }
+            program_data.authorized_payout_key.require_auth();
+        }
 
-        program_data.authorized_payout_key.require_auth();
+        // Cancel everything that has not been released yet
+        let mut cancelled_schedules: u32 = 0;
+        for day in Self::load_due_days(&env, &program_id).iter() {
+            for (_, schedule_id) in Self::load_due_bucket(&env, &program_id, day).iter() {
+                let mut schedule = Self::load_schedule(&env, &program_id, schedule_id)?;
+                Self::cancel_schedule(&env, &mut program_data, &mut schedule);
+                cancelled_schedules += 1;
+            }
+            Self::store_due_bucket(&env, &program_id, day, &Vec::new(&env));
+        }
+        Self::store_due_days(&env, &program_id, &Vec::new(&env));
 
-        // Validate amount
-        if amount <= 0 {
-            reentrancy_guard::clear_entered(&env);
-            panic!("Amount must be greater than zero");
+        program_data.status = ProgramStatus::Closed;
+        env.events().publish(
+            (PROGRAM_CLOSED,),
+            ProgramClosedEvent {
+                version: EVENT_VERSION_V2,
+                program_id: program_id.clone(),
+                cancelled_schedules,
+                closed_at: now,
+            },
+        );
+
+        Self::settle_leftovers(&env, &mut program_data);
+        Self::store_program(&env, &program_data);
+
+        Ok(program_data)
+    }
+
+    /// Refund whatever a closed program still holds and mark it settled once
+    /// no authorized claims are outstanding.
+    fn settle_leftovers(env: &Env, program_data: &mut ProgramData) {
+        let refunded = Self::refund_to_funders(env, program_data);
+        program_data.remaining_balance -= refunded;
+        program_data.total_refunded += refunded;
+
+        if program_data.pending_claims_total == 0 {
+            program_data.status = ProgramStatus::Settled;
+            env.events().publish(
+                (PROGRAM_SETTLED,),
+                ProgramSettledEvent {
+                    version: EVENT_VERSION_V2,
+                    program_id: program_data.program_id.clone(),
+                    total_refunded: program_data.total_refunded,
+                },
+            );
         }
+    }
 
-        // Validate sufficient balance
-        if amount > program_data.remaining_balance {
-            reentrancy_guard::clear_entered(&env);
-            panic!("Insufficient balance");
+    /// Pay the remaining balance not held for claims back to the deposit
+    /// ledger pro rata. A share that cannot be transferred is turned into a
+    /// claim for its funder. Returns the amount refunded.
+    fn refund_to_funders(env: &Env, program_data: &mut ProgramData) -> i128 {
+        let remaining = program_data.remaining_balance - program_data.pending_claims_total;
+        let deposits = Self::get_program_deposits(env.clone(), program_data.program_id.clone());
+        if remaining <= 0 || deposits.is_empty() {
+            return 0;
         }
 
-        // Transfer funds from contract to recipient
-        let contract_address = env.current_contract_address();
-        let token_client = token::Client::new(&env, &program_data.token_address);
-        token_client.transfer(&contract_address, &recipient, &amount);
+        let mut total_deposited: i128 = 0;
+        for deposit in deposits.iter() {
+            total_deposited += deposit.amount;
+        }
 
-        // Record payout
-        let timestamp = env.ledger().timestamp();
-        let payout_record = PayoutRecord {
-            recipient: recipient.clone(),
-            amount,
-            timestamp,
-        };
+        // Compute shares first so the rounding dust can go to the first funder
+        let mut shares: Vec<i128> = Vec::new(env);
+        let mut distributed: i128 = 0;
+        for deposit in deposits.iter() {
+            let share = remaining * deposit.amount / total_deposited;
+            shares.push_back(share);
+            distributed += share;
+        }
+        let dust = remaining - distributed;
+        shares.set(0, shares.get(0).unwrap() + dust);
+
+        let mut refunded: i128 = 0;
+        for i in 0..deposits.len() {
+            let funder = deposits.get(i).unwrap().funder;
+            let amount = shares.get(i).unwrap();
+            if amount == 0 {
+                continue;
+            }
+            if !Self::guarded_transfer(
+                env,
+                &program_data.token_address,
+                &program_data.program_id,
+                symbol_short!("refund"),
+                &funder,
+                amount,
+            ) {
+                Self::create_claim(env, program_data, funder, amount);
+                continue;
+            }
+            refunded += amount;
+            env.events().publish(
+                (PROGRAM_REFUND,),
+                ProgramRefundEvent {
+                    version: EVENT_VERSION_V2,
+                    program_id: program_data.program_id.clone(),
+                    funder,
+                    amount,
+                },
+            );
+        }
 
-        let mut updated_history = program_data.payout_history.clone();
-        updated_history.push_back(payout_record);
+        refunded
+    }
 
-        // Update program data
-        let mut updated_data = program_data.clone();
-        updated_data.remaining_balance -= amount;
-        updated_data.payout_history = updated_history;
+    // ========================================================================
+    // Payout Key Management
+    // ========================================================================
 
-        // Store updated data
-        env.storage().instance().set(&PROGRAM_DATA, &updated_data);
+    /// Rotate the authorized payout key of a program (admin only)
+    ///
+    /// With a `delay_seconds` of zero the new key is installed immediately.
+    /// Otherwise the rotation is queued and can be applied by anyone through
+    /// `apply_payout_key_rotation` once the delay has elapsed; scheduling a new
+    /// rotation replaces any pending one.
+    ///
+    /// # Arguments
+    /// * `program_id` - Program whose key is rotated
+    /// * `new_key` - Address that will authorize payouts
+    /// * `delay_seconds` - Seconds before the new key takes effect
+    pub fn rotate_payout_key(
+        env: Env,
+        program_id: String,
+        new_key: Address,
+        delay_seconds: u64,
+    ) -> Result<(), Error> {
+        Self::require_admin(&env)?;
+        let program_data = Self::load_program(&env, &program_id)?;
+        let rotation_key = DataKey::PendingKeyRotation(program_id.clone());
+
+        if delay_seconds == 0 {
+            env.storage().persistent().remove(&rotation_key);
+            Self::install_payout_key(&env, program_data, new_key);
+            return Ok(());
+        }
+
+        let effective_at = env
+            .ledger()
+            .timestamp()
+            .checked_add(delay_seconds)
+            .ok_or(Error::AmountOverflow)?;
+        env.storage().persistent().set(
+            &rotation_key,
+            &PendingKeyRotation {
+                new_key: new_key.clone(),
+                effective_at,
+            },
+        );
 
-        // Emit Payout event
         env.events().publish(
-            (PAYOUT,),
-            PayoutEvent {
+            (KEY_ROTATION_SCHEDULED,),
+            PayoutKeyRotationScheduledEvent {
                 version: EVENT_VERSION_V2,
-                program_id: updated_data.program_id.clone(),
-                recipient,
-                amount,
-                remaining_balance: updated_data.remaining_balance,
+                program_id,
+                new_key,
+                effective_at,
             },
         );
 
-        // Clear reentrancy guard before returning
-        reentrancy_guard::clear_entered(&env);
+        Ok(())
+    }
+
+    /// Install a pending payout key once its delay has elapsed
+    pub fn apply_payout_key_rotation(env: Env, program_id: String) -> Result<(), Error> {
+        let rotation_key = DataKey::PendingKeyRotation(program_id.clone());
+        let rotation: PendingKeyRotation = env
+            .storage()
+            .persistent()
+            .get(&rotation_key)
+            .ok_or(Error::NoPendingKeyRotation)?;
+
+        if env.ledger().timestamp() < rotation.effective_at {
+            return Err(Error::KeyRotationNotEffective);
+        }
+
+        let program_data = Self::load_program(&env, &program_id)?;
+        env.storage().persistent().remove(&rotation_key);
+        Self::install_payout_key(&env, program_data, rotation.new_key);
 
-        updated_data
+        Ok(())
     }
 
-    /// Get program information
-    ///
-    /// # Returns
-    /// ProgramData containing all program information
-    pub fn get_program_info(env: Env) -> ProgramData {
+    /// Cancel a pending payout key rotation (admin only)
+    pub fn cancel_payout_key_rotation(env: Env, program_id: String) -> Result<(), Error> {
+        Self::require_admin(&env)?;
+        let rotation_key = DataKey::PendingKeyRotation(program_id);
+        if !env.storage().persistent().has(&rotation_key) {
+            return Err(Error::NoPendingKeyRotation);
+        }
+        env.storage().persistent().remove(&rotation_key);
+
+        Ok(())
+    }
+
+    /// Get the pending payout key rotation of a program, if any
+    pub fn get_pending_key_rotation(env: Env, program_id: String) -> Option<PendingKeyRotation> {
         env.storage()
-            .instance()
-            .get(&PROGRAM_DATA)
-            .unwrap_or_else(|| panic!("Program not initialized"))
+            .persistent()
+            .get(&DataKey::PendingKeyRotation(program_id))
     }
 
-    /// Get remaining balance
+    /// Emergency revoke of a program's payout key (admin only)
     ///
-    /// # Returns
-    /// Current remaining balance
-    pub fn get_remaining_balance(env: Env) -> i128 {
-        let program_data: ProgramData = env
-            .storage()
-            .instance()
-            .get(&PROGRAM_DATA)
-            .unwrap_or_else(|| panic!("Program not initialized"));
+    /// Payouts and scheduled releases are frozen until a new key is installed
+    /// through `rotate_payout_key`.
+    pub fn revoke_payout_key(env: Env, program_id: String) -> Result<(), Error> {
+        Self::require_admin(&env)?;
+        let mut program_data = Self::load_program(&env, &program_id)?;
+        if program_data.payout_key_revoked {
+            return Err(Error::PayoutKeyAlreadyRevoked);
+        }
 
-        program_data.remaining_balance
+        program_data.payout_key_revoked = true;
+        Self::store_program(&env, &program_data);
+        Self::unindex_payout_key(&env, &program_data.authorized_payout_key, &program_id);
+
+        env.events().publish(
+            (KEY_REVOKED,),
+            PayoutKeyRevokedEvent {
+                version: EVENT_VERSION_V2,
+                program_id,
+                revoked_key: program_data.authorized_payout_key,
+            },
+        );
+
+        Ok(())
     }
 
-    /// Create a release schedule entry that can be triggered at/after `release_timestamp`.
-    pub fn create_program_release_schedule(
-        env: Env,
-        recipient: Address,
-        amount: i128,
-        release_timestamp: u64,
-    ) -> ProgramReleaseSchedule {
-        let program_data: ProgramData = env
+    /// Get the programs a payout key is currently authorized for
+    pub fn get_programs_by_payout_key(env: Env, payout_key: Address) -> Vec<String> {
+        env.storage()
+            .persistent()
+            .get(&(AUTH_KEY_INDEX, payout_key))
+            .unwrap_or_else(|| Vec::new(&env))
+    }
+
+    fn require_admin(env: &Env) -> Result<Address, Error> {
+        let admin: Address = env
             .storage()
             .instance()
-            .get(&PROGRAM_DATA)
-            .unwrap_or_else(|| panic!("Program not initialized"));
+            .get(&DataKey::Admin)
+            .ok_or(Error::NotInitialized)?;
+        admin.require_auth();
+        Ok(admin)
+    }
 
-        program_data.authorized_payout_key.require_auth();
+    fn install_payout_key(env: &Env, mut program_data: ProgramData, new_key: Address) {
+        let program_id = program_data.program_id.clone();
+        let old_key = program_data.authorized_payout_key.clone();
 
-        if amount <= 0 {
-            panic!("Amount must be greater than zero");
+        if !program_data.payout_key_revoked {
+            Self::unindex_payout_key(env, &old_key, &program_id);
         }
+        Self::index_payout_key(env, &new_key, &program_id);
 
-        let mut schedules: Vec<ProgramReleaseSchedule> = env
-            .storage()
-            .instance()
-            .get(&SCHEDULES)
-            .unwrap_or_else(|| Vec::new(&env));
-        let schedule_id: u64 = env
-            .storage()
-            .instance()
-            .get(&NEXT_SCHEDULE_ID)
-            .unwrap_or(1_u64);
+        program_data.authorized_payout_key = new_key.clone();
+        program_data.payout_key_revoked = false;
+        Self::store_program(env, &program_data);
 
-        let schedule = ProgramReleaseSchedule {
-            schedule_id,
-            recipient,
-            amount,
-            release_timestamp,
-            released: false,
-        };
-        schedules.push_back(schedule.clone());
+        env.events().publish(
+            (KEY_ROTATED,),
+            PayoutKeyRotatedEvent {
+                version: EVENT_VERSION_V2,
+                program_id,
+                old_key,
+                new_key,
+            },
+        );
+    }
 
-        env.storage().instance().set(&SCHEDULES, &schedules);
-        env.storage()
-            .instance()
-            .set(&NEXT_SCHEDULE_ID, &(schedule_id + 1));
+    fn index_payout_key(env: &Env, payout_key: &Address, program_id: &String) {
+        let key = (AUTH_KEY_INDEX, payout_key.clone());
+        let mut programs: Vec<String> = env
+            .storage()
+            .persistent()
+            .get(&key)
+            .unwrap_or_else(|| Vec::new(env));
+        if !programs.contains(program_id) {
+            programs.push_back(program_id.clone());
+            env.storage().persistent().set(&key, &programs);
+        }
+    }
 
-        schedule
+    fn unindex_payout_key(env: &Env, payout_key: &Address, program_id: &String) {
+        let key = (AUTH_KEY_INDEX, payout_key.clone());
+        let mut programs: Vec<String> = env
+            .storage()
+            .persistent()
+            .get(&key)
+            .unwrap_or_else(|| Vec::new(env));
+        if let Some(i) = programs.first_index_of(program_id) {
+            programs.remove(i);
+        }
+        if programs.is_empty() {
+            env.storage().persistent().remove(&key);
+        } else {
+            env.storage().persistent().set(&key, &programs);
+        }
     }
 
-    /// Trigger all due schedules where `now >= release_timestamp`.
-    pub fn trigger_program_releases(env: Env) -> u32 {
-        // Reentrancy guard: Check and set
-        reentrancy_guard::check_not_entered(&env);
-        reentrancy_guard::set_entered(&env);
+    // ========================================================================
+    // Multisig Approvals
+    // ========================================================================
 
-        let mut program_data: ProgramData = env
-            .storage()
-            .instance()
-            .get(&PROGRAM_DATA)
-            .unwrap_or_else(|| {
-                reentrancy_guard::clear_entered(&env);
-                panic!("Program not initialized")
-            });
-        program_data.authorized_payout_key.require_auth();
+    /// Update the multisig configuration of a program (admin only)
+    ///
+    /// Once the total paid to a recipient would exceed `threshold_amount`,
+    /// each further payout to them needs `required_signatures` approvals from
+    /// `signers`. A `required_signatures` of zero disables the check.
+    pub fn update_multisig_config(
+        env: Env,
+        program_id: String,
+        threshold_amount: i128,
+        signers: Vec<Address>,
+        required_signatures: u32,
+    ) -> Result<(), Error> {
+        Self::require_admin(&env)?;
+        Self::load_program(&env, &program_id)?;
+
+        if required_signatures > signers.len() {
+            return Err(Error::InvalidMultisigConfig);
+        }
 
-        let mut schedules: Vec<ProgramReleaseSchedule> = env
-            .storage()
-            .instance()
-            .get(&SCHEDULES)
-            .unwrap_or_else(|| Vec::new(&env));
-        let mut release_history: Vec<ProgramReleaseHistory> = env
-            .storage()
-            .instance()
-            .get(&RELEASE_HISTORY)
-            .unwrap_or_else(|| Vec::new(&env));
+        env.storage().persistent().set(
+            &DataKey::MultisigConfig(program_id),
+            &MultisigConfig {
+                threshold_amount,
+                signers,
+                required_signatures,
+            },
+        );
 
-        let now = env.ledger().timestamp();
-        let contract_address = env.current_contract_address();
-        let token_client = token::Client::new(&env, &program_data.token_address);
-        let mut released_count: u32 = 0;
+        Ok(())
+    }
 
-        for i in 0..schedules.len() {
-            let mut schedule = schedules.get(i).unwrap();
-            if schedule.released || now < schedule.release_timestamp {
-                continue;
-            }
+    /// Get the multisig configuration of a program
+    pub fn get_multisig_config(env: Env, program_id: String) -> MultisigConfig {
+        env.storage()
+            .persistent()
+            .get(&DataKey::MultisigConfig(program_id))
+            .unwrap_or(MultisigConfig {
+                threshold_amount: i128::MAX,
+                signers: vec![&env],
+                required_signatures: 0,
+            })
+    }
 
-            if schedule.amount > program_data.remaining_balance {
-                reentrancy_guard::clear_entered(&env);
-                panic!("Insufficient balance");
-            }
+    /// Signer approves paying `amount` to `recipient` from a program
+    ///
+    /// Approvals are bound to both recipient and amount; approving a different
+    /// amount for the same recipient needs the existing approvals revoked first.
+    /// For schedule releases the amount is a cap, since what has vested keeps
+    /// growing between approval and release.
+    pub fn approve_payout(
+        env: Env,
+        program_id: String,
+        recipient: Address,
+        amount: i128,
+        approver: Address,
+    ) -> Result<(), Error> {
+        Self::load_program(&env, &program_id)?;
+        let config = Self::get_multisig_config(env.clone(), program_id.clone());
+        if !config.signers.contains(&approver) {
+            return Err(Error::NotMultisigSigner);
+        }
+        approver.require_auth();
 
-            token_client.transfer(&contract_address, &schedule.recipient, &schedule.amount);
-            schedule.released = true;
-            schedules.set(i, schedule.clone());
+        let approval_key = DataKey::PayoutApproval(program_id.clone(), recipient.clone());
+        let mut approval: PayoutApproval =
+            env.storage()
+                .persistent()
+                .get(&approval_key)
+                .unwrap_or(PayoutApproval {
+                    recipient: recipient.clone(),
+                    amount,
+                    approvals: vec![&env],
+                });
 
-            program_data.remaining_balance -= schedule.amount;
-            program_data.payout_history.push_back(PayoutRecord {
-                recipient: schedule.recipient.clone(),
-                amount: schedule.amount,
-                timestamp: now,
-            });
-            release_history.push_back(ProgramReleaseHistory {
-                schedule_id: schedule.schedule_id,
-                recipient: schedule.recipient,
-                amount: schedule.amount,
-                released_at: now,
-            });
-            released_count += 1;
+        if approval.amount != amount {
+            return Err(Error::ApprovalAmountMismatch);
+        }
+        if approval.approvals.contains(&approver) {
+            return Ok(());
         }
 
-        env.storage().instance().set(&PROGRAM_DATA, &program_data);
-        env.storage().instance().set(&SCHEDULES, &schedules);
-        env.storage()
-            .instance()
-            .set(&RELEASE_HISTORY, &release_history);
+        approval.approvals.push_back(approver.clone());
+        env.storage().persistent().set(&approval_key, &approval);
 
-        // Clear reentrancy guard before returning
-        reentrancy_guard::clear_entered(&env);
+        env.events().publish(
+            (PAYOUT_APPROVED,),
+            PayoutApprovalEvent {
+                version: EVENT_VERSION_V2,
+                program_id,
+                recipient,
+                amount,
+                approver,
+            },
+        );
+
+        Ok(())
+    }
+
+    /// Withdraw a signer's approval before the payout executes
+    pub fn revoke_payout_approval(
+        env: Env,
+        program_id: String,
+        recipient: Address,
+        approver: Address,
+    ) -> Result<(), Error> {
+        approver.require_auth();
+
+        let approval_key = DataKey::PayoutApproval(program_id.clone(), recipient.clone());
+        let mut approval: PayoutApproval = env
+            .storage()
+            .persistent()
+            .get(&approval_key)
+            .ok_or(Error::ApprovalNotFound)?;
+
+        let index = approval
+            .approvals
+            .first_index_of(&approver)
+            .ok_or(Error::ApprovalNotFound)?;
+        approval.approvals.remove(index);
+
+        if approval.approvals.is_empty() {
+            env.storage().persistent().remove(&approval_key);
+        } else {
+            env.storage().persistent().set(&approval_key, &approval);
+        }
+
+        env.events().publish(
+            (PAYOUT_APPROVAL_REVOKED,),
+            PayoutApprovalEvent {
+                version: EVENT_VERSION_V2,
+                program_id,
+                recipient,
+                amount: approval.amount,
+                approver,
+            },
+        );
 
-        released_count
+        Ok(())
     }
 
-    pub fn get_program_release_schedules(env: Env) -> Vec<ProgramReleaseSchedule> {
+    /// View: get the collected approvals for paying a recipient, if any
+    pub fn get_payout_approval(
+        env: Env,
+        program_id: String,
+        recipient: Address,
+    ) -> Option<PayoutApproval> {
         env.storage()
-            .instance()
-            .get(&SCHEDULES)
-            .unwrap_or_else(|| Vec::new(&env))
+            .persistent()
+            .get(&DataKey::PayoutApproval(program_id, recipient))
     }
 
-    pub fn get_program_release_history(env: Env) -> Vec<ProgramReleaseHistory> {
+    /// Total counted against the multisig threshold for `recipient`.
+    fn recipient_paid_total(env: &Env, program_id: &String, recipient: &Address) -> i128 {
         env.storage()
-            .instance()
-            .get(&RELEASE_HISTORY)
-            .unwrap_or_else(|| Vec::new(&env))
+            .persistent()
+            .get(&DataKey::RecipientPaidTotal(
+                program_id.clone(),
+                recipient.clone(),
+            ))
+            .unwrap_or(0)
     }
 
-    /// Query payout history by recipient with pagination
-    pub fn query_payouts_by_recipient(
-        env: Env,
-        recipient: Address,
-        offset: u32,
-        limit: u32,
-    ) -> Vec<PayoutRecord> {
-        let program_data: ProgramData = env
-            .storage()
-            .instance()
-            .get(&PROGRAM_DATA)
-            .unwrap_or_else(|| panic!("Program not initialized"));
-        let history = program_data.payout_history;
-        let mut results = Vec::new(&env);
-        let mut count = 0u32;
-        let mut skipped = 0u32;
+    /// Check that a payout has the multisig approval it needs.
+    ///
+    /// The threshold applies to the running total paid to the recipient,
+    /// `paid_before` plus this payout, so splitting a large payout into
+    /// smaller ones does not avoid it. While that total stays at or below the
+    /// threshold payouts pass through untouched. Beyond it each payout needs
+    /// enough approvals from current signers for exactly this recipient and
+    /// amount, or at least this amount when `up_to` is set.
+    fn payout_approved(
+        env: &Env,
+        program_id: &String,
+        recipient: &Address,
+        paid_before: i128,
+        amount: i128,
+        up_to: bool,
+    ) -> bool {
+        let config = Self::get_multisig_config(env.clone(), program_id.clone());
+        if paid_before.saturating_add(amount) <= config.threshold_amount
+            || config.required_signatures == 0
+        {
+            return true;
+        }
 
-        for i in 0..history.len() {
-            if count >= limit {
-                break;
-            }
-            let record = history.get(i).unwrap();
-            if record.recipient == recipient {
-                if skipped < offset {
-                    skipped += 1;
-                    continue;
-                }
-                results.push_back(record);
-                count += 1;
+        let approval: PayoutApproval = match env.storage().persistent().get(
+            &DataKey::PayoutApproval(program_id.clone(), recipient.clone()),
+        ) {
+            Some(approval) => approval,
+            None => return false,
+        };
+        let covered = if up_to {
+            amount <= approval.amount
+        } else {
+            amount == approval.amount
+        };
+        if !covered {
+            return false;
+        }
+
+        // Only count approvers who are still configured signers
+        let mut valid_approvals = 0u32;
+        for approver in approval.approvals.iter() {
+            if config.signers.contains(&approver) {
+                valid_approvals += 1;
             }
         }
-        results
+        valid_approvals >= config.required_signatures
     }
 
-    /// Query payout history by amount range
-    pub fn query_payouts_by_amount(
-        env: Env,
-        min_amount: i128,
-        max_amount: i128,
-        offset: u32,
-        limit: u32,
-    ) -> Vec<PayoutRecord> {
-        let program_data: ProgramData = env
-            .storage()
-            .instance()
-            .get(&PROGRAM_DATA)
-            .unwrap_or_else(|| panic!("Program not initialized"));
-        let history = program_data.payout_history;
-        let mut results = Vec::new(&env);
-        let mut count = 0u32;
-        let mut skipped = 0u32;
+    /// Count a payout that went through against the recipient's running
+    /// total, using up the approval it needed so it cannot be reused.
+    ///
+    /// Called only once the payout has been made, so a transfer stopped by
+    /// the circuit breaker leaves the signers' approval in place.
+    fn consume_payout_approval(env: &Env, program_id: &String, recipient: &Address, amount: i128) {
+        let config = Self::get_multisig_config(env.clone(), program_id.clone());
+        let paid_total =
+            Self::recipient_paid_total(env, program_id, recipient).saturating_add(amount);
+
+        if paid_total > config.threshold_amount && config.required_signatures > 0 {
+            env.storage().persistent().remove(&DataKey::PayoutApproval(
+                program_id.clone(),
+                recipient.clone(),
+            ));
+        }
+        env.storage().persistent().set(
+            &DataKey::RecipientPaidTotal(program_id.clone(), recipient.clone()),
+            &paid_total,
+        );
+    }
 
-        for i in 0..history.len() {
-            if count >= limit {
-                break;
-            }
-            let record = history.get(i).unwrap();
-            if record.amount >= min_amount && record.amount <= max_amount {
-                if skipped < offset {
-                    skipped += 1;
-                    continue;
-                }
-                results.push_back(record);
-                count += 1;
+    // ========================================================================
+    // Circuit Breaker Management
+    // ========================================================================
+
+    /// Register the circuit breaker admin. Can only be set once, or changed
+    /// by the existing admin.
+    ///
+    /// # Arguments
+    /// * `new_admin` - Address to register as circuit breaker admin
+    /// * `caller`    - Existing admin (None if setting for the first time)
+    pub fn set_circuit_admin(
+        env: Env,
+        new_admin: Address,
+        caller: Option<Address>,
+    ) -> Result<(), Error> {
+        if let Some(current) = error_recovery::get_circuit_admin(&env) {
+            if caller.as_ref() != Some(&current) {
+                return Err(Error::Unauthorized);
             }
         }
-        results
+        error_recovery::set_circuit_admin(&env, new_admin, caller);
+
+        Ok(())
     }
 
-    /// Query payout history by timestamp range
-    pub fn query_payouts_by_timestamp(
-        env: Env,
-        min_timestamp: u64,
-        max_timestamp: u64,
-        offset: u32,
-        limit: u32,
-    ) -> Vec<PayoutRecord> {
-        let program_data: ProgramData = env
-            .storage()
-            .instance()
-            .get(&PROGRAM_DATA)
-            .unwrap_or_else(|| panic!("Program not initialized"));
-        let history = program_data.payout_history;
-        let mut results = Vec::new(&env);
-        let mut count = 0u32;
-        let mut skipped = 0u32;
+    /// Returns the registered circuit breaker admin, if any.
+    pub fn get_circuit_admin(env: Env) -> Option<Address> {
+        error_recovery::get_circuit_admin(&env)
+    }
 
-        for i in 0..history.len() {
-            if count >= limit {
-                break;
-            }
-            let record = history.get(i).unwrap();
-            if record.timestamp >= min_timestamp && record.timestamp <= max_timestamp {
-                if skipped < offset {
-                    skipped += 1;
-                    continue;
-                }
-                results.push_back(record);
-                count += 1;
-            }
+    /// Returns the full circuit breaker status snapshot.
+    ///
+    /// # Returns
+    /// * `CircuitBreakerStatus` with state, failure/success counts, timestamps
+    pub fn get_circuit_status(env: Env) -> error_recovery::CircuitBreakerStatus {
+        error_recovery::get_status(&env)
+    }
+
+    /// Admin resets the circuit breaker.
+    ///
+    /// Transitions:
+    /// - Open     → HalfOpen  (probe mode)
+    /// - HalfOpen → Closed    (hard reset)
+    /// - Closed   → Closed    (no-op reset)
+    ///
+    /// # Errors
+    /// * `Unauthorized` if caller is not the registered circuit breaker admin
+    pub fn reset_circuit_breaker(env: Env, admin: Address) -> Result<(), Error> {
+        if error_recovery::get_circuit_admin(&env).as_ref() != Some(&admin) {
+            return Err(Error::Unauthorized);
         }
-        results
+        error_recovery::reset_circuit_breaker(&env, &admin);
+
+        Ok(())
     }
 
-    /// Query release schedules by recipient
-    pub fn query_schedules_by_recipient(
+    /// Updates the circuit breaker configuration. Admin only.
+    ///
+    /// # Arguments
+    /// * `failure_threshold` - Consecutive failures needed to open circuit
+    /// * `success_threshold` - Consecutive successes in HalfOpen to close it
+    /// * `max_error_log`     - Maximum error log entries to retain
+    pub fn configure_circuit_breaker(
         env: Env,
-        recipient: Address,
-        offset: u32,
-        limit: u32,
-    ) -> Vec<ProgramReleaseSchedule> {
-        let schedules: Vec<ProgramReleaseSchedule> = env
-            .storage()
-            .instance()
-            .get(&SCHEDULES)
-            .unwrap_or_else(|| Vec::new(&env));
-        let mut results = Vec::new(&env);
-        let mut count = 0u32;
-        let mut skipped = 0u32;
-
-        for i in 0..schedules.len() {
-            if count >= limit {
-                break;
-            }
-            let schedule = schedules.get(i).unwrap();
-            if schedule.recipient == recipient {
-                if skipped < offset {
-                    skipped += 1;
-                    continue;
-                }
-                results.push_back(schedule);
-                count += 1;
+        admin: Address,
+        failure_threshold: u32,
+        success_threshold: u32,
+        max_error_log: u32,
+    ) -> Result<(), Error> {
+        let stored = error_recovery::get_circuit_admin(&env);
+        match stored {
+            Some(ref a) if a == &admin => {
+                admin.require_auth();
             }
+            _ => return Err(Error::Unauthorized),
         }
-        results
+        error_recovery::set_config(
+            &env,
+            error_recovery::CircuitBreakerConfig {
+                failure_threshold,
+                success_threshold,
+                max_error_log,
+            },
+        );
+
+        Ok(())
     }
 
-    /// Query release schedules by released status
-    pub fn query_schedules_by_status(
-        env: Env,
-        released: bool,
-        offset: u32,
-        limit: u32,
-    ) -> Vec<ProgramReleaseSchedule> {
-        let schedules: Vec<ProgramReleaseSchedule> = env
-            .storage()
-            .instance()
-            .get(&SCHEDULES)
-            .unwrap_or_else(|| Vec::new(&env));
-        let mut results = Vec::new(&env);
-        let mut count = 0u32;
-        let mut skipped = 0u32;
+    /// Returns the error log (last N failures recorded by the circuit breaker).
+    pub fn get_circuit_error_log(env: Env) -> soroban_sdk::Vec<error_recovery::ErrorEntry> {
+        error_recovery::get_error_log(&env)
+    }
 
-        for i in 0..schedules.len() {
-            if count >= limit {
-                break;
-            }
-            let schedule = schedules.get(i).unwrap();
-            if schedule.released == released {
-                if skipped < offset {
-                    skipped += 1;
-                    continue;
-                }
-                results.push_back(schedule);
-                count += 1;
+    /// Directly open the circuit (emergency lockout). Admin only.
+    pub fn emergency_open_circuit(env: Env, admin: Address) -> Result<(), Error> {
+        let stored = error_recovery::get_circuit_admin(&env);
+        match stored {
+            Some(ref a) if a == &admin => {
+                admin.require_auth();
             }
+            _ => return Err(Error::Unauthorized),
         }
-        results
+        error_recovery::open_circuit(&env);
+
+        Ok(())
     }
 
-    /// Query release history with filtering and pagination
-    pub fn query_releases_by_recipient(
-        env: Env,
-        recipient: Address,
-        offset: u32,
-        limit: u32,
-    ) -> Vec<ProgramReleaseHistory> {
-        let history: Vec<ProgramReleaseHistory> = env
-            .storage()
-            .instance()
-            .get(&RELEASE_HISTORY)
-            .unwrap_or_else(|| Vec::new(&env));
-        let mut results = Vec::new(&env);
-        let mut count = 0u32;
-        let mut skipped = 0u32;
+    /// Reject the call with `CircuitOpen` while the breaker is open.
+    fn require_circuit_closed(env: &Env) -> Result<(), Error> {
+        if error_recovery::check_and_allow(env).is_err() {
+            return Err(Error::CircuitOpen);
+        }
 
-        for i in 0..history.len() {
-            if count >= limit {
-                break;
-            }
-            let record = history.get(i).unwrap();
-            if record.recipient == recipient {
-                if skipped < offset {
-                    skipped += 1;
-                    continue;
+        Ok(())
+    }
+
+    /// Transfer from the contract through the circuit breaker.
+    ///
+    /// A failed transfer is recorded against `program_id` and `operation`
+    /// and reported as `false` rather than aborting. Callers must not turn
+    /// that into an error return, which would roll the failure count back;
+    /// they skip the payout and leave it to be retried instead. Once the
+    /// breaker has opened, further transfers are not attempted.
+    fn guarded_transfer(
+        env: &Env,
+        token_address: &Address,
+        program_id: &String,
+        operation: Symbol,
+        recipient: &Address,
+        amount: i128,
+    ) -> bool {
+        let sent = error_recovery::check_and_allow(env).is_ok() && {
+            let token_client = token::Client::new(env, token_address);
+            match token_client.try_transfer(&env.current_contract_address(), recipient, &amount) {
+                Ok(Ok(())) => {
+                    error_recovery::record_success(env);
+                    true
+                }
+                _ => {
+                    error_recovery::record_failure(
+                        env,
+                        program_id.clone(),
+                        operation.clone(),
+                        error_recovery::ERR_TRANSFER_FAILED,
+                    );
+                    false
                 }
-                results.push_back(record);
-                count += 1;
             }
+        };
+
+        if !sent {
+            env.events().publish(
+                (PAYOUT_FAILED,),
+                PayoutFailedEvent {
+                    version: EVENT_VERSION_V2,
+                    program_id: program_id.clone(),
+                    operation,
+                    recipient: recipient.clone(),
+                    amount,
+                },
+            );
         }
-        results
+        sent
     }
 
-    /// Get aggregate statistics for the program
-    pub fn get_program_aggregate_stats(env: Env) -> ProgramAggregateStats {
-        let program_data: ProgramData = env
-            .storage()
-            .instance()
-            .get(&PROGRAM_DATA)
-            .unwrap_or_else(|| panic!("Program not initialized"));
-        let schedules: Vec<ProgramReleaseSchedule> = env
-            .storage()
-            .instance()
-            .get(&SCHEDULES)
-            .unwrap_or_else(|| Vec::new(&env));
+    // ========================================================================
+    // Payout Functions
+    // ========================================================================
 
-        let mut scheduled_count = 0u32;
-        let mut released_count = 0u32;
+    /// Validate a batch against the program, including any multisig
+    /// approvals it needs. Returns the batch total.
+    fn validate_batch(
+        env: &Env,
+        program_data: &ProgramData,
+        recipients: &Vec<Address>,
+        amounts: &Vec<i128>,
+    ) -> Result<i128, Error> {
+        // Validate input lengths match
+        if recipients.len() != amounts.len() {
+            return Err(Error::BatchSizeMismatch);
+        }
 
-        for i in 0..schedules.len() {
-            let schedule = schedules.get(i).unwrap();
-            if schedule.released {
-                released_count += 1;
-            } else {
-                scheduled_count += 1;
+        if recipients.is_empty() {
+            return Err(Error::EmptyBatch);
+        }
+
+        // Calculate total payout amount
+        let mut total_payout: i128 = 0;
+        for amount in amounts.iter() {
+            if amount <= 0 {
+                return Err(Error::InvalidAmount);
             }
+            total_payout = total_payout
+                .checked_add(amount)
+                .ok_or(Error::AmountOverflow)?;
         }
 
-        ProgramAggregateStats {
-            total_funds: program_data.total_funds,
-            remaining_balance: program_data.remaining_balance,
-            total_paid_out: program_data.total_funds - program_data.remaining_balance,
-            payout_count: program_data.payout_history.len(),
-            scheduled_count,
-            released_count,
+        // Validate sufficient balance; funds committed to schedules are off limits
+        if total_payout > Self::available_balance(program_data) {
+            return Err(Error::InsufficientBalance);
         }
+
+        // Entries taking a recipient past the threshold need multisig
+        // approval; repeated entries for one recipient add up, and one
+        // approval covers a single entry
+        let mut batch_paid: Map<Address, i128> = Map::new(env);
+        let mut approvals_used: Vec<Address> = Vec::new(env);
+        let config = Self::get_multisig_config(env.clone(), program_data.program_id.clone());
+        for i in 0..recipients.len() {
+            let recipient = recipients.get(i).unwrap();
+            let amount = amounts.get(i).unwrap();
+            let paid_before = match batch_paid.get(recipient.clone()) {
+                Some(paid) => paid,
+                None => Self::recipient_paid_total(env, &program_data.program_id, &recipient),
+            };
+            let paid_total = paid_before.saturating_add(amount);
+
+            if paid_total > config.threshold_amount && config.required_signatures > 0 {
+                if approvals_used.contains(&recipient)
+                    || !Self::payout_approved(
+                        env,
+                        &program_data.program_id,
+                        &recipient,
+                        paid_before,
+                        amount,
+                        false,
+                    )
+                {
+                    return Err(Error::InsufficientApprovals);
+                }
+                approvals_used.push_back(recipient.clone());
+            }
+            batch_paid.set(recipient, paid_total);
+        }
+
+        Ok(total_payout)
     }
 
-    /// Get payouts by recipient
-    pub fn get_payouts_by_recipient(
+    /// Execute batch payouts to multiple recipients
+    ///
+    /// A transfer that fails is recorded by the circuit breaker and skipped:
+    /// it is neither charged to the program nor recorded as a payout, and
+    /// keeps any multisig approval it had, so it can be sent again later.
+    ///
+    /// # Arguments
+    /// * `program_id` - Program to pay out from
+    /// * `recipients` - Vector of recipient addresses
+    /// * `amounts` - Vector of amounts (must match recipients length)
+    ///
+    /// # Returns
+    /// Updated ProgramData after payouts
+    pub fn batch_payout(
         env: Env,
-        recipient: Address,
-        offset: u32,
-        limit: u32,
-    ) -> Vec<PayoutRecord> {
+        program_id: String,
+        recipients: Vec<Address>,
+        amounts: Vec<i128>,
+    ) -> Result<ProgramData, Error> {
+        // Reentrancy guard: Check and set
+        reentrancy_guard::check_not_entered(&env);
+        reentrancy_guard::set_entered(&env);
+
+        if Self::check_paused(&env, symbol_short!("release")) {
+            return Err(Error::FundsPaused);
+        }
+        Self::require_circuit_closed(&env)?;
+
+        // Verify authorization
         let program_data: ProgramData = env
             .storage()
-            .instance()
-            .get(&PROGRAM_DATA)
-            .unwrap_or_else(|| panic!("Program not initialized"));
-        let history = program_data.payout_history;
-        let mut results = Vec::new(&env);
-        let mut count = 0u32;
-        let mut skipped = 0u32;
+            .persistent()
+            .get(&DataKey::Program(program_id.clone()))
+            .ok_or(Error::ProgramNotFound)?;
 
-        for i in 0..history.len() {
-            if count >= limit {
-                break;
-            }
-            let record = history.get(i).unwrap();
-            if record.recipient == recipient {
-                if skipped < offset {
-                    skipped += 1;
-                    continue;
-                }
-                results.push_back(record);
-                count += 1;
-            }
+        if program_data.status != ProgramStatus::Active {
+            return Err(Error::ProgramNotActive);
         }
-        results
-    }
 
-    /// Get pending schedules (not yet released)
-    pub fn get_pending_schedules(env: Env) -> Vec<ProgramReleaseSchedule> {
-        let schedules: Vec<ProgramReleaseSchedule> = env
-            .storage()
-            .instance()
-            .get(&SCHEDULES)
-            .unwrap_or_else(|| Vec::new(&env));
-        let mut results = Vec::new(&env);
+        if program_data.payout_key_revoked {
+            return Err(Error::PayoutKeyRevoked);
+        }
 
-        for i in 0..schedules.len() {
-            let schedule = schedules.get(i).unwrap();
-            if !schedule.released {
-                results.push_back(schedule);
+        program_data.authorized_payout_key.require_auth();
+
+        Self::validate_batch(&env, &program_data, &recipients, &amounts)?;
+
+        // Execute transfers
+        let timestamp = env.ledger().timestamp();
+        let mut paid_count: u32 = 0;
+        let mut paid_total: i128 = 0;
+
+        for i in 0..recipients.len() {
+            let recipient = recipients.get(i).unwrap();
+            let amount = amounts.get(i).unwrap();
+
+            // Transfer funds from contract to recipient
+            if !Self::guarded_transfer(
+                &env,
+                &program_data.token_address,
+                &program_id,
+                symbol_short!("batch_pay"),
+                &recipient,
+                amount,
+            ) {
+                continue;
             }
+
+            // Record payout
+            Self::consume_payout_approval(&env, &program_id, &recipient, amount);
+            Self::record_payout(&env, &program_id, recipient, amount, timestamp);
+            paid_count += 1;
+            paid_total += amount;
         }
-        results
+
+        // Update program data
+        let mut updated_data = program_data.clone();
+        updated_data.remaining_balance -= paid_total;
+
+        // Store updated data
+        Self::store_program(&env, &updated_data);
+
+        // Emit BatchPayout event
+        env.events().publish(
+            (BATCH_PAYOUT,),
+            BatchPayoutEvent {
+                version: EVENT_VERSION_V2,
+                program_id: updated_data.program_id.clone(),
+                recipient_count: paid_count,
+                total_amount: paid_total,
+                remaining_balance: updated_data.remaining_balance,
+            },
+        );
+
+        // Clear reentrancy guard before returning
+        reentrancy_guard::clear_entered(&env);
+
+        Ok(updated_data)
     }
 
-    /// Get due schedules (ready to be released)
-    pub fn get_due_schedules(env: Env) -> Vec<ProgramReleaseSchedule> {
-        let schedules: Vec<ProgramReleaseSchedule> = env
-            .storage()
-            .instance()
-            .get(&SCHEDULES)
-            .unwrap_or_else(|| Vec::new(&env));
-        let now = env.ledger().timestamp();
-        let mut results = Vec::new(&env);
+    /// Execute batch payouts, attempting each transfer independently
+    ///
+    /// Where `batch_payout` skips a failing recipient (missing trustline,
+    /// frozen account, open circuit), this turns its entry into a pending
+    /// claim the recipient can pull later with `claim`. Like any claim, the
+    /// amount stays in `remaining_balance`, held in `pending_claims_total`
+    /// so it cannot be paid out twice, and is only deducted once claimed.
+    ///
+    /// # Returns
+    /// One result per recipient, in input order
+    pub fn batch_payout_partial(
+        env: Env,
+        program_id: String,
+        recipients: Vec<Address>,
+        amounts: Vec<i128>,
+    ) -> Result<Vec<BatchPayoutResult>, Error> {
+        // Reentrancy guard: Check and set
+        reentrancy_guard::check_not_entered(&env);
+        reentrancy_guard::set_entered(&env);
 
-        for i in 0..schedules.len() {
-            let schedule = schedules.get(i).unwrap();
-            if !schedule.released && schedule.release_timestamp <= now {
-                results.push_back(schedule);
-            }
+        if Self::check_paused(&env, symbol_short!("release")) {
+            return Err(Error::FundsPaused);
         }
-        results
-    }
+        Self::require_circuit_closed(&env)?;
 
-    /// Get total amount in pending schedules
-    pub fn get_total_scheduled_amount(env: Env) -> i128 {
-        let schedules: Vec<ProgramReleaseSchedule> = env
+        let mut program_data: ProgramData = env
             .storage()
-            .instance()
-            .get(&SCHEDULES)
-            .unwrap_or_else(|| Vec::new(&env));
-        let mut total = 0i128;
+            .persistent()
+            .get(&DataKey::Program(program_id.clone()))
+            .ok_or(Error::ProgramNotFound)?;
 
-        for i in 0..schedules.len() {
-            let schedule = schedules.get(i).unwrap();
-            if !schedule.released {
-                total += schedule.amount;
-            }
+        if program_data.status != ProgramStatus::Active {
+            return Err(Error::ProgramNotActive);
         }
-        total
-    }
-}
 
-#[cfg(test)]
-mod test {
-    use super::*;
-    use soroban_sdk::{
-        testutils::{Address as _, Ledger},
-        token, Address, Env, String, Vec,
-    };
+        if program_data.payout_key_revoked {
+            return Err(Error::PayoutKeyRevoked);
+        }
 
-    // Test helper to create a mock token contract
-    fn create_token_contract<'a>(env: &Env, admin: &Address) -> token::Client<'a> {
-        let token_contract = env.register_stellar_asset_contract_v2(admin.clone());
-        let token_address = token_contract.address();
-        token::Client::new(env, &token_address)
-    }
+        program_data.authorized_payout_key.require_auth();
 
-    // ========================================================================
-    // Program Registration Tests
-    // ========================================================================
+        Self::validate_batch(&env, &program_data, &recipients, &amounts)?;
 
-    fn setup_program_with_schedule(
-        env: &Env,
-        client: &ProgramEscrowContractClient<'static>,
-        contract_id: &Address,
-        authorized_key: &Address,
-        _token: &Address,
-        program_id: &String,
-        total_amount: i128,
-        winner: &Address,
-        release_timestamp: u64,
-    ) {
-        // // Register program
-        // client.register_program(program_id, token, authorized_key);
+        let timestamp = env.ledger().timestamp();
+        let token_client = token::Client::new(&env, &program_data.token_address);
+        let contract_address = env.current_contract_address();
+        let retry_config = error_recovery::RetryConfig { max_attempts: 1 };
+        let mut results = Vec::new(&env);
+        let mut paid_count: u32 = 0;
+        let mut paid_total: i128 = 0;
+
+        for i in 0..recipients.len() {
+            let recipient = recipients.get(i).unwrap();
+            let amount = amounts.get(i).unwrap();
+
+            let outcome = error_recovery::execute_with_retry(
+                &env,
+                &retry_config,
+                program_id.clone(),
+                symbol_short!("batch_pay"),
+                || match token_client.try_transfer(&contract_address, &recipient, &amount) {
+                    Ok(Ok(())) => Ok(()),
+                    _ => Err(error_recovery::ERR_TRANSFER_FAILED),
+                },
+            );
+
+            // A failed entry becomes a claim, which counts as paid here
+            Self::consume_payout_approval(&env, &program_id, &recipient, amount);
+            let claim_id = if outcome.succeeded {
+                program_data.remaining_balance -= amount;
+                Self::record_payout(&env, &program_id, recipient.clone(), amount, timestamp);
+                paid_count += 1;
+                paid_total += amount;
+                None
+            } else {
+                Some(Self::create_claim(
+                    &env,
+                    &mut program_data,
+                    recipient.clone(),
+                    amount,
+                ))
+            };
+
+            results.push_back(BatchPayoutResult {
+                recipient,
+                amount,
+                paid: outcome.succeeded,
+                claim_id,
+                error_code: outcome.final_error,
+            });
+        }
+
+        Self::store_program(&env, &program_data);
+
+        env.events().publish(
+            (BATCH_PAYOUT,),
+            BatchPayoutEvent {
+                version: EVENT_VERSION_V2,
+                program_id,
+                recipient_count: paid_count,
+                total_amount: paid_total,
+                remaining_balance: program_data.remaining_balance,
+            },
+        );
+
+        // Clear reentrancy guard before returning
+        reentrancy_guard::clear_entered(&env);
 
-        // // Create and fund token
-        // let token_client = create_token_contract(env, authorized_key);
-        // let token_admin = token::StellarAssetClient::new(env, &token_client.address);
-        // token_admin.mint(authorized_key, &total_amount);
+        Ok(results)
+    }
 
-        // // Lock funds for program
-        // token_client.approve(authorized_key, &env.current_contract_address(), &total_amount, &1000);
-        // client.lock_funds(program_id, &total_amount);
+    /// Execute a single payout to one recipient
+    ///
+    /// If the transfer fails, the failure is recorded by the circuit breaker
+    /// and the program is returned unchanged, with any multisig approval the
+    /// payout needed still in place.
+    ///
+    /// # Arguments
+    /// * `program_id` - Program to pay out from
+    /// * `recipient` - Address of the recipient
+    /// * `amount` - Amount to transfer
+    ///
+    /// # Returns
+    /// Updated ProgramData after payout
+    pub fn single_payout(
+        env: Env,
+        program_id: String,
+        recipient: Address,
+        amount: i128,
+    ) -> Result<ProgramData, Error> {
+        // Reentrancy guard: Check and set
+        reentrancy_guard::check_not_entered(&env);
+        reentrancy_guard::set_entered(&env);
 
-        // Create and fund token first, then register the program with the real token address
-        let token_client = create_token_contract(env, authorized_key);
-        let token_admin = token::StellarAssetClient::new(env, &token_client.address);
-        token_admin.mint(authorized_key, &total_amount);
+        if Self::check_paused(&env, symbol_short!("release")) {
+            return Err(Error::FundsPaused);
+        }
+        Self::require_circuit_closed(&env)?;
 
-        // Register program using the created token contract address
-        client.initialize_program(&program_id, &authorized_key, &token_client.address);
+        // Verify authorization
+        let program_data: ProgramData = env
+            .storage()
+            .persistent()
+            .get(&DataKey::Program(program_id.clone()))
+            .ok_or(Error::ProgramNotFound)?;
 
-        // Transfer tokens to contract first
-        token_client.transfer(&authorized_key, contract_id, &total_amount);
+        if program_data.status != ProgramStatus::Active {
+            return Err(Error::ProgramNotActive);
+        }
 
-        // Lock funds for program (records the amount in program state)
-        client.lock_program_funds(program_id, &total_amount);
+        if program_data.payout_key_revoked {
+            return Err(Error::PayoutKeyRevoked);
+        }
 
-        // Create release schedule
-        client.create_program_release_schedule(
-            &program_id,
-            &total_amount,
-            &release_timestamp,
-            winner,
-        );
-    }
+        program_data.authorized_payout_key.require_auth();
 
-    #[test]
-    fn test_single_program_release_schedule() {
-        let env = Env::default();
-        let contract_id = env.register_contract(None, ProgramEscrowContract);
-        let client = ProgramEscrowContractClient::new(&env, &contract_id);
+        // Validate amount
+        if amount <= 0 {
+            return Err(Error::InvalidAmount);
+        }
 
-        let authorized_key = Address::generate(&env);
-        let winner = Address::generate(&env);
-        let token = Address::generate(&env);
-        let program_id = String::from_str(&env, "Hackathon2024");
-        let amount = 1000_0000000;
-        let release_timestamp = 1000;
+        // Validate sufficient balance; funds committed to schedules are off limits
+        if amount > Self::available_balance(&program_data) {
+            return Err(Error::InsufficientBalance);
+        }
 
-        env.mock_all_auths();
+        let paid_before = Self::recipient_paid_total(&env, &program_id, &recipient);
+        if !Self::payout_approved(&env, &program_id, &recipient, paid_before, amount, false) {
+            return Err(Error::InsufficientApprovals);
+        }
 
-        // Setup program with schedule
-        setup_program_with_schedule(
+        // Transfer funds from contract to recipient
+        if !Self::guarded_transfer(
             &env,
-            &client,
-            &contract_id,
-            &authorized_key,
-            &token,
+            &program_data.token_address,
             &program_id,
+            symbol_short!("sngl_pay"),
+            &recipient,
             amount,
-            &winner,
-            release_timestamp,
-        );
-
-        // Verify schedule was created
-        let schedule = client.get_program_release_schedule(&program_id, &1);
-        assert_eq!(schedule.schedule_id, 1);
-        assert_eq!(schedule.amount, amount);
-        assert_eq!(schedule.release_timestamp, release_timestamp);
-        assert_eq!(schedule.recipient, winner);
-        assert!(!schedule.released);
-
-        // Check pending schedules
-        let pending = client.get_pending_program_schedules(&program_id);
-        assert_eq!(pending.len(), 1);
-
-        // Event verification can be added later - focusing on core functionality
-    }
+        ) {
+            reentrancy_guard::clear_entered(&env);
+            return Ok(program_data);
+        }
 
-    #[test]
-    fn test_multiple_program_release_schedules() {
-        let env = Env::default();
-        let contract_id = env.register_contract(None, ProgramEscrowContract);
-        let client = ProgramEscrowContractClient::new(&env, &contract_id);
+        // Record payout
+        let timestamp = env.ledger().timestamp();
+        Self::consume_payout_approval(&env, &program_id, &recipient, amount);
+        Self::record_payout(&env, &program_id, recipient.clone(), amount, timestamp);
 
-        let authorized_key = Address::generate(&env);
-        let winner1 = Address::generate(&env);
-        let winner2 = Address::generate(&env);
-        let token = Address::generate(&env);
-        let program_id = String::from_str(&env, "Hackathon2024");
-        let amount1 = 600_0000000;
-        let amount2 = 400_0000000;
-        let total_amount = amount1 + amount2;
+        // Update program data
+        let mut updated_data = program_data.clone();
+        updated_data.remaining_balance -= amount;
 
-        env.mock_all_auths();
+        // Store updated data
+        Self::store_program(&env, &updated_data);
 
-        // Register program
-        client.initialize_program(&program_id, &authorized_key, &token);
+        // Emit Payout event
+        env.events().publish(
+            (PAYOUT,),
+            PayoutEvent {
+                version: EVENT_VERSION_V2,
+                program_id: updated_data.program_id.clone(),
+                recipient,
+                amount,
+                remaining_balance: updated_data.remaining_balance,
+            },
+        );
 
-        // Create and fund token
-        let token_client = create_token_contract(&env, &authorized_key);
-        let token_admin = token::StellarAssetClient::new(&env, &token_client.address);
-        token_admin.mint(&authorized_key, &total_amount);
+        // Clear reentrancy guard before returning
+        reentrancy_guard::clear_entered(&env);
 
-        // Transfer tokens to contract first
-        token_client.transfer(&authorized_key, &contract_id, &total_amount);
+        Ok(updated_data)
+    }
 
-        // Lock funds for program
-        client.lock_program_funds(&program_id, &total_amount);
+    // ========================================================================
+    // Claims
+    // ========================================================================
 
-        // Create first release schedule
-        client.create_program_release_schedule(&program_id, &amount1, &1000, &winner1);
+    /// Set the claim window duration (admin only).
+    /// claim_window: seconds a recipient has to claim after a payout is authorized.
+    pub fn set_claim_window(env: Env, claim_window: u64) -> Result<(), Error> {
+        Self::require_admin(&env)?;
+        env.storage()
+            .instance()
+            .set(&DataKey::ClaimWindow, &claim_window);
 
-        // Create second release schedule
-        client.create_program_release_schedule(&program_id, &amount2, &2000, &winner2);
+        Ok(())
+    }
 
-        // Verify both schedules exist
-        let all_schedules = client.get_all_prog_release_schedules(&program_id);
-        assert_eq!(all_schedules.len(), 2);
+    /// Get the claim window duration in seconds
+    pub fn get_claim_window(env: Env) -> u64 {
+        env.storage()
+            .instance()
+            .get(&DataKey::ClaimWindow)
+            .unwrap_or(0)
+    }
 
-        // Verify schedule IDs
-        let schedule1 = client.get_program_release_schedule(&program_id, &1);
-        let schedule2 = client.get_program_release_schedule(&program_id, &2);
-        assert_eq!(schedule1.schedule_id, 1);
-        assert_eq!(schedule2.schedule_id, 2);
+    /// Authorize a payout as a pending claim instead of an immediate transfer
+    ///
+    /// The amount is set aside from the program balance and the recipient
+    /// pulls it with `claim` within the claim window. This works for accounts
+    /// that cannot receive a push transfer yet, e.g. without a trustline.
+    ///
+    /// # Returns
+    /// The id of the new claim
+    pub fn authorize_claim(
+        env: Env,
+        program_id: String,
+        recipient: Address,
+        amount: i128,
+    ) -> Result<u64, Error> {
+        if Self::check_paused(&env, symbol_short!("release")) {
+            return Err(Error::FundsPaused);
+        }
 
-        // Verify amounts
-        assert_eq!(schedule1.amount, amount1);
-        assert_eq!(schedule2.amount, amount2);
+        let mut program_data = Self::load_program(&env, &program_id)?;
+        if program_data.status != ProgramStatus::Active {
+            return Err(Error::ProgramNotActive);
+        }
+        if program_data.payout_key_revoked {
+            return Err(Error::PayoutKeyRevoked);
+        }
+        program_data.authorized_payout_key.require_auth();
 
-        // Verify recipients
-        assert_eq!(schedule1.recipient, winner1);
-        assert_eq!(schedule2.recipient, winner2);
+        if amount <= 0 {
+            return Err(Error::InvalidAmount);
+        }
+        if amount > Self::available_balance(&program_data) {
+            return Err(Error::InsufficientBalance);
+        }
+        let paid_before = Self::recipient_paid_total(&env, &program_id, &recipient);
+        if !Self::payout_approved(&env, &program_id, &recipient, paid_before, amount, false) {
+            return Err(Error::InsufficientApprovals);
+        }
+        Self::consume_payout_approval(&env, &program_id, &recipient, amount);
 
-        // Check pending schedules
-        let pending = client.get_pending_program_schedules(&program_id);
-        assert_eq!(pending.len(), 2);
+        let claim_id = Self::create_claim(&env, &mut program_data, recipient, amount);
+        Self::store_program(&env, &program_data);
 
-        // Event verification can be added later - focusing on core functionality
+        Ok(claim_id)
     }
 
-    #[test]
-    fn test_program_automatic_release_at_timestamp() {
-        let env = Env::default();
-        let contract_id = env.register_contract(None, ProgramEscrowContract);
-        let client = ProgramEscrowContractClient::new(&env, &contract_id);
-
-        let authorized_key = Address::generate(&env);
-        let winner = Address::generate(&env);
-        let token = Address::generate(&env);
-        let program_id = String::from_str(&env, "Hackathon2024");
-        let amount = 1000_0000000;
-        let release_timestamp = 1000;
-
-        env.mock_all_auths();
-
-        // Setup program with schedule
-        setup_program_with_schedule(
-            &env,
-            &client,
-            &contract_id,
-            &authorized_key,
-            &token,
-            &program_id,
+    /// Hold `amount` of the program balance for a pending claim. It stays in
+    /// `remaining_balance` until claimed. The caller persists `program_data`.
+    fn create_claim(
+        env: &Env,
+        program_data: &mut ProgramData,
+        recipient: Address,
+        amount: i128,
+    ) -> u64 {
+        let program_id = program_data.program_id.clone();
+        let claim_id: u64 = env
+            .storage()
+            .persistent()
+            .get(&DataKey::NextClaimId(program_id.clone()))
+            .unwrap_or(1_u64);
+        let expires_at = env
+            .ledger()
+            .timestamp()
+            .saturating_add(Self::get_claim_window(env.clone()));
+        let claim = ClaimRecord {
+            claim_id,
+            recipient: recipient.clone(),
             amount,
-            &winner,
-            release_timestamp,
-        );
-
-        // Try to release before timestamp (should fail)
-        env.ledger().set_timestamp(999);
-        let result = client.try_release_prog_schedule_automatic(&program_id, &1);
-        assert!(result.is_err());
-
-        // Advance time to after release timestamp
-        env.ledger().set_timestamp(1001);
-
-        // Release automatically
-        client.release_prog_schedule_automatic(&program_id, &1);
-
-        // Verify schedule was released
-        let schedule = client.get_program_release_schedule(&program_id, &1);
-        assert!(schedule.released);
-        assert_eq!(schedule.released_at, Some(1001));
-
-        assert_eq!(schedule.released_by, Some(contract_id.clone()));
+            expires_at,
+            claimed: false,
+        };
 
-        // Check no pending schedules
-        let pending = client.get_pending_program_schedules(&program_id);
-        assert_eq!(pending.len(), 0);
+        program_data.pending_claims_total += amount;
+        env.storage()
+            .persistent()
+            .set(&DataKey::PendingClaim(program_id.clone(), claim_id), &claim);
+        env.storage()
+            .persistent()
+            .set(&DataKey::NextClaimId(program_id.clone()), &(claim_id + 1));
 
-        // Verify release history
-        let history = client.get_program_release_history(&program_id);
-        assert_eq!(history.len(), 1);
-        assert_eq!(history.get(0).unwrap().release_type, ReleaseType::Automatic);
+        env.events().publish(
+            (CLAIM_CREATED,),
+            ClaimCreatedEvent {
+                version: EVENT_VERSION_V2,
+                program_id,
+                claim_id,
+                recipient,
+                amount,
+                expires_at,
+            },
+        );
 
-        // Event verification can be added later - focusing on core functionality
+        claim_id
     }
 
-    #[test]
-    fn test_program_manual_trigger_before_after_timestamp() {
-        let env = Env::default();
-        let contract_id = env.register_contract(None, ProgramEscrowContract);
-        let client = ProgramEscrowContractClient::new(&env, &contract_id);
+    /// Recipient pulls an authorized payout within the claim window
+    ///
+    /// If the transfer fails the claim stays pending and can be retried.
+    pub fn claim(env: Env, program_id: String, claim_id: u64) -> Result<(), Error> {
+        if Self::check_paused(&env, symbol_short!("release")) {
+            return Err(Error::FundsPaused);
+        }
+        Self::require_circuit_closed(&env)?;
+
+        let claim_key = DataKey::PendingClaim(program_id.clone(), claim_id);
+        let mut claim: ClaimRecord = env
+            .storage()
+            .persistent()
+            .get(&claim_key)
+            .ok_or(Error::ClaimNotFound)?;
 
-        let authorized_key = Address::generate(&env);
-        let winner = Address::generate(&env);
-        let token = Address::generate(&env);
-        let program_id = String::from_str(&env, "Hackathon2024");
-        let amount = 1000_0000000;
-        let release_timestamp = 1000;
+        claim.recipient.require_auth();
 
-        env.mock_all_auths();
+        if claim.claimed {
+            return Err(Error::ClaimAlreadyExecuted);
+        }
+        let now = env.ledger().timestamp();
+        if now > claim.expires_at {
+            return Err(Error::ClaimExpired);
+        }
 
-        // Setup program with schedule
-        setup_program_with_schedule(
+        let mut program_data = Self::load_program(&env, &program_id)?;
+        if !Self::guarded_transfer(
             &env,
-            &client,
-            &contract_id,
-            &authorized_key,
-            &token,
+            &program_data.token_address,
             &program_id,
-            amount,
-            &winner,
-            release_timestamp,
-        );
+            symbol_short!("claim"),
+            &claim.recipient,
+            claim.amount,
+        ) {
+            return Ok(());
+        }
 
-        // Manually release before timestamp (authorized key can do this)
-        env.ledger().set_timestamp(999);
-        client.release_program_schedule_manual(&program_id, &1);
-
-        // Verify schedule was released
-        let schedule = client.get_program_release_schedule(&program_id, &1);
-        assert!(schedule.released);
-        assert_eq!(schedule.released_at, Some(999));
-        assert_eq!(schedule.released_by, Some(authorized_key.clone()));
-
-        // Verify release history
-        let history = client.get_program_release_history(&program_id);
-        assert_eq!(history.len(), 1);
-        assert_eq!(history.get(0).unwrap().release_type, ReleaseType::Manual);
-
-        // Event verification can be added later - focusing on core functionality
-    }
-
-    #[test]
-    fn test_verify_program_schedule_tracking_and_history() {
-        let env = Env::default();
-        let contract_id = env.register_contract(None, ProgramEscrowContract);
-        let client = ProgramEscrowContractClient::new(&env, &contract_id);
-
-        let authorized_key = Address::generate(&env);
-        let winner1 = Address::generate(&env);
-        let winner2 = Address::generate(&env);
-        let program_id = String::from_str(&env, "Hackathon2024");
-        let amount1 = 600_0000000;
-        let amount2 = 400_0000000;
-        let total_amount = amount1 + amount2;
-
-        env.mock_all_auths();
-
-        // Create and fund token FIRST
-        let token_client = create_token_contract(&env, &authorized_key);
-        let token_admin = token::StellarAssetClient::new(&env, &token_client.address);
-        token_admin.mint(&authorized_key, &total_amount);
-
-        // Register program with REAL token address
-        client.initialize_program(&program_id, &authorized_key, &token_client.address);
-
-        // Transfer tokens to contract first
-        token_client.transfer(&authorized_key, &contract_id, &total_amount);
-
-        // Lock funds for program
-        client.lock_program_funds(&program_id, &total_amount);
-
-        // Create first schedule
-        client.create_program_release_schedule(&program_id, &amount1, &1000, &winner1);
-
-        // Create second schedule
-        client.create_program_release_schedule(&program_id, &amount2, &2000, &winner2);
-
-        // Release first schedule manually
-        client.release_program_schedule_manual(&program_id, &1);
-
-        // Advance time and release second schedule automatically
-        env.ledger().set_timestamp(2001);
-        client.release_prog_schedule_automatic(&program_id, &2);
-
-        // Verify complete history
-        let history = client.get_program_release_history(&program_id);
-        assert_eq!(history.len(), 2);
-
-        // Check first release (manual)
-        let first_release = history.get(0).unwrap();
-        assert_eq!(first_release.schedule_id, 1);
-        assert_eq!(first_release.amount, amount1);
-        assert_eq!(first_release.recipient, winner1);
-        assert_eq!(first_release.release_type, ReleaseType::Manual);
-
-        // Check second release (automatic)
-        let second_release = history.get(1).unwrap();
-        assert_eq!(second_release.schedule_id, 2);
-        assert_eq!(second_release.amount, amount2);
-        assert_eq!(second_release.recipient, winner2);
-        assert_eq!(second_release.release_type, ReleaseType::Automatic);
-
-        // Verify no pending schedules
-        let pending = client.get_pending_program_schedules(&program_id);
-        assert_eq!(pending.len(), 0);
-
-        // Verify all schedules are marked as released
-        let all_schedules = client.get_all_prog_release_schedules(&program_id);
-        assert_eq!(all_schedules.len(), 2);
-        assert!(all_schedules.get(0).unwrap().released);
-        assert!(all_schedules.get(1).unwrap().released);
-    }
-
-    #[test]
-    fn test_program_overlapping_schedules() {
-        let env = Env::default();
-        let contract_id = env.register_contract(None, ProgramEscrowContract);
-        let client = ProgramEscrowContractClient::new(&env, &contract_id);
-
-        let authorized_key = Address::generate(&env);
-        let winner1 = Address::generate(&env);
-        let winner2 = Address::generate(&env);
-        let winner3 = Address::generate(&env);
-        let program_id = String::from_str(&env, "Hackathon2024");
-        let amount1 = 300_0000000;
-        let amount2 = 300_0000000;
-        let amount3 = 400_0000000;
-        let total_amount = amount1 + amount2 + amount3;
-        let base_timestamp = 1000;
-
-        env.mock_all_auths();
-
-        // Create and fund token FIRST
-        let token_client = create_token_contract(&env, &authorized_key);
-        let token_admin = token::StellarAssetClient::new(&env, &token_client.address);
-        token_admin.mint(&authorized_key, &total_amount);
-
-        // Register program with REAL token address
-        client.initialize_program(&program_id, &authorized_key, &token_client.address);
-
-        // Transfer tokens to contract first
-        token_client.transfer(&authorized_key, &contract_id, &total_amount);
-
-        // Lock funds for program
-        client.lock_program_funds(&program_id, &total_amount);
-
-        // Create overlapping schedules (all at same timestamp)
-        client.create_program_release_schedule(
+        program_data.pending_claims_total -= claim.amount;
+        program_data.remaining_balance -= claim.amount;
+        Self::record_payout(
+            &env,
             &program_id,
-            &amount1,
-            &base_timestamp,
-            &winner1.clone(),
+            claim.recipient.clone(),
+            claim.amount,
+            now,
         );
+        if program_data.status == ProgramStatus::Closed {
+            Self::settle_leftovers(&env, &mut program_data);
+        }
+        Self::store_program(&env, &program_data);
 
-        client.create_program_release_schedule(
-            &program_id,
-            &amount2,
-            &base_timestamp,
-            &winner2.clone(),
-        );
+        claim.claimed = true;
+        env.storage().persistent().set(&claim_key, &claim);
 
-        client.create_program_release_schedule(
-            &program_id,
-            &amount3,
-            &base_timestamp,
-            &winner3.clone(),
+        env.events().publish(
+            (CLAIM_EXECUTED,),
+            ClaimExecutedEvent {
+                version: EVENT_VERSION_V2,
+                program_id,
+                claim_id,
+                recipient: claim.recipient,
+                amount: claim.amount,
+            },
         );
 
-        // Advance time to after release timestamp
-        env.ledger().set_timestamp(base_timestamp + 1);
-
-        // Check due schedules (should be all 3)
-        let due = client.get_due_program_schedules(&program_id);
-        assert_eq!(due.len(), 3);
+        Ok(())
+    }
 
-        // Release schedules one by one
-        client.release_prog_schedule_automatic(&program_id, &1);
-        client.release_prog_schedule_automatic(&program_id, &2);
-        client.release_prog_schedule_automatic(&program_id, &3);
+    /// Payout key cancels an unclaimed claim, returning it to the program balance
+    pub fn cancel_pending_claim(env: Env, program_id: String, claim_id: u64) -> Result<(), Error> {
+        let program_data = Self::load_program(&env, &program_id)?;
+        if program_data.payout_key_revoked {
+            return Err(Error::PayoutKeyRevoked);
+        }
+        program_data.authorized_payout_key.require_auth();
 
-        // Verify all schedules are released
-        let pending = client.get_pending_program_schedules(&program_id);
-        assert_eq!(pending.len(), 0);
+        let claim = Self::take_unclaimed(&env, &program_id, claim_id)?;
+        Self::return_claim(&env, program_data, claim, CLAIM_CANCELLED);
 
-        // Verify complete history
-        let history = client.get_program_release_history(&program_id);
-        assert_eq!(history.len(), 3);
+        Ok(())
+    }
 
-        // Verify all were automatic releases
-        for release in history.iter() {
-            assert_eq!(release.release_type, ReleaseType::Automatic);
+    /// Return an expired claim to the program balance. Callable by anyone.
+    pub fn expire_claim(env: Env, program_id: String, claim_id: u64) -> Result<(), Error> {
+        let program_data = Self::load_program(&env, &program_id)?;
+        let claim = Self::take_unclaimed(&env, &program_id, claim_id)?;
+        if env.ledger().timestamp() <= claim.expires_at {
+            return Err(Error::ClaimNotExpired);
         }
+        Self::return_claim(&env, program_data, claim, CLAIM_EXPIRED);
 
-        // Event verification can be added later - focusing on core functionality
+        Ok(())
     }
 
-    #[test]
-    fn test_register_single_program() {
-        let env = Env::default();
-        let contract_id = env.register_contract(None, ProgramEscrowContract);
-        let client = ProgramEscrowContractClient::new(&env, &contract_id);
-
-        let backend = Address::generate(&env);
-        let token = Address::generate(&env);
-        let prog_id = String::from_str(&env, "Hackathon2024");
-
-        // Register program
-        let program = client.initialize_program(&prog_id, &backend, &token);
-
-        // Verify program data
-        assert_eq!(program.program_id, prog_id);
-        assert_eq!(program.authorized_payout_key, backend);
-        assert_eq!(program.token_address, token);
-        assert_eq!(program.total_funds, 0);
-        assert_eq!(program.remaining_balance, 0);
-        assert_eq!(program.payout_history.len(), 0);
-
-        // Verify it exists
-        assert!(client.program_exists(&prog_id));
-        assert_eq!(client.get_program_count(), 1);
+    /// View: get a claim of a program
+    pub fn get_pending_claim(
+        env: Env,
+        program_id: String,
+        claim_id: u64,
+    ) -> Result<ClaimRecord, Error> {
+        env.storage()
+            .persistent()
+            .get(&DataKey::PendingClaim(program_id, claim_id))
+            .ok_or(Error::ClaimNotFound)
     }
 
-    #[test]
-    fn test_multiple_programs_isolation() {
-        let env = Env::default();
-        let contract_id = env.register_contract(None, ProgramEscrowContract);
-        let client = ProgramEscrowContractClient::new(&env, &contract_id);
-
-        let backend1 = Address::generate(&env);
-        let backend2 = Address::generate(&env);
-        let backend3 = Address::generate(&env);
-        let token = Address::generate(&env);
+    fn take_unclaimed(env: &Env, program_id: &String, claim_id: u64) -> Result<ClaimRecord, Error> {
+        let claim_key = DataKey::PendingClaim(program_id.clone(), claim_id);
+        let claim: ClaimRecord = env
+            .storage()
+            .persistent()
+            .get(&claim_key)
+            .ok_or(Error::ClaimNotFound)?;
+        if claim.claimed {
+            return Err(Error::ClaimAlreadyExecuted);
+        }
+        env.storage().persistent().remove(&claim_key);
+        Ok(claim)
+    }
 
-        // Register three programs
-        let prog1 = String::from_str(&env, "ETHGlobal2024");
-        let prog2 = String::from_str(&env, "Stellar2024");
-        let prog3 = String::from_str(&env, "BuildathonQ1");
+    fn return_claim(env: &Env, mut program_data: ProgramData, claim: ClaimRecord, topic: Symbol) {
+        program_data.pending_claims_total -= claim.amount;
+        // A closed program sends the returned amount straight back to funders
+        if program_data.status == ProgramStatus::Closed {
+            Self::settle_leftovers(env, &mut program_data);
+        }
+        Self::store_program(env, &program_data);
 
-        client.initialize_program(&prog1, &backend1, &token);
-        client.initialize_program(&prog2, &backend2, &token);
-        client.initialize_program(&prog3, &backend3, &token);
+        env.events().publish(
+            (topic,),
+            ClaimCancelledEvent {
+                version: EVENT_VERSION_V2,
+                program_id: program_data.program_id,
+                claim_id: claim.claim_id,
+                recipient: claim.recipient,
+                amount: claim.amount,
+            },
+        );
+    }
 
-        // Verify all exist
-        assert!(client.program_exists(&prog1));
-        assert!(client.program_exists(&prog2));
-        assert!(client.program_exists(&prog3));
-        assert_eq!(client.get_program_count(), 3);
+    /// Get program information
+    ///
+    /// # Returns
+    /// ProgramData containing all program information
+    pub fn get_program_info(env: Env, program_id: String) -> Result<ProgramData, Error> {
+        Self::load_program(&env, &program_id)
+    }
 
-        // Verify complete isolation
-        let info1 = client.get_program_info(&prog1);
-        let info2 = client.get_program_info(&prog2);
-        let info3 = client.get_program_info(&prog3);
+    /// Get remaining balance
+    ///
+    /// # Returns
+    /// Current remaining balance, including funds committed to schedules
+    pub fn get_remaining_balance(env: Env, program_id: String) -> Result<i128, Error> {
+        let program_data = Self::load_program(&env, &program_id)?;
 
-        assert_eq!(info1.program_id, prog1);
-        assert_eq!(info2.program_id, prog2);
-        assert_eq!(info3.program_id, prog3);
+        Ok(program_data.remaining_balance)
+    }
 
-        assert_eq!(info1.authorized_payout_key, backend1);
-        assert_eq!(info2.authorized_payout_key, backend2);
-        assert_eq!(info3.authorized_payout_key, backend3);
+    /// Get available balance
+    ///
+    /// # Returns
+    /// Remaining balance not committed to release schedules or held for
+    /// pending claims
+    pub fn get_available_balance(env: Env, program_id: String) -> Result<i128, Error> {
+        let program_data = Self::load_program(&env, &program_id)?;
 
-        // Verify list programs
-        let programs = client.list_programs();
-        assert_eq!(programs.len(), 3);
+        Ok(Self::available_balance(&program_data))
     }
 
-    #[test]
-    #[should_panic(expected = "Program already exists")]
-    fn test_duplicate_program_registration() {
-        let env = Env::default();
-        let contract_id = env.register_contract(None, ProgramEscrowContract);
-        let client = ProgramEscrowContractClient::new(&env, &contract_id);
-
-        let backend = Address::generate(&env);
-        let token = Address::generate(&env);
-        let prog_id = String::from_str(&env, "Hackathon2024");
+    fn available_balance(program_data: &ProgramData) -> i128 {
+        program_data.remaining_balance
+            - program_data.committed_balance
+            - program_data.pending_claims_total
+    }
 
-        // Register once - should succeed
-        client.initialize_program(&prog_id, &backend, &token);
+    /// Create a release schedule entry that can be triggered at/after `release_timestamp`.
+    pub fn create_program_release_schedule(
+        env: Env,
+        program_id: String,
+        recipient: Address,
+        amount: i128,
+        release_timestamp: u64,
+    ) -> Result<ProgramReleaseSchedule, Error> {
+        Self::add_schedule(
+            &env,
+            &program_id,
+            recipient,
+            amount,
+            release_timestamp,
+            ReleaseKind::OneShot,
+        )
+    }
 
-        // Register again - should panic
-        client.initialize_program(&prog_id, &backend, &token);
+    /// Create a vesting schedule: nothing is available before
+    /// `start_timestamp + cliff_seconds`, after which the amount vests
+    /// linearly from `start_timestamp` to `start_timestamp + duration_seconds`.
+    pub fn create_program_vesting_schedule(
+        env: Env,
+        program_id: String,
+        recipient: Address,
+        amount: i128,
+        start_timestamp: u64,
+        cliff_seconds: u64,
+        duration_seconds: u64,
+    ) -> Result<ProgramReleaseSchedule, Error> {
+        if duration_seconds == 0 || cliff_seconds > duration_seconds {
+            return Err(Error::InvalidSchedulePeriod);
+        }
+        Self::add_schedule(
+            &env,
+            &program_id,
+            recipient,
+            amount,
+            start_timestamp,
+            ReleaseKind::CliffLinear(cliff_seconds, duration_seconds),
+        )
     }
 
-    #[test]
-    #[should_panic(expected = "Program ID cannot be empty")]
-    fn test_empty_program_id() {
-        let env = Env::default();
-        let contract_id = env.register_contract(None, ProgramEscrowContract);
-        let client = ProgramEscrowContractClient::new(&env, &contract_id);
+    /// Create a schedule paying `amount` in `tranche_count` equal tranches,
+    /// the first at `first_release_timestamp` and one every `period_seconds`.
+    pub fn create_program_tranche_schedule(
+        env: Env,
+        program_id: String,
+        recipient: Address,
+        amount: i128,
+        first_release_timestamp: u64,
+        period_seconds: u64,
+        tranche_count: u32,
+    ) -> Result<ProgramReleaseSchedule, Error> {
+        if period_seconds == 0 || tranche_count == 0 {
+            return Err(Error::InvalidSchedulePeriod);
+        }
+        Self::add_schedule(
+            &env,
+            &program_id,
+            recipient,
+            amount,
+            first_release_timestamp,
+            ReleaseKind::Periodic(period_seconds, tranche_count),
+        )
+    }
 
-        let backend = Address::generate(&env);
-        let token = Address::generate(&env);
-        let empty_id = String::from_str(&env, "");
+    fn add_schedule(
+        env: &Env,
+        program_id: &String,
+        recipient: Address,
+        amount: i128,
+        release_timestamp: u64,
+        kind: ReleaseKind,
+    ) -> Result<ProgramReleaseSchedule, Error> {
+        let mut program_data = Self::load_program(env, program_id)?;
 
-        client.initialize_program(&empty_id, &backend, &token);
-    }
+        if program_data.status != ProgramStatus::Active {
+            return Err(Error::ProgramNotActive);
+        }
 
-    #[test]
-    #[should_panic(expected = "Program not found")]
-    fn test_get_nonexistent_program() {
-        let env = Env::default();
-        let contract_id = env.register_contract(None, ProgramEscrowContract);
-        let client = ProgramEscrowContractClient::new(&env, &contract_id);
+        if program_data.payout_key_revoked {
+            return Err(Error::PayoutKeyRevoked);
+        }
 
-        let prog_id = String::from_str(&env, "DoesNotExist");
-        client.get_program_info(&prog_id);
-    }
+        program_data.authorized_payout_key.require_auth();
 
-    // ========================================================================
-    // Fund Locking Tests
-    // ========================================================================
+        if amount <= 0 {
+            return Err(Error::InvalidAmount);
+        }
 
-    #[test]
-    fn test_lock_funds_single_program() {
-        let env = Env::default();
-        env.mock_all_auths();
+        // Reserve the full amount now so releases can never run dry
+        if amount > Self::available_balance(&program_data) {
+            return Err(Error::InsufficientAvailableBalance);
+        }
+        program_data.committed_balance += amount;
+        Self::store_program(env, &program_data);
 
-        let admin = Address::generate(&env);
-        let contract_id = env.register_contract(None, ProgramEscrowContract);
-        let client = ProgramEscrowContractClient::new(&env, &contract_id);
-        let token_client = create_token_contract(&env, &admin);
+        let schedule_id: u64 = env
+            .storage()
+            .persistent()
+            .get(&DataKey::NextScheduleId(program_id.clone()))
+            .unwrap_or(1_u64);
 
-        let backend = Address::generate(&env);
-        let prog_id = String::from_str(&env, "Hackathon2024");
+        let schedule = ProgramReleaseSchedule {
+            schedule_id,
+            recipient,
+            amount,
+            release_timestamp,
+            kind,
+            released_amount: 0,
+            released: false,
+            cancelled: false,
+        };
 
-        // Register program
-        client.initialize_program(&prog_id, &backend, &token_client.address);
+        Self::store_schedule(env, program_id, &schedule);
+        Self::index_schedule(env, program_id, &schedule);
+        env.storage().persistent().set(
+            &DataKey::NextScheduleId(program_id.clone()),
+            &(schedule_id + 1),
+        );
 
-        // Lock funds
-        let amount = 10_000_0000000i128; // 10,000 USDC
-        let updated = client.lock_program_funds(&prog_id, &amount);
+        Ok(schedule)
+    }
 
-        assert_eq!(updated.total_funds, amount);
-        assert_eq!(updated.remaining_balance, amount);
-    }
-
-    #[test]
-    fn test_lock_funds_multiple_programs_isolation() {
-        let env = Env::default();
-        env.mock_all_auths();
+    /// Total amount of a schedule that has vested by `now`, including
+    /// whatever was already released.
+    fn vested_amount(schedule: &ProgramReleaseSchedule, now: u64) -> i128 {
+        if now < schedule.release_timestamp {
+            return 0;
+        }
+        let elapsed = now - schedule.release_timestamp;
+
+        match schedule.kind {
+            ReleaseKind::OneShot => schedule.amount,
+            ReleaseKind::CliffLinear(cliff_seconds, duration_seconds) => {
+                if elapsed < cliff_seconds {
+                    0
+                } else if elapsed >= duration_seconds {
+                    schedule.amount
+                } else {
+                    schedule.amount * elapsed as i128 / duration_seconds as i128
+                }
+            }
+            ReleaseKind::Periodic(period_seconds, tranche_count) => {
+                let unlocked = (elapsed / period_seconds + 1).min(tranche_count as u64);
+                schedule.amount * unlocked as i128 / tranche_count as i128
+            }
+        }
+    }
 
-        let admin = Address::generate(&env);
-        let contract_id = env.register_contract(None, ProgramEscrowContract);
-        let client = ProgramEscrowContractClient::new(&env, &contract_id);
-        let token_client = create_token_contract(&env, &admin);
+    /// Amount of a schedule that has vested but not been released yet
+    fn releasable_amount(schedule: &ProgramReleaseSchedule, now: u64) -> i128 {
+        if schedule.released || schedule.cancelled {
+            return 0;
+        }
+        Self::vested_amount(schedule, now) - schedule.released_amount
+    }
 
-        let backend1 = Address::generate(&env);
-        let backend2 = Address::generate(&env);
-
-        let prog1 = String::from_str(&env, "Program1");
-        let prog2 = String::from_str(&env, "Program2");
-
-        // Register programs
-        client.initialize_program(&prog1, &backend1, &token_client.address);
-        client.initialize_program(&prog2, &backend2, &token_client.address);
+    /// Release the vested-so-far part of a schedule to its recipient.
+    ///
+    /// Updates the schedule in place and appends to the payout and release
+    /// history; the caller persists the program and schedule.
+    /// Returns `false`, changing nothing, if the transfer failed.
+    fn release_schedule_amount(
+        env: &Env,
+        program_data: &mut ProgramData,
+        schedule: &mut ProgramReleaseSchedule,
+        amount: i128,
+        now: u64,
+    ) -> bool {
+        if !Self::guarded_transfer(
+            env,
+            &program_data.token_address,
+            &program_data.program_id,
+            symbol_short!("release"),
+            &schedule.recipient,
+            amount,
+        ) {
+            return false;
+        }
 
-        // Lock different amounts in each program
-        let amount1 = 5_000_0000000i128;
-        let amount2 = 10_000_0000000i128;
+        Self::consume_payout_approval(env, &program_data.program_id, &schedule.recipient, amount);
+        schedule.released_amount += amount;
+        schedule.released = schedule.released_amount >= schedule.amount;
 
-        client.lock_program_funds(&prog1, &amount1);
-        client.lock_program_funds(&prog2, &amount2);
-
-        // Verify isolation - funds don't mix
-        let info1 = client.get_program_info(&prog1);
-        let info2 = client.get_program_info(&prog2);
-
-        assert_eq!(info1.total_funds, amount1);
-        assert_eq!(info1.remaining_balance, amount1);
-        assert_eq!(info2.total_funds, amount2);
-        assert_eq!(info2.remaining_balance, amount2);
-    }
-
-    #[test]
-    fn test_multi_tenant_payout_history_isolation() {
-        let env = Env::default();
-        env.mock_all_auths();
-
-        let contract_id = env.register_contract(None, ProgramEscrowContract);
-        let client = ProgramEscrowContractClient::new(&env, &contract_id);
-        let token_admin = Address::generate(&env);
-        let token_client = create_token_contract(&env, &token_admin);
-
-        let backend1 = Address::generate(&env);
-        let backend2 = Address::generate(&env);
-
-        let prog1 = String::from_str(&env, "Program1");
-        let prog2 = String::from_str(&env, "Program2");
-
-        // Mint tokens to backends
-        let token_admin_client = token::StellarAssetClient::new(&env, &token_client.address);
-        let amount1 = 10_000_0000000i128;
-        let amount2 = 20_000_0000000i128;
-        token_admin_client.mint(&backend1, &amount1);
-        token_admin_client.mint(&backend2, &amount2);
-
-        // Register programs
-        client.initialize_program(&prog1, &backend1, &token_client.address);
-        client.initialize_program(&prog2, &backend2, &token_client.address);
-
-        // Transfer tokens to contract and lock funds
-        token_client.transfer(&backend1, &contract_id, &amount1);
-        token_client.transfer(&backend2, &contract_id, &amount2);
-        client.lock_program_funds(&prog1, &amount1);
-        client.lock_program_funds(&prog2, &amount2);
-
-        // Perform payouts on each program
-        let recipient1 = Address::generate(&env);
-        let recipient2 = Address::generate(&env);
-        let recipient3 = Address::generate(&env);
-        let recipient4 = Address::generate(&env);
-
-        // Payouts for program 1
-        client.single_payout(&prog1, &recipient1, &2_000_0000000);
-        client.single_payout(&prog1, &recipient2, &3_000_0000000);
-
-        // Payouts for program 2
-        client.single_payout(&prog2, &recipient3, &5_000_0000000);
-        client.single_payout(&prog2, &recipient4, &7_000_0000000);
-
-        // Verify payout history isolation
-        let info1 = client.get_program_info(&prog1);
-        let info2 = client.get_program_info(&prog2);
-
-        // Program 1 should have 2 payouts
-        assert_eq!(info1.payout_history.len(), 2);
-        assert_eq!(info1.payout_history.get(0).unwrap().recipient, recipient1);
-        assert_eq!(info1.payout_history.get(0).unwrap().amount, 2_000_0000000);
-        assert_eq!(info1.payout_history.get(1).unwrap().recipient, recipient2);
-        assert_eq!(info1.payout_history.get(1).unwrap().amount, 3_000_0000000);
-        assert_eq!(info1.remaining_balance, 5_000_0000000); // 10 - 2 - 3
-
-        // Program 2 should have 2 different payouts
-        assert_eq!(info2.payout_history.len(), 2);
-        assert_eq!(info2.payout_history.get(0).unwrap().recipient, recipient3);
-        assert_eq!(info2.payout_history.get(0).unwrap().amount, 5_000_0000000);
-        assert_eq!(info2.payout_history.get(1).unwrap().recipient, recipient4);
-        assert_eq!(info2.payout_history.get(1).unwrap().amount, 7_000_0000000);
-        assert_eq!(info2.remaining_balance, 8_000_0000000); // 20 - 5 - 7
-    }
-
-    #[test]
-    fn test_multi_tenant_release_schedule_isolation() {
-        let env = Env::default();
-        env.mock_all_auths();
-
-        let contract_id = env.register_contract(None, ProgramEscrowContract);
-        let client = ProgramEscrowContractClient::new(&env, &contract_id);
-        let token_client = create_token_contract(&env, &Address::generate(&env));
-
-        let backend1 = Address::generate(&env);
-        let backend2 = Address::generate(&env);
-
-        let prog1 = String::from_str(&env, "Program1");
-        let prog2 = String::from_str(&env, "Program2");
-
-        // Register programs
-        client.initialize_program(&prog1, &backend1, &token_client.address);
-        client.initialize_program(&prog2, &backend2, &token_client.address);
-
-        // Lock funds
-        let amount1 = 10_000_0000000i128;
-        let amount2 = 20_000_0000000i128;
-        client.lock_program_funds(&prog1, &amount1);
-        client.lock_program_funds(&prog2, &amount2);
-
-        // Create release schedules for each program
-        let winner1 = Address::generate(&env);
-        let winner2 = Address::generate(&env);
-        let winner3 = Address::generate(&env);
-        let winner4 = Address::generate(&env);
-
-        let future_timestamp = env.ledger().timestamp() + 1000;
-
-        // Schedules for program 1
-        client.create_program_release_schedule(&prog1, &2_000_0000000, &future_timestamp, &winner1);
-        client.create_program_release_schedule(
-            &prog1,
-            &3_000_0000000,
-            &(future_timestamp + 100),
-            &winner2,
+        program_data.committed_balance -= amount;
+        program_data.remaining_balance -= amount;
+        Self::record_payout(
+            env,
+            &program_data.program_id,
+            schedule.recipient.clone(),
+            amount,
+            now,
         );
-
-        // Schedules for program 2
-        client.create_program_release_schedule(&prog2, &5_000_0000000, &future_timestamp, &winner3);
-        client.create_program_release_schedule(
-            &prog2,
-            &7_000_0000000,
-            &(future_timestamp + 100),
-            &winner4,
+        Self::record_release(
+            env,
+            &program_data.program_id,
+            schedule,
+            amount,
+            ReleaseAction::Released,
         );
+        true
+    }
+
+    /// Recipient withdraws whatever has vested on a schedule so far.
+    ///
+    /// # Returns
+    /// The amount transferred, zero if the transfer failed
+    pub fn withdraw_vested(env: Env, program_id: String, schedule_id: u64) -> Result<i128, Error> {
+        // Reentrancy guard: Check and set
+        reentrancy_guard::check_not_entered(&env);
+        reentrancy_guard::set_entered(&env);
+
+        if Self::check_paused(&env, symbol_short!("release")) {
+            return Err(Error::FundsPaused);
+        }
+        Self::require_circuit_closed(&env)?;
 
-        // Verify schedule isolation
-        let schedules1 = client.get_all_prog_release_schedules(&prog1);
-        let schedules2 = client.get_all_prog_release_schedules(&prog2);
-
-        assert_eq!(schedules1.len(), 2);
-        assert_eq!(schedules1.get(0).unwrap().amount, 2_000_0000000);
-        assert_eq!(schedules1.get(0).unwrap().recipient, winner1);
-        assert_eq!(schedules1.get(1).unwrap().amount, 3_000_0000000);
-        assert_eq!(schedules1.get(1).unwrap().recipient, winner2);
+        let mut program_data: ProgramData = env
+            .storage()
+            .persistent()
+            .get(&DataKey::Program(program_id.clone()))
+            .ok_or(Error::ProgramNotFound)?;
 
-        assert_eq!(schedules2.len(), 2);
-        assert_eq!(schedules2.get(0).unwrap().amount, 5_000_0000000);
-        assert_eq!(schedules2.get(0).unwrap().recipient, winner3);
-        assert_eq!(schedules2.get(1).unwrap().amount, 7_000_0000000);
-        assert_eq!(schedules2.get(1).unwrap().recipient, winner4);
-    }
-
-    #[test]
-    fn test_multi_tenant_release_history_isolation() {
-        let env = Env::default();
-        env.mock_all_auths();
+        if program_data.status != ProgramStatus::Active {
+            return Err(Error::ProgramNotActive);
+        }
 
-        let contract_id = env.register_contract(None, ProgramEscrowContract);
-        let client = ProgramEscrowContractClient::new(&env, &contract_id);
-        let token_admin = Address::generate(&env);
-        let token_client = create_token_contract(&env, &token_admin);
-
-        let backend1 = Address::generate(&env);
-        let backend2 = Address::generate(&env);
+        let mut schedule: ProgramReleaseSchedule = env
+            .storage()
+            .persistent()
+            .get(&DataKey::ReleaseSchedule(program_id.clone(), schedule_id))
+            .ok_or(Error::ScheduleNotFound)?;
 
-        let prog1 = String::from_str(&env, "Program1");
-        let prog2 = String::from_str(&env, "Program2");
-
-        // Mint tokens to backends
-        let token_admin_client = token::StellarAssetClient::new(&env, &token_client.address);
-        let amount1 = 10_000_0000000i128;
-        let amount2 = 20_000_0000000i128;
-        token_admin_client.mint(&backend1, &amount1);
-        token_admin_client.mint(&backend2, &amount2);
-
-        // Register programs
-        client.initialize_program(&prog1, &backend1, &token_client.address);
-        client.initialize_program(&prog2, &backend2, &token_client.address);
+        schedule.recipient.require_auth();
 
-        // Transfer tokens to contract and lock funds
-        token_client.transfer(&backend1, &contract_id, &amount1);
-        token_client.transfer(&backend2, &contract_id, &amount2);
-        client.lock_program_funds(&prog1, &amount1);
-        client.lock_program_funds(&prog2, &amount2);
+        let now = env.ledger().timestamp();
+        let amount = Self::releasable_amount(&schedule, now);
+        if amount <= 0 {
+            return Err(Error::NothingVested);
+        }
 
-        // Create and release schedules
-        let winner1 = Address::generate(&env);
-        let winner2 = Address::generate(&env);
+        if amount > program_data.remaining_balance {
+            return Err(Error::InsufficientBalance);
+        }
 
-        let future_timestamp = env.ledger().timestamp() + 100;
+        let paid_before = Self::recipient_paid_total(&env, &program_id, &schedule.recipient);
+        if !Self::payout_approved(
+            &env,
+            &program_id,
+            &schedule.recipient,
+            paid_before,
+            amount,
+            true,
+        ) {
+            return Err(Error::InsufficientApprovals);
+        }
 
-        // Create schedules for both programs
-        client.create_program_release_schedule(&prog1, &4_000_0000000, &future_timestamp, &winner1);
-        client.create_program_release_schedule(&prog2, &8_000_0000000, &future_timestamp, &winner2);
-
-        // Advance time to allow release
-        env.ledger().set_timestamp(future_timestamp + 1);
-
-        // Release schedules
-        client.release_program_schedule_manual(&prog1, &1); // Release first schedule of program 1
-        client.release_program_schedule_manual(&prog2, &1); // Release first schedule of program 2
-
-        // Verify release history isolation
-        let history1 = client.get_program_release_history(&prog1);
-        let history2 = client.get_program_release_history(&prog2);
+        if !Self::release_schedule_amount(&env, &mut program_data, &mut schedule, amount, now) {
+            reentrancy_guard::clear_entered(&env);
+            return Ok(0);
+        }
+        if schedule.released {
+            Self::unindex_schedule(&env, &program_id, &schedule);
+        }
+        Self::store_schedule(&env, &program_id, &schedule);
+        Self::store_program(&env, &program_data);
 
-        assert_eq!(history1.len(), 1);
-        assert_eq!(history1.get(0).unwrap().amount, 4_000_0000000);
-        assert_eq!(history1.get(0).unwrap().recipient, winner1);
+        // Clear reentrancy guard before returning
+        reentrancy_guard::clear_entered(&env);
 
-        assert_eq!(history2.len(), 1);
-        assert_eq!(history2.get(0).unwrap().amount, 8_000_0000000);
-        assert_eq!(history2.get(0).unwrap().recipient, winner2);
+        Ok(amount)
     }
 
-    #[test]
-    fn test_multi_tenant_analytics_isolation_concept() {
-        // Note: Current analytics are global, but this test demonstrates
-        // the concept of what program-specific analytics isolation would look like
-        let env = Env::default();
-        env.mock_all_auths();
-
-        let contract_id = env.register_contract(None, ProgramEscrowContract);
-        let client = ProgramEscrowContractClient::new(&env, &contract_id);
-        let token_admin = Address::generate(&env);
-        let token_client = create_token_contract(&env, &token_admin);
-
-        let backend1 = Address::generate(&env);
-        let backend2 = Address::generate(&env);
+    /// Cancel the unreleased remainder of a schedule (payout key only)
+    ///
+    /// Whatever was already released stays with the recipient; the rest is
+    /// no longer owed and stops counting towards scheduled totals.
+    pub fn cancel_program_release_schedule(
+        env: Env,
+        program_id: String,
+        schedule_id: u64,
+    ) -> Result<ProgramReleaseSchedule, Error> {
+        let mut program_data = Self::load_program(&env, &program_id)?;
+        if program_data.status != ProgramStatus::Active {
+            return Err(Error::ProgramNotActive);
+        }
+        if program_data.payout_key_revoked {
+            return Err(Error::PayoutKeyRevoked);
+        }
+        program_data.authorized_payout_key.require_auth();
 
-        let prog1 = String::from_str(&env, "Program1");
-        let prog2 = String::from_str(&env, "Program2");
+        let mut schedule = Self::load_schedule(&env, &program_id, schedule_id)?;
+        if schedule.released || schedule.cancelled {
+            return Err(Error::ScheduleNotPending);
+        }
 
-        // Mint tokens to backends
-        let token_admin_client = token::StellarAssetClient::new(&env, &token_client.address);
-        let amount1 = 10_000_0000000i128;
-        let amount2 = 20_000_0000000i128;
-        token_admin_client.mint(&backend1, &amount1);
-        token_admin_client.mint(&backend2, &amount2);
+        Self::unindex_schedule(&env, &program_id, &schedule);
+        Self::cancel_schedule(&env, &mut program_data, &mut schedule);
+        Self::store_program(&env, &program_data);
 
-        // Register programs
-        client.initialize_program(&prog1, &backend1, &token_client.address);
-        client.initialize_program(&prog2, &backend2, &token_client.address);
+        Ok(schedule)
+    }
 
-        // Transfer tokens to contract and lock funds
-        token_client.transfer(&backend1, &contract_id, &amount1);
-        token_client.transfer(&backend2, &contract_id, &amount2);
-        client.lock_program_funds(&prog1, &amount1);
-        client.lock_program_funds(&prog2, &amount2);
+    /// Mark a pending schedule cancelled and release what it still had
+    /// reserved back to the available balance, recording the cancellation
+    /// in the release history. The caller removes it from the due index and
+    /// persists `program_data`.
+    fn cancel_schedule(
+        env: &Env,
+        program_data: &mut ProgramData,
+        schedule: &mut ProgramReleaseSchedule,
+    ) {
+        let program_id = program_data.program_id.clone();
+        schedule.cancelled = true;
+        Self::store_schedule(env, &program_id, schedule);
 
-        // Perform operations on each program (just lock funds for now)
-        // Note: Payouts would be tested separately for authorization
+        let cancelled_amount = schedule.amount - schedule.released_amount;
+        program_data.committed_balance -= cancelled_amount;
 
-        // Global analytics should reflect all operations
-        let analytics = client.get_analytics();
-        assert!(analytics.operation_count >= 2); // init x2, lock x2
+        Self::record_release(
+            env,
+            &program_id,
+            schedule,
+            cancelled_amount,
+            ReleaseAction::Cancelled,
+        );
 
-        // In a future version with program-specific analytics, we would test:
-        // let analytics1 = client.get_program_analytics(&prog1);
-        // let analytics2 = client.get_program_analytics(&prog2);
-        // assert_eq!(analytics1.operation_count, 2); // init, lock
-        // assert_eq!(analytics2.operation_count, 2); // init, lock
+        env.events().publish(
+            (SCHEDULE_CANCELLED,),
+            ScheduleCancelledEvent {
+                version: EVENT_VERSION_V2,
+                program_id,
+                schedule_id: schedule.schedule_id,
+                recipient: schedule.recipient.clone(),
+                cancelled_amount,
+            },
+        );
     }
 
-    #[test]
-    fn test_lock_funds_cumulative() {
-        let env = Env::default();
-        env.mock_all_auths();
+    /// Change the recipient and release time of a schedule that has not paid
+    /// anything out yet (payout key only)
+    pub fn update_program_release_schedule(
+        env: Env,
+        program_id: String,
+        schedule_id: u64,
+        new_recipient: Address,
+        new_release_timestamp: u64,
+    ) -> Result<ProgramReleaseSchedule, Error> {
+        let program_data = Self::load_program(&env, &program_id)?;
+        if program_data.status != ProgramStatus::Active {
+            return Err(Error::ProgramNotActive);
+        }
+        if program_data.payout_key_revoked {
+            return Err(Error::PayoutKeyRevoked);
+        }
+        program_data.authorized_payout_key.require_auth();
 
-        let admin = Address::generate(&env);
-        let contract_id = env.register_contract(None, ProgramEscrowContract);
-        let client = ProgramEscrowContractClient::new(&env, &contract_id);
-        let token_client = create_token_contract(&env, &admin);
+        let mut schedule = Self::load_schedule(&env, &program_id, schedule_id)?;
+        if schedule.cancelled || schedule.released_amount > 0 {
+            return Err(Error::ScheduleNotPending);
+        }
 
-        let backend = Address::generate(&env);
-        let prog_id = String::from_str(&env, "Hackathon2024");
+        let old_recipient = schedule.recipient.clone();
+        let old_release_timestamp = schedule.release_timestamp;
+        Self::unindex_schedule(&env, &program_id, &schedule);
+        schedule.recipient = new_recipient.clone();
+        schedule.release_timestamp = new_release_timestamp;
+        Self::index_schedule(&env, &program_id, &schedule);
+        Self::store_schedule(&env, &program_id, &schedule);
 
-        client.initialize_program(&prog_id, &backend, &token_client.address);
+        Self::record_release(
+            &env,
+            &program_id,
+            &schedule,
+            schedule.amount,
+            ReleaseAction::Updated,
+        );
 
-        // Lock funds multiple times
-        client.lock_program_funds(&prog_id, &1_000_0000000);
-        client.lock_program_funds(&prog_id, &2_000_0000000);
-        client.lock_program_funds(&prog_id, &3_000_0000000);
+        env.events().publish(
+            (SCHEDULE_UPDATED,),
+            ScheduleUpdatedEvent {
+                version: EVENT_VERSION_V2,
+                program_id,
+                schedule_id,
+                old_recipient,
+                new_recipient,
+                old_release_timestamp,
+                new_release_timestamp,
+            },
+        );
 
-        let info = client.get_program_info(&prog_id);
-        assert_eq!(info.total_funds, 6_000_0000000);
-        assert_eq!(info.remaining_balance, 6_000_0000000);
+        Ok(schedule)
     }
 
-    #[test]
-    #[should_panic(expected = "Amount must be greater than zero")]
-    fn test_lock_zero_funds() {
-        let env = Env::default();
-        let contract_id = env.register_contract(None, ProgramEscrowContract);
-        let client = ProgramEscrowContractClient::new(&env, &contract_id);
-
-        let backend = Address::generate(&env);
-        let token = Address::generate(&env);
-        let prog_id = String::from_str(&env, "Hackathon2024");
-
-        client.initialize_program(&prog_id, &backend, &token);
-        client.lock_program_funds(&prog_id, &0);
+    /// View: amount of a schedule vested so far, including released parts
+    pub fn get_vested_amount(
+        env: Env,
+        program_id: String,
+        schedule_id: u64,
+    ) -> Result<i128, Error> {
+        let schedule = Self::load_schedule(&env, &program_id, schedule_id)?;
+        Ok(Self::vested_amount(&schedule, env.ledger().timestamp()))
     }
 
-    // ========================================================================
-    // Batch Payout Tests
-    // ========================================================================
+    /// Release vested funds on due schedules, walking the due index.
+    ///
+    /// Examines at most `max_count` index entries that come after `cursor`.
+    /// Fully released schedules leave the index; the rest (still vesting,
+    /// awaiting approval or short of balance) are skipped and stay pending.
+    /// Start a sweep at `ReleaseCursor::START` and pass `next_cursor` back
+    /// until `completed` is true. The cursor is the key of the last entry
+    /// examined rather than a position, so schedules added, cancelled or
+    /// released between runs do not shift it.
+    pub fn trigger_program_releases(
+        env: Env,
+        program_id: String,
+        cursor: ReleaseCursor,
+        max_count: u32,
+    ) -> Result<ReleaseRunResult, Error> {
+        // Reentrancy guard: Check and set
+        reentrancy_guard::check_not_entered(&env);
+        reentrancy_guard::set_entered(&env);
+        Self::require_circuit_closed(&env)?;
 
-    #[test]
-    #[should_panic(expected = "Recipients and amounts vectors must have the same length")]
-    fn test_batch_payout_mismatched_lengths() {
-        let env = Env::default();
-        env.mock_all_auths();
+        let mut program_data: ProgramData = env
+            .storage()
+            .persistent()
+            .get(&DataKey::Program(program_id.clone()))
+            .ok_or(Error::ProgramNotFound)?;
 
-        let admin = Address::generate(&env);
-        let contract_id = env.register_contract(None, ProgramEscrowContract);
-        let client = ProgramEscrowContractClient::new(&env, &contract_id);
-        let token_client = create_token_contract(&env, &admin);
+        if program_data.status != ProgramStatus::Active {
+            return Err(Error::ProgramNotActive);
+        }
 
-        let backend = Address::generate(&env);
-        let prog_id = String::from_str(&env, "Test");
+        if program_data.payout_key_revoked {
+            return Err(Error::PayoutKeyRevoked);
+        }
 
-        client.initialize_program(&prog_id, &backend, &token_client.address);
-        client.lock_program_funds(&prog_id, &10_000_0000000);
+        program_data.authorized_payout_key.require_auth();
 
-        let recipients = soroban_sdk::vec![&env, Address::generate(&env), Address::generate(&env)];
-        let amounts = soroban_sdk::vec![&env, 1_000_0000000i128]; // Mismatch!
+        let mut days = Self::load_due_days(&env, &program_id);
+        let mut days_changed = false;
 
-        client.batch_payout(&prog_id, &recipients, &amounts);
-    }
+        let now = env.ledger().timestamp();
+        let after = (cursor.unlock_at, cursor.schedule_id);
+        let mut last = after;
+        let mut examined: u32 = 0;
+        let mut released_count: u32 = 0;
+        let mut skipped_count: u32 = 0;
+        let mut completed = true;
+
+        let mut day_position: u32 = 0;
+        while day_position < days.len() {
+            let day = days.get(day_position).unwrap();
+            // Days are sorted, so nothing from here on is due yet
+            if day.saturating_mul(DUE_BUCKET_SECONDS) > now {
+                break;
+            }
 
-    #[test]
-    #[should_panic(expected = "Insufficient balance")]
-    fn test_batch_payout_insufficient_balance() {
-        let env = Env::default();
-        env.mock_all_auths();
+            // Entries up to the cursor were examined by an earlier run
+            if day < after.0 / DUE_BUCKET_SECONDS {
+                day_position += 1;
+                continue;
+            }
+            let mut bucket = Self::load_due_bucket(&env, &program_id, day);
+            let mut i: u32 = 0;
+            while i < bucket.len() && bucket.get(i).unwrap() <= after {
+                i += 1;
+            }
+            let mut bucket_changed = false;
+            let mut stop = false;
+
+            while i < bucket.len() {
+                let (unlock_at, schedule_id) = bucket.get(i).unwrap();
+                // Buckets are sorted, so nothing past this point is due yet
+                if unlock_at > now {
+                    stop = true;
+                    break;
+                }
+                if examined >= max_count {
+                    completed = false;
+                    stop = true;
+                    break;
+                }
+                examined += 1;
+                last = (unlock_at, schedule_id);
+
+                let mut schedule = Self::load_schedule(&env, &program_id, schedule_id)?;
+                let amount = Self::releasable_amount(&schedule, now);
+
+                // Large releases wait until the signers have approved them,
+                // and a failed transfer is recorded by the breaker and left
+                // pending
+                if amount <= 0
+                    || amount > program_data.remaining_balance
+                    || !Self::payout_approved(
+                        &env,
+                        &program_id,
+                        &schedule.recipient,
+                        Self::recipient_paid_total(&env, &program_id, &schedule.recipient),
+                        amount,
+                        true,
+                    )
+                    || !Self::release_schedule_amount(
+                        &env,
+                        &mut program_data,
+                        &mut schedule,
+                        amount,
+                        now,
+                    )
+                {
+                    skipped_count += 1;
+                    i += 1;
+                    continue;
+                }
+                Self::store_schedule(&env, &program_id, &schedule);
+                released_count += 1;
 
-        let admin = Address::generate(&env);
-        let contract_id = env.register_contract(None, ProgramEscrowContract);
-        let client = ProgramEscrowContractClient::new(&env, &contract_id);
-        let token_client = create_token_contract(&env, &admin);
+                if schedule.released {
+                    bucket.remove(i);
+                    bucket_changed = true;
+                } else {
+                    i += 1;
+                }
+            }
 
-        let backend = Address::generate(&env);
-        let prog_id = String::from_str(&env, "Test");
+            if bucket_changed {
+                Self::store_due_bucket(&env, &program_id, day, &bucket);
+            }
+            if bucket.is_empty() {
+                days.remove(day_position);
+                days_changed = true;
+            } else {
+                day_position += 1;
+            }
+            if stop {
+                break;
+            }
+        }
 
-        client.initialize_program(&prog_id, &backend, &token_client.address);
-        client.lock_program_funds(&prog_id, &5_000_0000000);
+        Self::store_program(&env, &program_data);
+        if days_changed {
+            Self::store_due_days(&env, &program_id, &days);
+        }
 
-        let recipients = soroban_sdk::vec![&env, Address::generate(&env)];
-        let amounts = soroban_sdk::vec![&env, 10_000_0000000i128]; // More than available!
+        // Clear reentrancy guard before returning
+        reentrancy_guard::clear_entered(&env);
 
-        client.batch_payout(&prog_id, &recipients, &amounts);
+        Ok(ReleaseRunResult {
+            released_count,
+            skipped_count,
+            next_cursor: ReleaseCursor {
+                unlock_at: last.0,
+                schedule_id: last.1,
+            },
+            completed,
+        })
     }
 
-    #[test]
-    fn test_program_count() {
-        let env = Env::default();
-        let contract_id = env.register_contract(None, ProgramEscrowContract);
-        let client = ProgramEscrowContractClient::new(&env, &contract_id);
+    pub fn get_program_release_schedules(
+        env: Env,
+        program_id: String,
+    ) -> Vec<ProgramReleaseSchedule> {
+        Self::load_schedules(&env, &program_id)
+    }
 
-        assert_eq!(client.get_program_count(), 0);
+    /// Page through a program's release history in the order it was written.
+    /// `cursor` is the entry id to start from.
+    pub fn get_program_release_history(
+        env: Env,
+        program_id: String,
+        cursor: u64,
+        limit: u32,
+    ) -> Result<ReleaseHistoryPage, Error> {
+        Self::load_program(&env, &program_id)?;
+        let count = Self::release_history_count(&env, &program_id);
+        let end = count.min(cursor.saturating_add(limit as u64));
+        let mut records = Vec::new(&env);
+
+        for entry_id in cursor..end {
+            records.push_back(Self::load_release_entry(&env, &program_id, entry_id));
+        }
+        Ok(ReleaseHistoryPage {
+            records,
+            next_cursor: if end < count { Some(end) } else { None },
+        })
+    }
 
-        let backend = Address::generate(&env);
-        let token = Address::generate(&env);
+    /// Number of release history entries of a program
+    pub fn get_release_history_count(env: Env, program_id: String) -> Result<u64, Error> {
+        Self::load_program(&env, &program_id)?;
+        Ok(Self::release_history_count(&env, &program_id))
+    }
 
-        client.initialize_program(&String::from_str(&env, "P1"), &backend, &token);
-        assert_eq!(client.get_program_count(), 1);
+    /// Page through a program's payout records in the order they were made.
+    /// `cursor` is the payout id to start from.
+    pub fn get_payout_history(
+        env: Env,
+        program_id: String,
+        cursor: u64,
+        limit: u32,
+    ) -> Result<PayoutPage, Error> {
+        Self::load_program(&env, &program_id)?;
+        let count = Self::payout_count(&env, &program_id);
+        let end = count.min(cursor.saturating_add(limit as u64));
+        let mut records = Vec::new(&env);
+
+        for payout_id in cursor..end {
+            records.push_back(Self::load_payout(&env, &program_id, payout_id));
+        }
+        Ok(PayoutPage {
+            records,
+            next_cursor: if end < count { Some(end) } else { None },
+        })
+    }
 
-        client.initialize_program(&String::from_str(&env, "P2"), &backend, &token);
-        assert_eq!(client.get_program_count(), 2);
+    pub fn get_payout_count(env: Env, program_id: String) -> Result<u64, Error> {
+        Self::load_program(&env, &program_id)?;
+        Ok(Self::payout_count(&env, &program_id))
+    }
 
-        client.initialize_program(&String::from_str(&env, "P3"), &backend, &token);
-        assert_eq!(client.get_program_count(), 3);
+    /// Query payout history by recipient with cursor pagination. `cursor`
+    /// is the position within the recipient's payouts.
+    pub fn query_payouts_by_recipient(
+        env: Env,
+        program_id: String,
+        recipient: Address,
+        cursor: u64,
+        limit: u32,
+    ) -> Result<PayoutPage, Error> {
+        Self::load_program(&env, &program_id)?;
+        let payout_ids = Self::load_recipient_payouts(&env, &program_id, &recipient);
+        let count = payout_ids.len() as u64;
+        let end = count.min(cursor.saturating_add(limit as u64));
+        let mut records = Vec::new(&env);
+
+        for position in cursor..end {
+            let payout_id = payout_ids.get(position as u32).unwrap();
+            records.push_back(Self::load_payout(&env, &program_id, payout_id));
+        }
+        Ok(PayoutPage {
+            records,
+            next_cursor: if end < count { Some(end) } else { None },
+        })
     }
 
-    // ========================================================================
-    // Anti-Abuse Tests
-    // ========================================================================
+    /// Query payout history by amount range. `cursor` is the payout id to
+    /// resume from. Amounts are not indexed, so `limit` caps the records
+    /// examined and a page may hold fewer matches than `limit`.
+    pub fn query_payouts_by_amount(
+        env: Env,
+        program_id: String,
+        min_amount: i128,
+        max_amount: i128,
+        cursor: u64,
+        limit: u32,
+    ) -> Result<PayoutPage, Error> {
+        Self::load_program(&env, &program_id)?;
+        let count = Self::payout_count(&env, &program_id);
+        let end = count.min(cursor.saturating_add(limit as u64));
+        let mut records = Vec::new(&env);
+
+        for payout_id in cursor..end {
+            let record = Self::load_payout(&env, &program_id, payout_id);
+            if record.amount >= min_amount && record.amount <= max_amount {
+                records.push_back(record);
+            }
+        }
+        Ok(PayoutPage {
+            records,
+            next_cursor: if end < count { Some(end) } else { None },
+        })
+    }
 
-    #[test]
-    #[should_panic(expected = "Operation in cooldown period")]
-    fn test_anti_abuse_cooldown_panic() {
-        let env = Env::default();
-        env.mock_all_auths();
-        env.ledger().set_timestamp(1000);
-        let contract_id = env.register_contract(None, ProgramEscrowContract);
-        let client = ProgramEscrowContractClient::new(&env, &contract_id);
+    /// Query payout history by timestamp range. `cursor` is the payout id
+    /// to resume from; pass 0 to start at `min_timestamp`.
+    pub fn query_payouts_by_timestamp(
+        env: Env,
+        program_id: String,
+        min_timestamp: u64,
+        max_timestamp: u64,
+        cursor: u64,
+        limit: u32,
+    ) -> Result<PayoutPage, Error> {
+        Self::load_program(&env, &program_id)?;
+        let count = Self::payout_count(&env, &program_id);
+        let mut payout_id = cursor.max(Self::first_payout_at(&env, &program_id, min_timestamp));
+        let mut records = Vec::new(&env);
+
+        while payout_id < count {
+            let record = Self::load_payout(&env, &program_id, payout_id);
+            if record.timestamp > max_timestamp {
+                break;
+            }
+            if records.len() >= limit {
+                return Ok(PayoutPage {
+                    records,
+                    next_cursor: Some(payout_id),
+                });
+            }
+            records.push_back(record);
+            payout_id += 1;
+        }
+        Ok(PayoutPage {
+            records,
+            next_cursor: None,
+        })
+    }
 
-        let admin = Address::generate(&env);
-        client.set_admin(&admin);
-        client.update_rate_limit_config(&3600, &10, &60);
+    /// Query release schedules by recipient
+    pub fn query_schedules_by_recipient(
+        env: Env,
+        program_id: String,
+        recipient: Address,
+        offset: u32,
+        limit: u32,
+    ) -> Vec<ProgramReleaseSchedule> {
+        let schedules = Self::load_schedules(&env, &program_id);
+        let mut results = Vec::new(&env);
+        let mut count = 0u32;
+        let mut skipped = 0u32;
 
-        let backend = Address::generate(&env);
-        let token = Address::generate(&env);
+        for i in 0..schedules.len() {
+            if count >= limit {
+                break;
+            }
+            let schedule = schedules.get(i).unwrap();
+            if schedule.recipient == recipient {
+                if skipped < offset {
+                    skipped += 1;
+                    continue;
+                }
+                results.push_back(schedule);
+                count += 1;
+            }
+        }
+        results
+    }
 
-        client.initialize_program(&String::from_str(&env, "P1"), &backend, &token);
+    /// Query release schedules by released status
+    pub fn query_schedules_by_status(
+        env: Env,
+        program_id: String,
+        released: bool,
+        offset: u32,
+        limit: u32,
+    ) -> Vec<ProgramReleaseSchedule> {
+        let schedules = Self::load_schedules(&env, &program_id);
+        let mut results = Vec::new(&env);
+        let mut count = 0u32;
+        let mut skipped = 0u32;
 
-        // Advance time by 30s (less than 60s cooldown)
-        env.ledger().with_mut(|li| li.timestamp += 30);
+        for i in 0..schedules.len() {
+            if count >= limit {
+                break;
+            }
+            let schedule = schedules.get(i).unwrap();
+            if schedule.released == released {
+                if skipped < offset {
+                    skipped += 1;
+                    continue;
+                }
+                results.push_back(schedule);
+                count += 1;
+            }
+        }
+        results
+    }
 
-        client.initialize_program(&String::from_str(&env, "P2"), &backend, &token);
+    /// Query release history by recipient. Examines up to `limit` entries
+    /// starting at entry id `cursor`.
+    pub fn query_releases_by_recipient(
+        env: Env,
+        program_id: String,
+        recipient: Address,
+        cursor: u64,
+        limit: u32,
+    ) -> Result<ReleaseHistoryPage, Error> {
+        Self::load_program(&env, &program_id)?;
+        let count = Self::release_history_count(&env, &program_id);
+        let end = count.min(cursor.saturating_add(limit as u64));
+        let mut records = Vec::new(&env);
+
+        for entry_id in cursor..end {
+            let record = Self::load_release_entry(&env, &program_id, entry_id);
+            if record.recipient == recipient {
+                records.push_back(record);
+            }
+        }
+        Ok(ReleaseHistoryPage {
+            records,
+            next_cursor: if end < count { Some(end) } else { None },
+        })
     }
 
-    #[test]
-    #[should_panic(expected = "Rate limit exceeded")]
-    fn test_anti_abuse_limit_panic() {
-        let env = Env::default();
-        env.mock_all_auths();
-        env.ledger().set_timestamp(1000);
-        let contract_id = env.register_contract(None, ProgramEscrowContract);
-        let client = ProgramEscrowContractClient::new(&env, &contract_id);
+    /// Get aggregate statistics for the program
+    pub fn get_program_aggregate_stats(
+        env: Env,
+        program_id: String,
+    ) -> Result<ProgramAggregateStats, Error> {
+        let program_data = Self::load_program(&env, &program_id)?;
+        let schedules = Self::load_schedules(&env, &program_id);
 
-        let admin = Address::generate(&env);
-        client.set_admin(&admin);
-        client.update_rate_limit_config(&3600, &2, &0); // 2 ops max, no cooldown
+        let mut scheduled_count = 0u32;
+        let mut released_count = 0u32;
 
-        let backend = Address::generate(&env);
-        let token = Address::generate(&env);
+        for i in 0..schedules.len() {
+            let schedule = schedules.get(i).unwrap();
+            if schedule.released {
+                released_count += 1;
+            } else if !schedule.cancelled {
+                scheduled_count += 1;
+            }
+        }
 
-        client.initialize_program(&String::from_str(&env, "P1"), &backend, &token);
-        client.initialize_program(&String::from_str(&env, "P2"), &backend, &token);
-        client.initialize_program(&String::from_str(&env, "P3"), &backend, &token);
-        // Should panic
+        Ok(ProgramAggregateStats {
+            total_funds: program_data.total_funds,
+            remaining_balance: program_data.remaining_balance,
+            total_paid_out: program_data.total_funds
+                - program_data.remaining_balance
+                - program_data.total_refunded,
+            payout_count: Self::payout_count(&env, &program_id),
+            scheduled_count,
+            released_count,
+        })
     }
 
-    #[test]
-    fn test_anti_abuse_whitelist() {
-        let env = Env::default();
-        env.mock_all_auths();
-        env.ledger().set_timestamp(1000);
-        let contract_id = env.register_contract(None, ProgramEscrowContract);
-        let client = ProgramEscrowContractClient::new(&env, &contract_id);
-
-        let admin = Address::generate(&env);
-        client.set_admin(&admin);
-        client.update_rate_limit_config(&3600, &1, &60); // 1 op max
-
-        let backend = Address::generate(&env);
-        let token = Address::generate(&env);
+    /// Get payouts by recipient
+    pub fn get_payouts_by_recipient(
+        env: Env,
+        program_id: String,
+        recipient: Address,
+        cursor: u64,
+        limit: u32,
+    ) -> Result<PayoutPage, Error> {
+        Self::query_payouts_by_recipient(env, program_id, recipient, cursor, limit)
+    }
 
-        client.set_whitelist(&backend, &true);
+    /// Get pending schedules (not yet released)
+    pub fn get_pending_schedules(env: Env, program_id: String) -> Vec<ProgramReleaseSchedule> {
+        let schedules = Self::load_schedules(&env, &program_id);
+        let mut results = Vec::new(&env);
 
-        client.initialize_program(&String::from_str(&env, "P1"), &backend, &token);
-        client.initialize_program(&String::from_str(&env, "P2"), &backend, &token);
-        // Should work because whitelisted
+        for i in 0..schedules.len() {
+            let schedule = schedules.get(i).unwrap();
+            if !schedule.released && !schedule.cancelled {
+                results.push_back(schedule);
+            }
+        }
+        results
     }
 
-    #[test]
-    fn test_anti_abuse_config_update() {
-        let env = Env::default();
-        env.mock_all_auths();
-        let contract_id = env.register_contract(None, ProgramEscrowContract);
-        let client = ProgramEscrowContractClient::new(&env, &contract_id);
+    /// Get due schedules (with a vested amount ready to be released), in
+    /// the order `trigger_program_releases` visits them
+    pub fn get_due_schedules(env: Env, program_id: String) -> Vec<ProgramReleaseSchedule> {
+        let now = env.ledger().timestamp();
+        let mut results = Vec::new(&env);
 
-        let admin = Address::generate(&env);
-        client.set_admin(&admin);
+        for day in Self::load_due_days(&env, &program_id).iter() {
+            if day.saturating_mul(DUE_BUCKET_SECONDS) > now {
+                break;
+            }
+            for (unlock_at, schedule_id) in Self::load_due_bucket(&env, &program_id, day).iter() {
+                if unlock_at > now {
+                    break;
+                }
+                let Ok(schedule) = Self::load_schedule(&env, &program_id, schedule_id) else {
+                    continue;
+                };
+                if Self::releasable_amount(&schedule, now) > 0 {
+                    results.push_back(schedule);
+                }
+            }
+        }
+        results
+    }
 
-        client.update_rate_limit_config(&7200, &5, &120);
+    /// Get total amount still to be released from pending schedules
+    pub fn get_total_scheduled_amount(env: Env, program_id: String) -> i128 {
+        let schedules = Self::load_schedules(&env, &program_id);
+        let mut total = 0i128;
 
-        let config = client.get_rate_limit_config();
-        assert_eq!(config.window_size, 7200);
-        assert_eq!(config.max_operations, 5);
-        assert_eq!(config.cooldown_period, 120);
+        for i in 0..schedules.len() {
+            let schedule = schedules.get(i).unwrap();
+            if !schedule.released && !schedule.cancelled {
+                total += schedule.amount - schedule.released_amount;
+            }
+        }
+        total
     }
 }
+
+#[cfg(test)]
 mod test;
@@ -19,13 +19,19 @@ use soroban_sdk::{contract, contractimpl, Address, Env, String};
 
 /// Interface for the ProgramEscrow contract (simplified for testing)
 pub trait ProgramEscrowTrait {
-    fn single_payout(env: Env, recipient: Address, amount: i128);
+    fn single_payout(env: Env, program_id: String, recipient: Address, amount: i128);
     fn batch_payout(
         env: Env,
+        program_id: String,
         recipients: soroban_sdk::Vec<Address>,
         amounts: soroban_sdk::Vec<i128>,
     );
-    fn trigger_program_releases(env: Env) -> u32;
+    fn trigger_program_releases(
+        env: Env,
+        program_id: String,
+        cursor: crate::ReleaseCursor,
+        max_count: u32,
+    ) -> crate::ReleaseRunResult;
 }
 
 #[contract]
@@ -33,11 +39,15 @@ pub struct MaliciousReentrantContract;
 
 #[contractimpl]
 impl MaliciousReentrantContract {
-    /// Initialize the malicious contract with the target escrow contract address
-    pub fn init(env: Env, target_contract: Address) {
+    /// Initialize the malicious contract with the target escrow contract
+    /// address and the program it attacks
+    pub fn init(env: Env, target_contract: Address, program_id: String) {
         env.storage()
             .instance()
             .set(&soroban_sdk::symbol_short!("Target"), &target_contract);
+        env.storage()
+            .instance()
+            .set(&soroban_sdk::symbol_short!("TargetPrg"), &program_id);
     }
 
     /// Get the target contract address
@@ -48,6 +58,14 @@ impl MaliciousReentrantContract {
             .unwrap()
     }
 
+    /// Get the targeted program id
+    pub fn get_target_program(env: Env) -> String {
+        env.storage()
+            .instance()
+            .get(&soroban_sdk::symbol_short!("TargetPrg"))
+            .unwrap()
+    }
+
     /// Set attack mode
     /// - 0: No attack (normal behavior)
     /// - 1: Reenter on single_payout
@@ -116,7 +134,7 @@ impl MaliciousReentrantContract {
                 // Attempt to call single_payout again (reentrancy)
                 // This should be blocked by the reentrancy guard
                 let client = crate::ProgramEscrowContractClient::new(&env, &target);
-                client.single_payout(&attacker, &amount);
+                client.single_payout(&Self::get_target_program(env.clone()), &attacker, &amount);
             }
             2 => {
                 // Attack mode 2: Reenter batch_payout
@@ -128,7 +146,11 @@ impl MaliciousReentrantContract {
 
                 // Attempt to call batch_payout again (reentrancy)
                 let client = crate::ProgramEscrowContractClient::new(&env, &target);
-                client.batch_payout(&recipients, &amounts);
+                client.batch_payout(
+                    &Self::get_target_program(env.clone()),
+                    &recipients,
+                    &amounts,
+                );
             }
             3 => {
                 // Attack mode 3: Reenter trigger_program_releases
@@ -136,7 +158,11 @@ impl MaliciousReentrantContract {
 
                 // Attempt to trigger releases again (reentrancy)
                 let client = crate::ProgramEscrowContractClient::new(&env, &target);
-                client.trigger_program_releases();
+                client.trigger_program_releases(
+                    &Self::get_target_program(env.clone()),
+                    &crate::ReleaseCursor::START,
+                    &10,
+                );
             }
             _ => {
                 // No attack, normal behavior
@@ -150,7 +176,7 @@ impl MaliciousReentrantContract {
         Self::increment_attack_count(&env);
 
         let client = crate::ProgramEscrowContractClient::new(&env, &target);
-        client.single_payout(&recipient, &amount);
+        client.single_payout(&Self::get_target_program(env.clone()), &recipient, &amount);
     }
 
     /// Attempt direct reentrancy attack on batch_payout
@@ -163,7 +189,11 @@ impl MaliciousReentrantContract {
         Self::increment_attack_count(&env);
 
         let client = crate::ProgramEscrowContractClient::new(&env, &target);
-        client.batch_payout(&recipients, &amounts);
+        client.batch_payout(
+            &Self::get_target_program(env.clone()),
+            &recipients,
+            &amounts,
+        );
     }
 
     /// Attempt nested call during execution
@@ -181,6 +211,6 @@ impl MaliciousReentrantContract {
         Self::set_attack_mode(env.clone(), 1);
 
         // This should trigger the callback which will attempt reentrancy
-        client.single_payout(&attacker, &amount);
+        client.single_payout(&Self::get_target_program(env.clone()), &attacker, &amount);
     }
 }
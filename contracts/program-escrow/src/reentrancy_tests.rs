@@ -16,7 +16,7 @@
 use crate::*;
 use soroban_sdk::{
     testutils::{Address as _, Ledger},
-    token, Address, Env, String, Vec,
+    token, Address, Env, String,
 };
 
 // Test helper to create a mock token contract
@@ -35,20 +35,23 @@ fn test_reentrancy_guard_basic_functionality() {
     use crate::reentrancy_guard::*;
 
     let env = Env::default();
+    let contract_id = env.register_contract(None, ProgramEscrowContract);
 
-    // Initially, guard should not be set
-    assert!(!is_entered(&env));
+    env.as_contract(&contract_id, || {
+        // Initially, guard should not be set
+        assert!(!is_entered(&env));
 
-    // Check should pass
-    check_not_entered(&env);
+        // Check should pass
+        check_not_entered(&env);
 
-    // Set the guard
-    set_entered(&env);
-    assert!(is_entered(&env));
+        // Set the guard
+        set_entered(&env);
+        assert!(is_entered(&env));
 
-    // Clear the guard
-    clear_entered(&env);
-    assert!(!is_entered(&env));
+        // Clear the guard
+        clear_entered(&env);
+        assert!(!is_entered(&env));
+    });
 }
 
 #[test]
@@ -57,12 +60,15 @@ fn test_reentrancy_guard_detects_reentry() {
     use crate::reentrancy_guard::*;
 
     let env = Env::default();
+    let contract_id = env.register_contract(None, ProgramEscrowContract);
 
-    // Set the guard
-    set_entered(&env);
+    env.as_contract(&contract_id, || {
+        // Set the guard
+        set_entered(&env);
 
-    // This should panic
-    check_not_entered(&env);
+        // This should panic
+        check_not_entered(&env);
+    });
 }
 
 #[test]
@@ -70,21 +76,24 @@ fn test_reentrancy_guard_allows_sequential_calls() {
     use crate::reentrancy_guard::*;
 
     let env = Env::default();
+    let contract_id = env.register_contract(None, ProgramEscrowContract);
 
-    // First call
-    check_not_entered(&env);
-    set_entered(&env);
-    clear_entered(&env);
-
-    // Second call (should succeed)
-    check_not_entered(&env);
-    set_entered(&env);
-    clear_entered(&env);
-
-    // Third call (should succeed)
-    check_not_entered(&env);
-    set_entered(&env);
-    clear_entered(&env);
+    env.as_contract(&contract_id, || {
+        // First call
+        check_not_entered(&env);
+        set_entered(&env);
+        clear_entered(&env);
+
+        // Second call (should succeed)
+        check_not_entered(&env);
+        set_entered(&env);
+        clear_entered(&env);
+
+        // Third call (should succeed)
+        check_not_entered(&env);
+        set_entered(&env);
+        clear_entered(&env);
+    });
 }
 
 // ============================================================================
@@ -111,14 +120,11 @@ fn test_single_payout_normal_execution() {
 
     client.init_program(&program_id, &authorized_key, &token_client.address);
 
-    // Transfer tokens to contract
-    token_client.transfer(&authorized_key, &contract_id, &amount);
-
     // Lock funds
-    client.lock_program_funds(&amount);
+    client.lock_program_funds(&program_id, &authorized_key, &amount);
 
     // Execute single payout (should succeed)
-    let result = client.single_payout(&recipient, &(amount / 2));
+    let result = client.single_payout(&program_id, &recipient, &(amount / 2));
 
     assert_eq!(result.remaining_balance, amount / 2);
 }
@@ -142,14 +148,13 @@ fn test_single_payout_blocks_reentrancy() {
     token_admin.mint(&authorized_key, &amount);
 
     client.init_program(&program_id, &authorized_key, &token_client.address);
-    token_client.transfer(&authorized_key, &contract_id, &amount);
-    client.lock_program_funds(&amount);
+    client.lock_program_funds(&program_id, &authorized_key, &amount);
 
     // Manually set the reentrancy guard to simulate an ongoing call
-    crate::reentrancy_guard::set_entered(&env);
+    env.as_contract(&contract_id, || crate::reentrancy_guard::set_entered(&env));
 
     // This should panic with "Reentrancy detected"
-    client.single_payout(&authorized_key, &(amount / 2));
+    client.single_payout(&program_id, &authorized_key, &(amount / 2));
 }
 
 // ============================================================================
@@ -176,14 +181,13 @@ fn test_batch_payout_normal_execution() {
     token_admin.mint(&authorized_key, &total_amount);
 
     client.init_program(&program_id, &authorized_key, &token_client.address);
-    token_client.transfer(&authorized_key, &contract_id, &total_amount);
-    client.lock_program_funds(&total_amount);
+    client.lock_program_funds(&program_id, &authorized_key, &total_amount);
 
     // Execute batch payout
     let recipients = vec![&env, recipient1, recipient2];
     let amounts = vec![&env, 400_0000000i128, 600_0000000i128];
 
-    let result = client.batch_payout(&recipients, &amounts);
+    let result = client.batch_payout(&program_id, &recipients, &amounts);
 
     assert_eq!(result.remaining_balance, 0);
 }
@@ -209,16 +213,15 @@ fn test_batch_payout_blocks_reentrancy() {
     token_admin.mint(&authorized_key, &total_amount);
 
     client.init_program(&program_id, &authorized_key, &token_client.address);
-    token_client.transfer(&authorized_key, &contract_id, &total_amount);
-    client.lock_program_funds(&total_amount);
+    client.lock_program_funds(&program_id, &authorized_key, &total_amount);
 
     // Manually set the reentrancy guard
-    crate::reentrancy_guard::set_entered(&env);
+    env.as_contract(&contract_id, || crate::reentrancy_guard::set_entered(&env));
 
     // This should panic
     let recipients = vec![&env, recipient1, recipient2];
     let amounts = vec![&env, 400_0000000i128, 600_0000000i128];
-    client.batch_payout(&recipients, &amounts);
+    client.batch_payout(&program_id, &recipients, &amounts);
 }
 
 // ============================================================================
@@ -245,16 +248,15 @@ fn test_cross_function_reentrancy_single_to_batch() {
     token_admin.mint(&authorized_key, &amount);
 
     client.init_program(&program_id, &authorized_key, &token_client.address);
-    token_client.transfer(&authorized_key, &contract_id, &amount);
-    client.lock_program_funds(&amount);
+    client.lock_program_funds(&program_id, &authorized_key, &amount);
 
     // Simulate being inside single_payout
-    crate::reentrancy_guard::set_entered(&env);
+    env.as_contract(&contract_id, || crate::reentrancy_guard::set_entered(&env));
 
     // Try to call batch_payout (should be blocked)
     let recipients = vec![&env, recipient];
     let amounts = vec![&env, amount / 2];
-    client.batch_payout(&recipients, &amounts);
+    client.batch_payout(&program_id, &recipients, &amounts);
 }
 
 #[test]
@@ -277,14 +279,13 @@ fn test_cross_function_reentrancy_batch_to_single() {
     token_admin.mint(&authorized_key, &amount);
 
     client.init_program(&program_id, &authorized_key, &token_client.address);
-    token_client.transfer(&authorized_key, &contract_id, &amount);
-    client.lock_program_funds(&amount);
+    client.lock_program_funds(&program_id, &authorized_key, &amount);
 
     // Simulate being inside batch_payout
-    crate::reentrancy_guard::set_entered(&env);
+    env.as_contract(&contract_id, || crate::reentrancy_guard::set_entered(&env));
 
     // Try to call single_payout (should be blocked)
-    client.single_payout(&recipient, &(amount / 2));
+    client.single_payout(&program_id, &recipient, &(amount / 2));
 }
 
 // ============================================================================
@@ -311,17 +312,18 @@ fn test_trigger_releases_normal_execution() {
     token_admin.mint(&authorized_key, &amount);
 
     client.init_program(&program_id, &authorized_key, &token_client.address);
-    token_client.transfer(&authorized_key, &contract_id, &amount);
-    client.lock_program_funds(&amount);
+    client.lock_program_funds(&program_id, &authorized_key, &amount);
 
     // Create schedule
-    client.create_program_release_schedule(&recipient, &amount, &release_timestamp);
+    client.create_program_release_schedule(&program_id, &recipient, &amount, &release_timestamp);
 
     // Advance time
     env.ledger().set_timestamp(release_timestamp + 1);
 
     // Trigger releases (should succeed)
-    let released_count = client.trigger_program_releases();
+    let released_count = client
+        .trigger_program_releases(&program_id, &ReleaseCursor::START, &50)
+        .released_count;
 
     assert_eq!(released_count, 1);
 }
@@ -347,20 +349,19 @@ fn test_trigger_releases_blocks_reentrancy() {
     token_admin.mint(&authorized_key, &amount);
 
     client.init_program(&program_id, &authorized_key, &token_client.address);
-    token_client.transfer(&authorized_key, &contract_id, &amount);
-    client.lock_program_funds(&amount);
+    client.lock_program_funds(&program_id, &authorized_key, &amount);
 
     // Create schedule
-    client.create_program_release_schedule(&recipient, &amount, &release_timestamp);
+    client.create_program_release_schedule(&program_id, &recipient, &amount, &release_timestamp);
 
     // Advance time
     env.ledger().set_timestamp(release_timestamp + 1);
 
     // Manually set the reentrancy guard
-    crate::reentrancy_guard::set_entered(&env);
+    env.as_contract(&contract_id, || crate::reentrancy_guard::set_entered(&env));
 
     // This should panic
-    client.trigger_program_releases();
+    client.trigger_program_releases(&program_id, &ReleaseCursor::START, &50);
 }
 
 // ============================================================================
@@ -389,15 +390,14 @@ fn test_multiple_sequential_payouts_succeed() {
     token_admin.mint(&authorized_key, &total_amount);
 
     client.init_program(&program_id, &authorized_key, &token_client.address);
-    token_client.transfer(&authorized_key, &contract_id, &total_amount);
-    client.lock_program_funds(&total_amount);
+    client.lock_program_funds(&program_id, &authorized_key, &total_amount);
 
     // Execute multiple sequential payouts (all should succeed)
-    client.single_payout(&recipient1, &payout_amount);
-    client.single_payout(&recipient2, &payout_amount);
-    client.single_payout(&recipient3, &payout_amount);
+    client.single_payout(&program_id, &recipient1, &payout_amount);
+    client.single_payout(&program_id, &recipient2, &payout_amount);
+    client.single_payout(&program_id, &recipient3, &payout_amount);
 
-    let program_data = client.get_program_info();
+    let program_data = client.get_program_info(&program_id);
     assert_eq!(
         program_data.remaining_balance,
         total_amount - (payout_amount * 3)
@@ -429,17 +429,16 @@ fn test_guard_cleared_after_successful_payout() {
     token_admin.mint(&authorized_key, &amount);
 
     client.init_program(&program_id, &authorized_key, &token_client.address);
-    token_client.transfer(&authorized_key, &contract_id, &amount);
-    client.lock_program_funds(&amount);
+    client.lock_program_funds(&program_id, &authorized_key, &amount);
 
     // Guard should not be set initially
-    assert!(!is_entered(&env));
+    assert!(!env.as_contract(&contract_id, || is_entered(&env)));
 
     // Execute payout
-    client.single_payout(&recipient, &(amount / 2));
+    client.single_payout(&program_id, &recipient, &(amount / 2));
 
     // Guard should be cleared after successful execution
-    assert!(!is_entered(&env));
+    assert!(!env.as_contract(&contract_id, || is_entered(&env)));
 }
 
 #[test]
@@ -464,22 +463,21 @@ fn test_guard_state_across_multiple_operations() {
     token_admin.mint(&authorized_key, &total_amount);
 
     client.init_program(&program_id, &authorized_key, &token_client.address);
-    token_client.transfer(&authorized_key, &contract_id, &total_amount);
-    client.lock_program_funds(&total_amount);
+    client.lock_program_funds(&program_id, &authorized_key, &total_amount);
 
     // Verify guard state through multiple operations
-    assert!(!is_entered(&env));
+    assert!(!env.as_contract(&contract_id, || is_entered(&env)));
 
-    client.single_payout(&recipient1, &300_0000000i128);
-    assert!(!is_entered(&env));
+    client.single_payout(&program_id, &recipient1, &300_0000000i128);
+    assert!(!env.as_contract(&contract_id, || is_entered(&env)));
 
     let recipients = vec![&env, recipient2];
     let amounts = vec![&env, 200_0000000i128];
-    client.batch_payout(&recipients, &amounts);
-    assert!(!is_entered(&env));
+    client.batch_payout(&program_id, &recipients, &amounts);
+    assert!(!env.as_contract(&contract_id, || is_entered(&env)));
 
-    client.single_payout(&recipient1, &100_0000000i128);
-    assert!(!is_entered(&env));
+    client.single_payout(&program_id, &recipient1, &100_0000000i128);
+    assert!(!env.as_contract(&contract_id, || is_entered(&env)));
 }
 
 // ============================================================================
@@ -510,5 +508,10 @@ fn test_reentrancy_guard_model_documentation() {
     // Since the guard is automatically cleared on panic and explicitly
     // cleared on success, there's no risk of permanent lockout.
 
-    assert!(true, "Documentation test - see comments for guarantees");
+    use crate::reentrancy_guard::is_entered;
+
+    // A fresh contract starts with the guard cleared
+    let env = Env::default();
+    let contract_id = env.register_contract(None, ProgramEscrowContract);
+    assert!(!env.as_contract(&contract_id, || is_entered(&env)));
 }
@@ -2,8 +2,8 @@
 
 use super::*;
 use soroban_sdk::{
-    testutils::{Address as _, Events, Ledger},
-    token, vec, Address, Env, Map, String, Symbol, TryFromVal, Val,
+    testutils::{Address as _, Events, IssuerFlags, Ledger, MockAuth, MockAuthInvoke},
+    token, vec, Address, Env, IntoVal, Map, String, Symbol, TryFromVal, Val,
 };
 
 fn setup_program(
@@ -14,6 +14,7 @@ fn setup_program(
     Address,
     token::Client<'static>,
     token::StellarAssetClient<'static>,
+    String,
 ) {
     env.mock_all_auths();
 
@@ -30,11 +31,11 @@ fn setup_program(
     client.init_program(&program_id, &admin, &token_id);
 
     if initial_amount > 0 {
-        token_admin_client.mint(&client.address, &initial_amount);
-        client.lock_program_funds(&initial_amount);
+        token_admin_client.mint(&admin, &initial_amount);
+        client.lock_program_funds(&program_id, &admin, &initial_amount);
     }
 
-    (client, admin, token_client, token_admin_client)
+    (client, admin, token_client, token_admin_client, program_id)
 }
 
 fn next_seed(seed: &mut u64) -> u64 {
@@ -69,27 +70,83 @@ fn test_init_program_and_event() {
     assert_eq!(data.remaining_balance, 0);
 
     let events = env.events().all();
-    assert!(events.len() >= 1);
+    assert!(!events.is_empty());
 }
 
 #[test]
 fn test_lock_program_funds_multi_step_balance() {
     let env = Env::default();
-    let (client, _admin, _token, _token_admin) = setup_program(&env, 0);
+    let (client, admin, _token, token_admin, program_id) = setup_program(&env, 0);
 
-    client.lock_program_funds(&10_000);
-    client.lock_program_funds(&5_000);
-    assert_eq!(client.get_remaining_balance(), 15_000);
-    assert_eq!(client.get_program_info().total_funds, 15_000);
+    token_admin.mint(&admin, &15_000);
+    client.lock_program_funds(&program_id, &admin, &10_000);
+    client.lock_program_funds(&program_id, &admin, &5_000);
+    assert_eq!(client.get_remaining_balance(&program_id), 15_000);
+    assert_eq!(client.get_program_info(&program_id).total_funds, 15_000);
+}
+
+#[test]
+fn test_lock_program_funds_pulls_tokens_from_funder() {
+    let env = Env::default();
+    let (client, _admin, token_client, token_admin, program_id) = setup_program(&env, 0);
+    let funder = Address::generate(&env);
+    token_admin.mint(&funder, &50_000);
+
+    let data = client.lock_program_funds(&program_id, &funder, &20_000);
+    assert_eq!(env.auths()[0].0, funder);
+
+    assert_eq!(data.remaining_balance, 20_000);
+    assert_eq!(token_client.balance(&funder), 30_000);
+    assert_eq!(token_client.balance(&client.address), 20_000);
+}
+
+#[test]
+fn test_lock_program_funds_records_deposit_ledger() {
+    let env = Env::default();
+    let (client, admin, _token, token_admin, program_id) = setup_program(&env, 10_000);
+    let sponsor = Address::generate(&env);
+    token_admin.mint(&sponsor, &8_000);
+    token_admin.mint(&admin, &2_000);
+
+    client.lock_program_funds(&program_id, &sponsor, &8_000);
+    client.lock_program_funds(&program_id, &admin, &2_000);
+
+    let deposits = client.get_program_deposits(&program_id);
+    assert_eq!(deposits.len(), 2);
+    assert_eq!(
+        deposits.get(0).unwrap(),
+        ProgramDeposit {
+            funder: admin,
+            amount: 12_000
+        }
+    );
+    assert_eq!(
+        deposits.get(1).unwrap(),
+        ProgramDeposit {
+            funder: sponsor,
+            amount: 8_000
+        }
+    );
+    assert_eq!(client.get_remaining_balance(&program_id), 20_000);
+}
+
+#[test]
+#[should_panic]
+fn test_lock_program_funds_without_funder_balance_fails() {
+    let env = Env::default();
+    let (client, _admin, _token, _token_admin, program_id) = setup_program(&env, 0);
+    let funder = Address::generate(&env);
+
+    client.lock_program_funds(&program_id, &funder, &1_000);
 }
 
 #[test]
 fn test_edge_zero_initial_state() {
     let env = Env::default();
-    let (client, _admin, token_client, _token_admin) = setup_program(&env, 0);
+    let (client, _admin, token_client, _token_admin, program_id) = setup_program(&env, 0);
 
-    assert_eq!(client.get_remaining_balance(), 0);
-    assert_eq!(client.get_program_info().payout_history.len(), 0);
+    assert_eq!(client.get_remaining_balance(&program_id), 0);
+    assert_eq!(client.get_payout_count(&program_id), 0);
     assert_eq!(token_client.balance(&client.address), 0);
 }
 
@@ -97,12 +154,12 @@ fn test_edge_zero_initial_state() {
 fn test_edge_max_safe_lock_and_payout() {
     let env = Env::default();
     let safe_max = i64::MAX as i128;
-    let (client, _admin, token_client, _token_admin) = setup_program(&env, safe_max);
+    let (client, _admin, token_client, _token_admin, program_id) = setup_program(&env, safe_max);
 
     let recipient = Address::generate(&env);
-    client.single_payout(&recipient, &safe_max);
+    client.single_payout(&program_id, &recipient, &safe_max);
 
-    assert_eq!(client.get_remaining_balance(), 0);
+    assert_eq!(client.get_remaining_balance(&program_id), 0);
     assert_eq!(token_client.balance(&recipient), safe_max);
     assert_eq!(token_client.balance(&client.address), 0);
 }
@@ -110,10 +167,10 @@ fn test_edge_max_safe_lock_and_payout() {
 #[test]
 fn test_single_payout_token_transfer_integration() {
     let env = Env::default();
-    let (client, _admin, token_client, _token_admin) = setup_program(&env, 100_000);
+    let (client, _admin, token_client, _token_admin, program_id) = setup_program(&env, 100_000);
 
     let recipient = Address::generate(&env);
-    let data = client.single_payout(&recipient, &30_000);
+    let data = client.single_payout(&program_id, &recipient, &30_000);
 
     assert_eq!(data.remaining_balance, 70_000);
     assert_eq!(token_client.balance(&recipient), 30_000);
@@ -123,7 +180,7 @@ fn test_single_payout_token_transfer_integration() {
 #[test]
 fn test_batch_payout_token_transfer_integration() {
     let env = Env::default();
-    let (client, _admin, token_client, _token_admin) = setup_program(&env, 150_000);
+    let (client, _admin, token_client, _token_admin, program_id) = setup_program(&env, 150_000);
 
     let r1 = Address::generate(&env);
     let r2 = Address::generate(&env);
@@ -132,9 +189,9 @@ fn test_batch_payout_token_transfer_integration() {
     let recipients = vec![&env, r1.clone(), r2.clone(), r3.clone()];
     let amounts = vec![&env, 10_000, 20_000, 30_000];
 
-    let data = client.batch_payout(&recipients, &amounts);
+    let data = client.batch_payout(&program_id, &recipients, &amounts);
     assert_eq!(data.remaining_balance, 90_000);
-    assert_eq!(data.payout_history.len(), 3);
+    assert_eq!(client.get_payout_count(&program_id), 3);
 
     assert_eq!(token_client.balance(&r1), 10_000);
     assert_eq!(token_client.balance(&r2), 20_000);
@@ -144,44 +201,46 @@ fn test_batch_payout_token_transfer_integration() {
 #[test]
 fn test_complete_lifecycle_integration() {
     let env = Env::default();
-    let (client, _admin, token_client, token_admin) = setup_program(&env, 0);
+    let (client, admin, token_client, token_admin, program_id) = setup_program(&env, 0);
 
-    token_admin.mint(&client.address, &300_000);
-    client.lock_program_funds(&300_000);
+    token_admin.mint(&admin, &300_000);
+    client.lock_program_funds(&program_id, &admin, &300_000);
 
     let r1 = Address::generate(&env);
     let r2 = Address::generate(&env);
     let r3 = Address::generate(&env);
 
-    client.single_payout(&r1, &50_000);
+    client.single_payout(&program_id, &r1, &50_000);
     let recipients = vec![&env, r2.clone(), r3.clone()];
     let amounts = vec![&env, 70_000, 30_000];
-    client.batch_payout(&recipients, &amounts);
+    client.batch_payout(&program_id, &recipients, &amounts);
 
-    let info = client.get_program_info();
+    let info = client.get_program_info(&program_id);
     assert_eq!(info.total_funds, 300_000);
     assert_eq!(info.remaining_balance, 150_000);
-    assert_eq!(info.payout_history.len(), 3);
+    assert_eq!(client.get_payout_count(&program_id), 3);
     assert_eq!(token_client.balance(&client.address), 150_000);
 }
 
 #[test]
 fn test_property_fuzz_balance_invariants() {
     let env = Env::default();
-    let (client, _admin, token_client, _token_admin) = setup_program(&env, 1_000_000);
+    let (client, _admin, token_client, _token_admin, program_id) = setup_program(&env, 1_000_000);
 
     let mut seed = 123_u64;
     let mut expected_remaining = 1_000_000_i128;
 
     for _ in 0..40 {
+        // Each payout is its own transaction on-chain, with its own budget
+        env.budget().reset_default();
         let amount = (next_seed(&mut seed) % 4_000 + 1) as i128;
         if amount > expected_remaining {
             continue;
         }
 
-        if next_seed(&mut seed) % 2 == 0 {
+        if next_seed(&mut seed).is_multiple_of(2) {
             let recipient = Address::generate(&env);
-            client.single_payout(&recipient, &amount);
+            client.single_payout(&program_id, &recipient, &amount);
         } else {
             let recipient1 = Address::generate(&env);
             let recipient2 = Address::generate(&env);
@@ -192,11 +251,14 @@ fn test_property_fuzz_balance_invariants() {
             }
             let recipients = vec![&env, recipient1, recipient2];
             let amounts = vec![&env, first, second];
-            client.batch_payout(&recipients, &amounts);
+            client.batch_payout(&program_id, &recipients, &amounts);
         }
 
         expected_remaining -= amount;
-        assert_eq!(client.get_remaining_balance(), expected_remaining);
+        assert_eq!(
+            client.get_remaining_balance(&program_id),
+            expected_remaining
+        );
         assert_eq!(token_client.balance(&client.address), expected_remaining);
 
         if expected_remaining == 0 {
@@ -208,15 +270,17 @@ fn test_property_fuzz_balance_invariants() {
 #[test]
 fn test_stress_high_load_many_payouts() {
     let env = Env::default();
-    let (client, _admin, token_client, _token_admin) = setup_program(&env, 1_000_000);
+    let (client, _admin, token_client, _token_admin, program_id) = setup_program(&env, 1_000_000);
 
     for _ in 0..100 {
+        // Each payout is its own transaction on-chain, with its own budget
+        env.budget().reset_default();
         let recipient = Address::generate(&env);
-        client.single_payout(&recipient, &3_000);
+        client.single_payout(&program_id, &recipient, &3_000);
     }
 
-    let info = client.get_program_info();
-    assert_eq!(info.payout_history.len(), 100);
+    let info = client.get_program_info(&program_id);
+    assert_eq!(client.get_payout_count(&program_id), 100);
     assert_eq!(info.remaining_balance, 700_000);
     assert_eq!(token_client.balance(&client.address), 700_000);
 }
@@ -224,18 +288,18 @@ fn test_stress_high_load_many_payouts() {
 #[test]
 fn test_gas_proxy_batch_vs_single_event_efficiency() {
     let env_single = Env::default();
-    let (single_client, _single_admin, _single_token, _single_token_admin) =
+    let (single_client, _single_admin, _single_token, _single_token_admin, single_program) =
         setup_program(&env_single, 200_000);
 
     let single_before = env_single.events().all().len();
     for _ in 0..10 {
         let recipient = Address::generate(&env_single);
-        single_client.single_payout(&recipient, &1_000);
+        single_client.single_payout(&single_program, &recipient, &1_000);
     }
     let single_events = env_single.events().all().len() - single_before;
 
     let env_batch = Env::default();
-    let (batch_client, _batch_admin, _batch_token, _batch_token_admin) =
+    let (batch_client, _batch_admin, _batch_token, _batch_token_admin, batch_program) =
         setup_program(&env_batch, 200_000);
 
     let mut recipients = vec![&env_batch];
@@ -246,7 +310,7 @@ fn test_gas_proxy_batch_vs_single_event_efficiency() {
     }
 
     let batch_before = env_batch.events().all().len();
-    batch_client.batch_payout(&recipients, &amounts);
+    batch_client.batch_payout(&batch_program, &recipients, &amounts);
     let batch_events = env_batch.events().all().len() - batch_before;
 
     assert!(batch_events <= single_events);
@@ -255,14 +319,14 @@ fn test_gas_proxy_batch_vs_single_event_efficiency() {
 #[test]
 fn test_events_emit_v2_version_tags_for_all_program_emitters() {
     let env = Env::default();
-    let (client, _admin, _token_client, _token_admin) = setup_program(&env, 100_000);
+    let (client, _admin, _token_client, _token_admin, program_id) = setup_program(&env, 100_000);
     let r1 = Address::generate(&env);
     let r2 = Address::generate(&env);
 
-    client.single_payout(&r1, &10_000);
+    client.single_payout(&program_id, &r1, &10_000);
     let recipients = vec![&env, r2];
     let amounts = vec![&env, 5_000];
-    client.batch_payout(&recipients, &amounts);
+    client.batch_payout(&program_id, &recipients, &amounts);
 
     let events = env.events().all();
     let mut program_events_checked = 0_u32;
@@ -281,17 +345,20 @@ fn test_events_emit_v2_version_tags_for_all_program_emitters() {
 #[test]
 fn test_release_schedule_exact_timestamp_boundary() {
     let env = Env::default();
-    let (client, _admin, token_client, _token_admin) = setup_program(&env, 100_000);
+    let (client, _admin, token_client, _token_admin, program_id) = setup_program(&env, 100_000);
     let recipient = Address::generate(&env);
 
     let now = env.ledger().timestamp();
-    let schedule = client.create_program_release_schedule(&recipient, &25_000, &(now + 100));
+    let schedule =
+        client.create_program_release_schedule(&program_id, &recipient, &25_000, &(now + 100));
 
     env.ledger().set_timestamp(now + 100);
-    let released = client.trigger_program_releases();
+    let released = client
+        .trigger_program_releases(&program_id, &ReleaseCursor::START, &50)
+        .released_count;
     assert_eq!(released, 1);
 
-    let schedules = client.get_program_release_schedules();
+    let schedules = client.get_program_release_schedules(&program_id);
     let updated = schedules.get(0).unwrap();
     assert_eq!(updated.schedule_id, schedule.schedule_id);
     assert!(updated.released);
@@ -301,32 +368,36 @@ fn test_release_schedule_exact_timestamp_boundary() {
 #[test]
 fn test_release_schedule_just_before_timestamp_rejected() {
     let env = Env::default();
-    let (client, _admin, token_client, _token_admin) = setup_program(&env, 100_000);
+    let (client, _admin, token_client, _token_admin, program_id) = setup_program(&env, 100_000);
     let recipient = Address::generate(&env);
 
     let now = env.ledger().timestamp();
-    client.create_program_release_schedule(&recipient, &20_000, &(now + 80));
+    client.create_program_release_schedule(&program_id, &recipient, &20_000, &(now + 80));
 
     env.ledger().set_timestamp(now + 79);
-    let released = client.trigger_program_releases();
+    let released = client
+        .trigger_program_releases(&program_id, &ReleaseCursor::START, &50)
+        .released_count;
     assert_eq!(released, 0);
     assert_eq!(token_client.balance(&recipient), 0);
 
-    let schedules = client.get_program_release_schedules();
+    let schedules = client.get_program_release_schedules(&program_id);
     assert!(!schedules.get(0).unwrap().released);
 }
 
 #[test]
 fn test_release_schedule_significantly_after_timestamp_releases() {
     let env = Env::default();
-    let (client, _admin, token_client, _token_admin) = setup_program(&env, 100_000);
+    let (client, _admin, token_client, _token_admin, program_id) = setup_program(&env, 100_000);
     let recipient = Address::generate(&env);
 
     let now = env.ledger().timestamp();
-    client.create_program_release_schedule(&recipient, &30_000, &(now + 60));
+    client.create_program_release_schedule(&program_id, &recipient, &30_000, &(now + 60));
 
     env.ledger().set_timestamp(now + 10_000);
-    let released = client.trigger_program_releases();
+    let released = client
+        .trigger_program_releases(&program_id, &ReleaseCursor::START, &50)
+        .released_count;
     assert_eq!(released, 1);
     assert_eq!(token_client.balance(&recipient), 30_000);
 }
@@ -334,29 +405,35 @@ fn test_release_schedule_significantly_after_timestamp_releases() {
 #[test]
 fn test_release_schedule_overlapping_schedules() {
     let env = Env::default();
-    let (client, _admin, token_client, _token_admin) = setup_program(&env, 200_000);
+    let (client, _admin, token_client, _token_admin, program_id) = setup_program(&env, 200_000);
     let recipient1 = Address::generate(&env);
     let recipient2 = Address::generate(&env);
     let recipient3 = Address::generate(&env);
 
     let now = env.ledger().timestamp();
-    client.create_program_release_schedule(&recipient1, &10_000, &(now + 50));
-    client.create_program_release_schedule(&recipient2, &15_000, &(now + 50)); // overlapping timestamp
-    client.create_program_release_schedule(&recipient3, &20_000, &(now + 120));
+    client.create_program_release_schedule(&program_id, &recipient1, &10_000, &(now + 50));
+    client.create_program_release_schedule(&program_id, &recipient2, &15_000, &(now + 50)); // overlapping timestamp
+    client.create_program_release_schedule(&program_id, &recipient3, &20_000, &(now + 120));
 
     env.ledger().set_timestamp(now + 50);
-    let released_at_overlap = client.trigger_program_releases();
+    let released_at_overlap = client
+        .trigger_program_releases(&program_id, &ReleaseCursor::START, &50)
+        .released_count;
     assert_eq!(released_at_overlap, 2);
     assert_eq!(token_client.balance(&recipient1), 10_000);
     assert_eq!(token_client.balance(&recipient2), 15_000);
     assert_eq!(token_client.balance(&recipient3), 0);
 
     env.ledger().set_timestamp(now + 120);
-    let released_later = client.trigger_program_releases();
+    let released_later = client
+        .trigger_program_releases(&program_id, &ReleaseCursor::START, &50)
+        .released_count;
     assert_eq!(released_later, 1);
     assert_eq!(token_client.balance(&recipient3), 20_000);
 
-    let history = client.get_program_release_history();
+    let history = client
+        .get_program_release_history(&program_id, &0, &50)
+        .records;
     assert_eq!(history.len(), 3);
 }
 
@@ -385,7 +462,7 @@ fn test_release_schedule_overlapping_schedules() {
 fn test_batch_payout_happy_path_multiple_recipients() {
     // Test the happy path: valid batch with multiple distinct recipients
     let env = Env::default();
-    let (client, _admin, token_client, _token_admin) = setup_program(&env, 6_000_000);
+    let (client, _admin, token_client, _token_admin, program_id) = setup_program(&env, 6_000_000);
 
     let r1 = Address::generate(&env);
     let r2 = Address::generate(&env);
@@ -394,24 +471,25 @@ fn test_batch_payout_happy_path_multiple_recipients() {
     let recipients = vec![&env, r1.clone(), r2.clone(), r3.clone()];
     let amounts = vec![&env, 1_000_000, 2_000_000, 3_000_000];
 
-    let data = client.batch_payout(&recipients, &amounts);
+    let data = client.batch_payout(&program_id, &recipients, &amounts);
 
     // Verify balance updated correctly (all-or-nothing)
     assert_eq!(data.remaining_balance, 0);
 
     // Verify payout history has all three records
-    assert_eq!(data.payout_history.len(), 3);
+    let history = client.get_payout_history(&program_id, &0, &10).records;
+    assert_eq!(history.len(), 3);
 
     // Verify each payout record
-    let payout1 = data.payout_history.get(0).unwrap();
+    let payout1 = history.get(0).unwrap();
     assert_eq!(payout1.recipient, r1);
     assert_eq!(payout1.amount, 1_000_000);
 
-    let payout2 = data.payout_history.get(1).unwrap();
+    let payout2 = history.get(1).unwrap();
     assert_eq!(payout2.recipient, r2);
     assert_eq!(payout2.amount, 2_000_000);
 
-    let payout3 = data.payout_history.get(2).unwrap();
+    let payout3 = history.get(2).unwrap();
     assert_eq!(payout3.recipient, r3);
     assert_eq!(payout3.amount, 3_000_000);
 
@@ -426,7 +504,7 @@ fn test_batch_payout_with_duplicate_recipient_addresses() {
     // Test batch containing duplicate recipient addresses
     // This validates that the contract handles repeated recipients correctly
     let env = Env::default();
-    let (client, _admin, token_client, _token_admin) = setup_program(&env, 4_500_000);
+    let (client, _admin, token_client, _token_admin, program_id) = setup_program(&env, 4_500_000);
 
     let r1 = Address::generate(&env);
     let r2 = Address::generate(&env);
@@ -435,19 +513,20 @@ fn test_batch_payout_with_duplicate_recipient_addresses() {
     let recipients = vec![&env, r1.clone(), r2.clone(), r1.clone()];
     let amounts = vec![&env, 1_000_000, 2_000_000, 1_500_000];
 
-    let data = client.batch_payout(&recipients, &amounts);
+    let data = client.batch_payout(&program_id, &recipients, &amounts);
 
     // Balance should be fully consumed
     assert_eq!(data.remaining_balance, 0);
 
     // Payout history should have all three records (duplicates are allowed)
-    assert_eq!(data.payout_history.len(), 3);
+    let history = client.get_payout_history(&program_id, &0, &10).records;
+    assert_eq!(history.len(), 3);
 
     // Count occurrences of r1 in history
     let mut r1_count = 0;
     let mut r1_total = 0i128;
-    for i in 0..data.payout_history.len() {
-        let record = data.payout_history.get(i).unwrap();
+    for i in 0..history.len() {
+        let record = history.get(i).unwrap();
         if record.recipient == r1 {
             r1_count += 1;
             r1_total += record.amount;
@@ -472,7 +551,8 @@ fn test_batch_payout_maximum_batch_size() {
     let amount_per_recipient = 100_000i128;
     let total_amount = (batch_size as i128) * amount_per_recipient;
 
-    let (client, _admin, _token_client, _token_admin) = setup_program(&env, total_amount);
+    let (client, _admin, _token_client, _token_admin, program_id) =
+        setup_program(&env, total_amount);
 
     let mut recipients = vec![&env];
     let mut amounts = vec![&env];
@@ -483,91 +563,99 @@ fn test_batch_payout_maximum_batch_size() {
     }
 
     // Execute large batch payout
-    let data = client.batch_payout(&recipients, &amounts);
+    let data = client.batch_payout(&program_id, &recipients, &amounts);
 
     // Balance should be fully consumed
     assert_eq!(data.remaining_balance, 0);
 
     // Payout history should have all records
-    assert_eq!(data.payout_history.len(), batch_size as u32);
+    let history = client
+        .get_payout_history(&program_id, &0, &(batch_size as u32))
+        .records;
+    assert_eq!(history.len(), batch_size as u32);
 
     // Verify total payout amount
     let mut total_paid = 0i128;
-    for i in 0..data.payout_history.len() {
-        let record = data.payout_history.get(i).unwrap();
+    for i in 0..history.len() {
+        let record = history.get(i).unwrap();
         total_paid += record.amount;
     }
     assert_eq!(total_paid, total_amount);
 }
 
 #[test]
-#[should_panic(expected = "Cannot process empty batch")]
 fn test_batch_payout_empty_batch_panic() {
     // Test that empty batch is rejected
     let env = Env::default();
-    let (client, _admin, _token_client, _token_admin) = setup_program(&env, 1_000_000);
+    let (client, _admin, _token_client, _token_admin, program_id) = setup_program(&env, 1_000_000);
 
     let recipients = vec![&env];
     let amounts = vec![&env];
 
-    // Should panic
-    client.batch_payout(&recipients, &amounts);
+    assert_eq!(
+        client.try_batch_payout(&program_id, &recipients, &amounts),
+        Err(Ok(Error::EmptyBatch))
+    );
 }
 
 #[test]
-#[should_panic(expected = "Recipients and amounts vectors must have the same length")]
 fn test_batch_payout_mismatched_arrays_panic() {
     // Test that mismatched recipient/amount arrays are rejected
     let env = Env::default();
-    let (client, _admin, _token_client, _token_admin) = setup_program(&env, 5_000_000);
+    let (client, _admin, _token_client, _token_admin, program_id) = setup_program(&env, 5_000_000);
 
     let recipients = vec![&env, Address::generate(&env), Address::generate(&env)];
     let amounts = vec![&env, 1_000_000]; // Only 1 amount for 2 recipients
 
-    // Should panic
-    client.batch_payout(&recipients, &amounts);
+    assert_eq!(
+        client.try_batch_payout(&program_id, &recipients, &amounts),
+        Err(Ok(Error::BatchSizeMismatch))
+    );
 }
 
 #[test]
-#[should_panic(expected = "All amounts must be greater than zero")]
 fn test_batch_payout_invalid_amount_zero_panic() {
     // Test that zero amounts are rejected
     let env = Env::default();
-    let (client, _admin, _token_client, _token_admin) = setup_program(&env, 5_000_000);
+    let (client, _admin, _token_client, _token_admin, program_id) = setup_program(&env, 5_000_000);
 
     let recipients = vec![&env, Address::generate(&env)];
     let amounts = vec![&env, 0i128]; // Zero amount - invalid
 
-    // Should panic
-    client.batch_payout(&recipients, &amounts);
+    assert_eq!(
+        client.try_batch_payout(&program_id, &recipients, &amounts),
+        Err(Ok(Error::InvalidAmount))
+    );
 }
 
 #[test]
-#[should_panic(expected = "All amounts must be greater than zero")]
 fn test_batch_payout_invalid_amount_negative_panic() {
     // Test that negative amounts are rejected
     let env = Env::default();
-    let (client, _admin, _token_client, _token_admin) = setup_program(&env, 5_000_000);
+    let (client, _admin, _token_client, _token_admin, program_id) = setup_program(&env, 5_000_000);
 
     let recipients = vec![&env, Address::generate(&env)];
     let amounts = vec![&env, -1_000_000]; // Negative amount - invalid
 
-    // Should panic
-    client.batch_payout(&recipients, &amounts);
+    assert_eq!(
+        client.try_batch_payout(&program_id, &recipients, &amounts),
+        Err(Ok(Error::InvalidAmount))
+    );
 }
 
 #[test]
-#[should_panic(expected = "Insufficient balance")]
 fn test_batch_payout_insufficient_balance_panic() {
     // Test that insufficient balance is rejected
     let env = Env::default();
-    let (client, _admin, _token_client, _token_admin) = setup_program(&env, 5_000_000);
+    let (client, _admin, _token_client, _token_admin, program_id) = setup_program(&env, 5_000_000);
 
     let recipients = vec![&env, Address::generate(&env)];
     let amounts = vec![&env, 10_000_000]; // More than available
 
-    // Should panic
-    client.batch_payout(&recipients, &amounts);
+    assert_eq!(
+        client.try_batch_payout(&program_id, &recipients, &amounts),
+        Err(Ok(Error::InsufficientBalance))
+    );
 }
 
 #[test]
@@ -575,7 +663,7 @@ fn test_batch_payout_partial_spend() {
     // Test batch payout that doesn't spend entire balance
     // This validates that partial payouts work correctly
     let env = Env::default();
-    let (client, _admin, _token_client, _token_admin) = setup_program(&env, 10_000_000);
+    let (client, _admin, _token_client, _token_admin, program_id) = setup_program(&env, 10_000_000);
 
     let r1 = Address::generate(&env);
     let r2 = Address::generate(&env);
@@ -583,13 +671,13 @@ fn test_batch_payout_partial_spend() {
     let recipients = vec![&env, r1, r2];
     let amounts = vec![&env, 3_000_000, 3_000_000];
 
-    let data = client.batch_payout(&recipients, &amounts);
+    let data = client.batch_payout(&program_id, &recipients, &amounts);
 
     // Remaining balance should be correct
     assert_eq!(data.remaining_balance, 4_000_000);
 
     // Payout history should have both records
-    assert_eq!(data.payout_history.len(), 2);
+    assert_eq!(client.get_payout_count(&program_id), 2);
 }
 
 #[test]
@@ -597,30 +685,30 @@ fn test_batch_payout_atomicity_all_or_nothing() {
     // Test that batch payout maintains atomicity (all-or-nothing semantics)
     // Verify that either all payouts succeed or the entire transaction fails
     let env = Env::default();
-    let (client, _admin, _token_client, _token_admin) = setup_program(&env, 3_000_000);
+    let (client, _admin, _token_client, _token_admin, program_id) = setup_program(&env, 3_000_000);
 
     let r1 = Address::generate(&env);
     let r2 = Address::generate(&env);
 
     // Get program state before payout
-    let program_data_before = client.get_program_info();
-    let history_len_before = program_data_before.payout_history.len();
+    let program_data_before = client.get_program_info(&program_id);
+    let history_len_before = client.get_payout_count(&program_id);
     let balance_before = program_data_before.remaining_balance;
 
     // Execute successful batch payout
     let recipients = vec![&env, r1, r2];
     let amounts = vec![&env, 1_000_000, 2_000_000];
 
-    let data = client.batch_payout(&recipients, &amounts);
+    let data = client.batch_payout(&program_id, &recipients, &amounts);
 
     // All records must be written
-    assert_eq!(data.payout_history.len(), history_len_before + 2);
+    assert_eq!(client.get_payout_count(&program_id), history_len_before + 2);
 
     // Balance must be fully updated
     assert_eq!(data.remaining_balance, balance_before - 3_000_000);
 
     // All conditions should be satisfied together (atomicity)
-    assert_eq!(data.payout_history.len(), 2);
+    assert_eq!(client.get_payout_count(&program_id), 2);
     assert_eq!(data.remaining_balance, 0);
 }
 
@@ -629,16 +717,16 @@ fn test_batch_payout_sequential_batches() {
     // Test multiple sequential batch payouts to same program
     // Validates that history accumulates correctly
     let env = Env::default();
-    let (client, _admin, _token_client, _token_admin) = setup_program(&env, 9_000_000);
+    let (client, _admin, _token_client, _token_admin, program_id) = setup_program(&env, 9_000_000);
 
     // First batch
     let r1 = Address::generate(&env);
     let recipients1 = vec![&env, r1];
     let amounts1 = vec![&env, 3_000_000];
-    let data1 = client.batch_payout(&recipients1, &amounts1);
+    let data1 = client.batch_payout(&program_id, &recipients1, &amounts1);
 
     // Verify after first batch
-    assert_eq!(data1.payout_history.len(), 1);
+    assert_eq!(client.get_payout_count(&program_id), 1);
     assert_eq!(data1.remaining_balance, 6_000_000);
 
     // Second batch
@@ -646,19 +734,2139 @@ fn test_batch_payout_sequential_batches() {
     let r3 = Address::generate(&env);
     let recipients2 = vec![&env, r2, r3];
     let amounts2 = vec![&env, 2_000_000, 4_000_000];
-    let data2 = client.batch_payout(&recipients2, &amounts2);
+    let data2 = client.batch_payout(&program_id, &recipients2, &amounts2);
 
     // Verify after second batch
-    assert_eq!(data2.payout_history.len(), 3);
+    assert_eq!(client.get_payout_count(&program_id), 3);
     assert_eq!(data2.remaining_balance, 0);
 
     // Verify history order
-    let record1 = data2.payout_history.get(0).unwrap();
+    let history = client.get_payout_history(&program_id, &0, &10).records;
+    let record1 = history.get(0).unwrap();
     assert_eq!(record1.amount, 3_000_000);
 
-    let record2 = data2.payout_history.get(1).unwrap();
+    let record2 = history.get(1).unwrap();
     assert_eq!(record2.amount, 2_000_000);
 
-    let record3 = data2.payout_history.get(2).unwrap();
+    let record3 = history.get(2).unwrap();
     assert_eq!(record3.amount, 4_000_000);
 }
+
+// ============================================================================
+// Multi-tenant Tests
+// ============================================================================
+
+fn setup_contract(
+    env: &Env,
+) -> (
+    ProgramEscrowContractClient<'static>,
+    token::Client<'static>,
+    token::StellarAssetClient<'static>,
+) {
+    env.mock_all_auths();
+
+    let contract_id = env.register_contract(None, ProgramEscrowContract);
+    let client = ProgramEscrowContractClient::new(env, &contract_id);
+
+    let token_admin = Address::generate(env);
+    let token_id = env
+        .register_stellar_asset_contract_v2(token_admin)
+        .address();
+    let token_client = token::Client::new(env, &token_id);
+    let token_admin_client = token::StellarAssetClient::new(env, &token_id);
+
+    (client, token_client, token_admin_client)
+}
+
+#[test]
+fn test_init_program_requires_authorization() {
+    let env = Env::default();
+    let contract_id = env.register_contract(None, ProgramEscrowContract);
+    let client = ProgramEscrowContractClient::new(&env, &contract_id);
+    let backend = Address::generate(&env);
+    let token = Address::generate(&env);
+    let program_id = String::from_str(&env, "squatted");
+
+    // Nobody signed: the id cannot be claimed
+    assert!(client
+        .try_init_program(&program_id, &backend, &token)
+        .is_err());
+    assert!(!client.program_exists(&program_id));
+
+    // With a contract admin set, the payout key alone is not enough
+    let admin = Address::generate(&env);
+    client.initialize_contract(&admin);
+    env.mock_auths(&[MockAuth {
+        address: &backend,
+        invoke: &MockAuthInvoke {
+            contract: &contract_id,
+            fn_name: "init_program",
+            args: (program_id.clone(), backend.clone(), token.clone()).into_val(&env),
+            sub_invokes: &[],
+        },
+    }]);
+    assert!(client
+        .try_init_program(&program_id, &backend, &token)
+        .is_err());
+
+    env.mock_all_auths();
+    client.init_program(&program_id, &backend, &token);
+    assert_eq!(env.auths().len(), 2);
+    assert!(client.program_exists(&program_id));
+}
+
+#[test]
+fn test_register_multiple_programs() {
+    let env = Env::default();
+    let (client, token_client, _token_admin) = setup_contract(&env);
+    assert_eq!(client.get_program_count(), 0);
+
+    let backend1 = Address::generate(&env);
+    let backend2 = Address::generate(&env);
+    let prog1 = String::from_str(&env, "ETHGlobal2024");
+    let prog2 = String::from_str(&env, "Stellar2024");
+
+    client.init_program(&prog1, &backend1, &token_client.address);
+    client.init_program(&prog2, &backend2, &token_client.address);
+
+    assert!(client.program_exists(&prog1));
+    assert!(client.program_exists(&prog2));
+    assert!(!client.program_exists(&String::from_str(&env, "Other")));
+    assert_eq!(client.get_program_count(), 2);
+    assert_eq!(
+        client.list_programs(),
+        vec![&env, prog1.clone(), prog2.clone()]
+    );
+
+    assert_eq!(
+        client.get_program_info(&prog1).authorized_payout_key,
+        backend1
+    );
+    assert_eq!(
+        client.get_program_info(&prog2).authorized_payout_key,
+        backend2
+    );
+}
+
+#[test]
+fn test_duplicate_program_registration() {
+    let env = Env::default();
+    let (client, token_client, _token_admin) = setup_contract(&env);
+    let backend = Address::generate(&env);
+    let prog_id = String::from_str(&env, "Hackathon2024");
+
+    client.init_program(&prog_id, &backend, &token_client.address);
+    assert_eq!(
+        client.try_init_program(&prog_id, &backend, &token_client.address),
+        Err(Ok(Error::ProgramExists))
+    );
+}
+
+#[test]
+fn test_empty_program_id() {
+    let env = Env::default();
+    let (client, token_client, _token_admin) = setup_contract(&env);
+    let backend = Address::generate(&env);
+
+    assert_eq!(
+        client.try_init_program(&String::from_str(&env, ""), &backend, &token_client.address),
+        Err(Ok(Error::InvalidProgramId))
+    );
+}
+
+#[test]
+fn test_get_nonexistent_program() {
+    let env = Env::default();
+    let (client, _token_client, _token_admin) = setup_contract(&env);
+
+    assert_eq!(
+        client.try_get_program_info(&String::from_str(&env, "DoesNotExist")),
+        Err(Ok(Error::ProgramNotFound))
+    );
+}
+
+#[test]
+fn test_multi_tenant_funds_and_payout_isolation() {
+    let env = Env::default();
+    let (client, token_client, token_admin) = setup_contract(&env);
+
+    let backend1 = Address::generate(&env);
+    let backend2 = Address::generate(&env);
+    let prog1 = String::from_str(&env, "Program1");
+    let prog2 = String::from_str(&env, "Program2");
+    client.init_program(&prog1, &backend1, &token_client.address);
+    client.init_program(&prog2, &backend2, &token_client.address);
+
+    token_admin.mint(&backend1, &10_000);
+    token_admin.mint(&backend2, &20_000);
+    client.lock_program_funds(&prog1, &backend1, &10_000);
+    client.lock_program_funds(&prog2, &backend2, &20_000);
+
+    let r1 = Address::generate(&env);
+    let r2 = Address::generate(&env);
+    client.single_payout(&prog1, &r1, &2_000);
+    client.batch_payout(&prog2, &vec![&env, r2.clone()], &vec![&env, 5_000]);
+
+    let info1 = client.get_program_info(&prog1);
+    let info2 = client.get_program_info(&prog2);
+    assert_eq!(info1.total_funds, 10_000);
+    assert_eq!(info1.remaining_balance, 8_000);
+    let history1 = client.get_payout_history(&prog1, &0, &10).records;
+    assert_eq!(history1.len(), 1);
+    assert_eq!(history1.get(0).unwrap().recipient, r1);
+    assert_eq!(info2.total_funds, 20_000);
+    assert_eq!(info2.remaining_balance, 15_000);
+    let history2 = client.get_payout_history(&prog2, &0, &10).records;
+    assert_eq!(history2.len(), 1);
+    assert_eq!(history2.get(0).unwrap().recipient, r2);
+
+    assert_eq!(client.get_program_deposits(&prog1).len(), 1);
+    assert_eq!(
+        client.get_program_deposits(&prog2).get(0).unwrap().funder,
+        backend2
+    );
+    assert_eq!(token_client.balance(&client.address), 23_000);
+}
+
+#[test]
+fn test_multi_tenant_payout_cannot_spend_other_program_funds() {
+    let env = Env::default();
+    let (client, token_client, token_admin) = setup_contract(&env);
+
+    let backend = Address::generate(&env);
+    let prog1 = String::from_str(&env, "Program1");
+    let prog2 = String::from_str(&env, "Program2");
+    client.init_program(&prog1, &backend, &token_client.address);
+    client.init_program(&prog2, &backend, &token_client.address);
+
+    token_admin.mint(&backend, &10_000);
+    client.lock_program_funds(&prog1, &backend, &10_000);
+
+    // The contract holds 10_000, but none of it belongs to prog2
+    assert_eq!(
+        client.try_single_payout(&prog2, &Address::generate(&env), &1_000),
+        Err(Ok(Error::InsufficientBalance))
+    );
+}
+
+#[test]
+fn test_multi_tenant_schedule_and_history_isolation() {
+    let env = Env::default();
+    let (client, token_client, token_admin) = setup_contract(&env);
+
+    let backend1 = Address::generate(&env);
+    let backend2 = Address::generate(&env);
+    let prog1 = String::from_str(&env, "Program1");
+    let prog2 = String::from_str(&env, "Program2");
+    client.init_program(&prog1, &backend1, &token_client.address);
+    client.init_program(&prog2, &backend2, &token_client.address);
+
+    token_admin.mint(&backend1, &10_000);
+    token_admin.mint(&backend2, &20_000);
+    client.lock_program_funds(&prog1, &backend1, &10_000);
+    client.lock_program_funds(&prog2, &backend2, &20_000);
+
+    let winner1 = Address::generate(&env);
+    let winner2 = Address::generate(&env);
+    let winner3 = Address::generate(&env);
+    let now = env.ledger().timestamp();
+
+    let s1 = client.create_program_release_schedule(&prog1, &winner1, &4_000, &(now + 100));
+    let s2 = client.create_program_release_schedule(&prog2, &winner2, &8_000, &(now + 100));
+    client.create_program_release_schedule(&prog2, &winner3, &1_000, &(now + 500));
+
+    // Schedule ids are allocated per program
+    assert_eq!(s1.schedule_id, 1);
+    assert_eq!(s2.schedule_id, 1);
+    assert_eq!(client.get_program_release_schedules(&prog1).len(), 1);
+    assert_eq!(client.get_program_release_schedules(&prog2).len(), 2);
+
+    env.ledger().set_timestamp(now + 100);
+    assert_eq!(
+        client
+            .trigger_program_releases(&prog1, &ReleaseCursor::START, &50)
+            .released_count,
+        1
+    );
+
+    let history1 = client.get_program_release_history(&prog1, &0, &50).records;
+    assert_eq!(history1.len(), 1);
+    assert_eq!(history1.get(0).unwrap().recipient, winner1);
+    assert_eq!(client.get_release_history_count(&prog2), 0);
+    assert_eq!(client.get_due_schedules(&prog2).len(), 1);
+    assert_eq!(client.get_remaining_balance(&prog2), 20_000);
+    assert_eq!(token_client.balance(&winner1), 4_000);
+}
+
+// ============================================================================
+// Payout Key Rotation Tests
+// ============================================================================
+
+fn setup_program_with_admin(
+    env: &Env,
+    initial_amount: i128,
+) -> (
+    ProgramEscrowContractClient<'static>,
+    Address,
+    token::Client<'static>,
+    String,
+) {
+    let (client, admin, token_client, _token_admin, program_id) =
+        setup_program(env, initial_amount);
+    let contract_admin = Address::generate(env);
+    client.initialize_contract(&contract_admin);
+    (client, admin, token_client, program_id)
+}
+
+#[test]
+fn test_rotate_payout_key_immediately() {
+    let env = Env::default();
+    let (client, old_key, token_client, program_id) = setup_program_with_admin(&env, 10_000);
+    let new_key = Address::generate(&env);
+
+    client.rotate_payout_key(&program_id, &new_key, &0);
+
+    let info = client.get_program_info(&program_id);
+    assert_eq!(info.authorized_payout_key, new_key);
+    assert!(!info.payout_key_revoked);
+    assert_eq!(client.get_programs_by_payout_key(&old_key).len(), 0);
+    assert_eq!(
+        client.get_programs_by_payout_key(&new_key),
+        vec![&env, program_id.clone()]
+    );
+
+    let recipient = Address::generate(&env);
+    client.single_payout(&program_id, &recipient, &1_000);
+    assert_eq!(env.auths()[0].0, new_key);
+    assert_eq!(token_client.balance(&recipient), 1_000);
+}
+
+#[test]
+fn test_delayed_key_rotation() {
+    let env = Env::default();
+    let (client, old_key, _token_client, program_id) = setup_program_with_admin(&env, 10_000);
+    let new_key = Address::generate(&env);
+    let now = env.ledger().timestamp();
+
+    client.rotate_payout_key(&program_id, &new_key, &3_600);
+
+    let pending = client.get_pending_key_rotation(&program_id).unwrap();
+    assert_eq!(pending.new_key, new_key);
+    assert_eq!(pending.effective_at, now + 3_600);
+
+    // Old key stays in charge until the delay elapses
+    assert_eq!(
+        client.try_apply_payout_key_rotation(&program_id),
+        Err(Ok(Error::KeyRotationNotEffective))
+    );
+    assert_eq!(
+        client.get_program_info(&program_id).authorized_payout_key,
+        old_key
+    );
+
+    env.ledger().set_timestamp(now + 3_600);
+    client.apply_payout_key_rotation(&program_id);
+
+    assert_eq!(
+        client.get_program_info(&program_id).authorized_payout_key,
+        new_key
+    );
+    assert_eq!(client.get_pending_key_rotation(&program_id), None);
+    assert_eq!(client.get_programs_by_payout_key(&old_key).len(), 0);
+}
+
+#[test]
+fn test_cancel_pending_key_rotation() {
+    let env = Env::default();
+    let (client, old_key, _token_client, program_id) = setup_program_with_admin(&env, 0);
+    let new_key = Address::generate(&env);
+
+    client.rotate_payout_key(&program_id, &new_key, &100);
+    client.cancel_payout_key_rotation(&program_id);
+
+    env.ledger().set_timestamp(env.ledger().timestamp() + 100);
+    assert_eq!(
+        client.try_apply_payout_key_rotation(&program_id),
+        Err(Ok(Error::NoPendingKeyRotation))
+    );
+    assert_eq!(
+        client.get_program_info(&program_id).authorized_payout_key,
+        old_key
+    );
+}
+
+#[test]
+fn test_revoke_freezes_payouts_until_new_key_installed() {
+    let env = Env::default();
+    let (client, old_key, token_client, program_id) = setup_program_with_admin(&env, 10_000);
+    let recipient = Address::generate(&env);
+
+    client.revoke_payout_key(&program_id);
+    assert!(client.get_program_info(&program_id).payout_key_revoked);
+    assert_eq!(client.get_programs_by_payout_key(&old_key).len(), 0);
+
+    assert_eq!(
+        client.try_single_payout(&program_id, &recipient, &1_000),
+        Err(Ok(Error::PayoutKeyRevoked))
+    );
+    assert_eq!(
+        client.try_batch_payout(
+            &program_id,
+            &vec![&env, recipient.clone()],
+            &vec![&env, 1_000]
+        ),
+        Err(Ok(Error::PayoutKeyRevoked))
+    );
+    assert_eq!(
+        client.try_trigger_program_releases(&program_id, &ReleaseCursor::START, &50),
+        Err(Ok(Error::PayoutKeyRevoked))
+    );
+
+    let new_key = Address::generate(&env);
+    client.rotate_payout_key(&program_id, &new_key, &0);
+    client.single_payout(&program_id, &recipient, &1_000);
+    assert_eq!(token_client.balance(&recipient), 1_000);
+}
+
+#[test]
+fn test_key_rotation_events() {
+    let env = Env::default();
+    let (client, _old_key, _token_client, program_id) = setup_program_with_admin(&env, 0);
+    let new_key = Address::generate(&env);
+
+    client.revoke_payout_key(&program_id);
+    client.rotate_payout_key(&program_id, &new_key, &0);
+
+    let mut revoked = 0;
+    let mut rotated = 0;
+    for (contract, topics, data) in env.events().all().iter() {
+        if contract != client.address {
+            continue;
+        }
+        let topic = Symbol::try_from_val(&env, &topics.get(0).unwrap()).unwrap();
+        if topic == symbol_short!("KeyRevoke") {
+            revoked += 1;
+        } else if topic == symbol_short!("KeyRot") {
+            rotated += 1;
+            assert_event_data_has_v2_tag(&env, &data);
+        }
+    }
+    assert_eq!(revoked, 1);
+    assert_eq!(rotated, 1);
+}
+
+#[test]
+fn test_rotate_payout_key_requires_contract_admin() {
+    let env = Env::default();
+    let (client, _admin, _token, _token_admin, program_id) = setup_program(&env, 0);
+
+    assert_eq!(
+        client.try_rotate_payout_key(&program_id, &Address::generate(&env), &0),
+        Err(Ok(Error::NotInitialized))
+    );
+}
+
+// ============================================================================
+// Program Lifecycle Tests
+// ============================================================================
+
+#[test]
+fn test_close_program_refunds_funders_pro_rata() {
+    let env = Env::default();
+    let (client, admin, token_client, token_admin, program_id) = setup_program(&env, 6_000);
+    let sponsor = Address::generate(&env);
+    token_admin.mint(&sponsor, &3_000);
+    client.lock_program_funds(&program_id, &sponsor, &3_000);
+
+    // 9_000 locked, 1_000 paid out, 8_000 left: 2/3 to admin, 1/3 to sponsor
+    let winner = Address::generate(&env);
+    client.single_payout(&program_id, &winner, &1_000);
+
+    let data = client.close_program(&program_id);
+    assert_eq!(env.auths()[0].0, admin);
+
+    assert_eq!(data.status, ProgramStatus::Settled);
+    assert_eq!(data.remaining_balance, 0);
+    assert_eq!(data.total_refunded, 8_000);
+    // 5_333 plus 1 unit of rounding dust to the first funder
+    assert_eq!(token_client.balance(&admin), 5_334);
+    assert_eq!(token_client.balance(&sponsor), 2_666);
+    assert_eq!(token_client.balance(&client.address), 0);
+
+    let stats = client.get_program_aggregate_stats(&program_id);
+    assert_eq!(stats.total_paid_out, 1_000);
+}
+
+#[test]
+fn test_close_program_cancels_unreleased_schedules() {
+    let env = Env::default();
+    let (client, _admin, token_client, _token_admin, program_id) = setup_program(&env, 10_000);
+    let r1 = Address::generate(&env);
+    let r2 = Address::generate(&env);
+    let now = env.ledger().timestamp();
+
+    client.create_program_release_schedule(&program_id, &r1, &2_000, &now);
+    client.create_program_release_schedule(&program_id, &r2, &3_000, &(now + 1_000));
+    client.trigger_program_releases(&program_id, &ReleaseCursor::START, &50);
+
+    client.close_program(&program_id);
+
+    // Closing cancels the schedule the same way cancelling it directly does
+    let mut cancel_events = 0;
+    for (_contract, topics, data) in env.events().all().iter() {
+        let topic = Symbol::try_from_val(&env, &topics.get(0).unwrap()).unwrap();
+        if topic == symbol_short!("SchCncl") {
+            cancel_events += 1;
+            assert_event_data_has_v2_tag(&env, &data);
+        }
+    }
+    assert_eq!(cancel_events, 1);
+    let history = client
+        .get_program_release_history(&program_id, &0, &50)
+        .records;
+    assert_eq!(history.len(), 2);
+    let entry = history.get(1).unwrap();
+    assert_eq!(entry.action, ReleaseAction::Cancelled);
+    assert_eq!(entry.recipient, r2);
+    assert_eq!(entry.amount, 3_000);
+
+    let schedules = client.get_program_release_schedules(&program_id);
+    assert!(schedules.get(0).unwrap().released);
+    assert!(schedules.get(1).unwrap().cancelled);
+    assert_eq!(client.get_pending_schedules(&program_id).len(), 0);
+    assert_eq!(client.get_total_scheduled_amount(&program_id), 0);
+    assert_eq!(token_client.balance(&r2), 0);
+    assert_eq!(token_client.balance(&client.address), 0);
+}
+
+#[test]
+fn test_closed_program_rejects_locks_and_payouts() {
+    let env = Env::default();
+    let (client, admin, _token_client, token_admin, program_id) = setup_program(&env, 10_000);
+    client.close_program(&program_id);
+
+    token_admin.mint(&admin, &1_000);
+    let recipient = Address::generate(&env);
+    assert_eq!(
+        client.try_lock_program_funds(&program_id, &admin, &1_000),
+        Err(Ok(Error::ProgramNotActive))
+    );
+    assert_eq!(
+        client.try_single_payout(&program_id, &recipient, &1),
+        Err(Ok(Error::ProgramNotActive))
+    );
+    assert_eq!(
+        client.try_batch_payout(&program_id, &vec![&env, recipient.clone()], &vec![&env, 1]),
+        Err(Ok(Error::ProgramNotActive))
+    );
+    assert_eq!(
+        client.try_create_program_release_schedule(&program_id, &recipient, &1, &0),
+        Err(Ok(Error::ProgramNotActive))
+    );
+    assert_eq!(
+        client.try_close_program(&program_id),
+        Err(Ok(Error::ProgramNotActive))
+    );
+}
+
+#[test]
+fn test_anyone_can_close_after_end_timestamp() {
+    let env = Env::default();
+    let (client, admin, token_client, _token_admin, program_id) = setup_program(&env, 10_000);
+    let now = env.ledger().timestamp();
+
+    client.set_program_end_time(&program_id, &(now + 500));
+    assert_eq!(
+        client.get_program_info(&program_id).end_timestamp,
+        Some(now + 500)
+    );
+
+    env.ledger().set_timestamp(now + 500);
+    client.close_program(&program_id);
+    // No signature was required once the program had ended
+    assert_eq!(env.auths().len(), 0);
+    assert_eq!(token_client.balance(&admin), 10_000);
+}
+
+#[test]
+fn test_revoked_key_cannot_close_or_set_end_time() {
+    let env = Env::default();
+    let (client, admin, token_client, program_id) = setup_program_with_admin(&env, 10_000);
+    let now = env.ledger().timestamp();
+
+    client.set_program_end_time(&program_id, &(now + 500));
+    client.revoke_payout_key(&program_id);
+
+    assert_eq!(
+        client.try_set_program_end_time(&program_id, &(now + 100)),
+        Err(Ok(Error::PayoutKeyRevoked))
+    );
+    assert_eq!(
+        client.try_close_program(&program_id),
+        Err(Ok(Error::PayoutKeyRevoked))
+    );
+
+    // Once ended the close is permissionless, so a revoked key cannot trap funds
+    env.ledger().set_timestamp(now + 500);
+    client.close_program(&program_id);
+    assert_eq!(token_client.balance(&admin), 10_000);
+}
+
+#[test]
+fn test_close_program_without_funds_settles() {
+    let env = Env::default();
+    let (client, _admin, _token_client, _token_admin, program_id) = setup_program(&env, 0);
+
+    let data = client.close_program(&program_id);
+    assert_eq!(data.status, ProgramStatus::Settled);
+    assert_eq!(data.total_refunded, 0);
+}
+
+// ============================================================================
+// Claim Tests
+// ============================================================================
+
+#[test]
+fn test_authorize_and_claim_payout() {
+    let env = Env::default();
+    let (client, _admin, token_client, program_id) = setup_program_with_admin(&env, 10_000);
+    let winner = Address::generate(&env);
+    client.set_claim_window(&500);
+
+    let claim_id = client.authorize_claim(&program_id, &winner, &4_000);
+    assert_eq!(claim_id, 1);
+
+    // The prize is set aside but not yet transferred
+    let info = client.get_program_info(&program_id);
+    assert_eq!(info.remaining_balance, 10_000);
+    assert_eq!(info.pending_claims_total, 4_000);
+    assert_eq!(client.get_available_balance(&program_id), 6_000);
+    assert_eq!(token_client.balance(&winner), 0);
+
+    let claim = client.get_pending_claim(&program_id, &claim_id);
+    assert_eq!(claim.expires_at, env.ledger().timestamp() + 500);
+
+    client.claim(&program_id, &claim_id);
+    assert_eq!(env.auths()[0].0, winner);
+    assert_eq!(token_client.balance(&winner), 4_000);
+
+    let info = client.get_program_info(&program_id);
+    assert_eq!(info.remaining_balance, 6_000);
+    assert_eq!(info.pending_claims_total, 0);
+    assert_eq!(client.get_payout_count(&program_id), 1);
+    assert!(client.get_pending_claim(&program_id, &claim_id).claimed);
+    assert_eq!(
+        client.try_claim(&program_id, &claim_id),
+        Err(Ok(Error::ClaimAlreadyExecuted))
+    );
+}
+
+#[test]
+fn test_authorize_claim_cannot_exceed_balance() {
+    let env = Env::default();
+    let (client, _admin, _token_client, program_id) = setup_program_with_admin(&env, 10_000);
+    let winner = Address::generate(&env);
+
+    client.authorize_claim(&program_id, &winner, &8_000);
+    assert_eq!(
+        client.try_authorize_claim(&program_id, &winner, &3_000),
+        Err(Ok(Error::InsufficientBalance))
+    );
+    assert_eq!(
+        client.try_single_payout(&program_id, &winner, &3_000),
+        Err(Ok(Error::InsufficientBalance))
+    );
+}
+
+#[test]
+fn test_expired_claim_returns_to_program_balance() {
+    let env = Env::default();
+    let (client, _admin, token_client, program_id) = setup_program_with_admin(&env, 10_000);
+    let winner = Address::generate(&env);
+    client.set_claim_window(&100);
+
+    let claim_id = client.authorize_claim(&program_id, &winner, &4_000);
+    assert_eq!(
+        client.try_expire_claim(&program_id, &claim_id),
+        Err(Ok(Error::ClaimNotExpired))
+    );
+
+    env.ledger().set_timestamp(env.ledger().timestamp() + 101);
+    assert_eq!(
+        client.try_claim(&program_id, &claim_id),
+        Err(Ok(Error::ClaimExpired))
+    );
+
+    client.expire_claim(&program_id, &claim_id);
+    let info = client.get_program_info(&program_id);
+    assert_eq!(info.remaining_balance, 10_000);
+    assert_eq!(info.pending_claims_total, 0);
+    assert_eq!(token_client.balance(&winner), 0);
+    assert_eq!(
+        client.try_get_pending_claim(&program_id, &claim_id),
+        Err(Ok(Error::ClaimNotFound))
+    );
+}
+
+#[test]
+fn test_cancel_pending_claim_by_payout_key() {
+    let env = Env::default();
+    let (client, admin, _token_client, program_id) = setup_program_with_admin(&env, 10_000);
+    let winner = Address::generate(&env);
+
+    let claim_id = client.authorize_claim(&program_id, &winner, &4_000);
+    client.cancel_pending_claim(&program_id, &claim_id);
+    assert_eq!(env.auths()[0].0, admin);
+
+    assert_eq!(client.get_remaining_balance(&program_id), 10_000);
+    assert_eq!(
+        client.try_claim(&program_id, &claim_id),
+        Err(Ok(Error::ClaimNotFound))
+    );
+}
+
+#[test]
+fn test_revoked_key_cannot_cancel_pending_claim() {
+    let env = Env::default();
+    let (client, _admin, token_client, program_id) = setup_program_with_admin(&env, 10_000);
+    let winner = Address::generate(&env);
+
+    let claim_id = client.authorize_claim(&program_id, &winner, &4_000);
+    client.revoke_payout_key(&program_id);
+
+    assert_eq!(
+        client.try_cancel_pending_claim(&program_id, &claim_id),
+        Err(Ok(Error::PayoutKeyRevoked))
+    );
+    // The winner can still collect
+    client.claim(&program_id, &claim_id);
+    assert_eq!(token_client.balance(&winner), 4_000);
+}
+
+#[test]
+fn test_close_program_keeps_claims_until_resolved() {
+    let env = Env::default();
+    let (client, admin, token_client, program_id) = setup_program_with_admin(&env, 10_000);
+    let w1 = Address::generate(&env);
+    let w2 = Address::generate(&env);
+    client.set_claim_window(&100);
+
+    let c1 = client.authorize_claim(&program_id, &w1, &3_000);
+    let c2 = client.authorize_claim(&program_id, &w2, &2_000);
+
+    let data = client.close_program(&program_id);
+    assert_eq!(data.status, ProgramStatus::Closed);
+    assert_eq!(token_client.balance(&admin), 5_000);
+
+    // Winners can still collect after close
+    client.claim(&program_id, &c1);
+    assert_eq!(token_client.balance(&w1), 3_000);
+    assert_eq!(
+        client.get_program_info(&program_id).status,
+        ProgramStatus::Closed
+    );
+
+    // The expired prize goes back to the funder and the program settles
+    env.ledger().set_timestamp(env.ledger().timestamp() + 101);
+    client.expire_claim(&program_id, &c2);
+    let info = client.get_program_info(&program_id);
+    assert_eq!(info.status, ProgramStatus::Settled);
+    assert_eq!(info.total_refunded, 7_000);
+    assert_eq!(token_client.balance(&admin), 7_000);
+    assert_eq!(token_client.balance(&client.address), 0);
+}
+
+// ============================================================================
+// Multisig Tests
+// ============================================================================
+
+fn setup_multisig(
+    env: &Env,
+    client: &ProgramEscrowContractClient<'static>,
+    program_id: &String,
+) -> (Address, Address, Address) {
+    let s1 = Address::generate(env);
+    let s2 = Address::generate(env);
+    let s3 = Address::generate(env);
+    client.update_multisig_config(
+        program_id,
+        &1_000,
+        &vec![env, s1.clone(), s2.clone(), s3.clone()],
+        &2,
+    );
+    (s1, s2, s3)
+}
+
+#[test]
+fn test_multisig_defaults_to_disabled() {
+    let env = Env::default();
+    let (client, _admin, token_client, program_id) = setup_program_with_admin(&env, 10_000);
+    let winner = Address::generate(&env);
+
+    let config = client.get_multisig_config(&program_id);
+    assert_eq!(config.required_signatures, 0);
+    assert_eq!(config.threshold_amount, i128::MAX);
+
+    client.single_payout(&program_id, &winner, &5_000);
+    assert_eq!(token_client.balance(&winner), 5_000);
+}
+
+#[test]
+fn test_multisig_config_rejects_impossible_threshold() {
+    let env = Env::default();
+    let (client, _admin, _token_client, program_id) = setup_program_with_admin(&env, 10_000);
+    let signer = Address::generate(&env);
+
+    assert_eq!(
+        client.try_update_multisig_config(&program_id, &1_000, &vec![&env, signer], &2),
+        Err(Ok(Error::InvalidMultisigConfig))
+    );
+}
+
+#[test]
+fn test_payout_below_threshold_needs_no_approval() {
+    let env = Env::default();
+    let (client, _admin, token_client, program_id) = setup_program_with_admin(&env, 10_000);
+    setup_multisig(&env, &client, &program_id);
+    let winner = Address::generate(&env);
+
+    client.single_payout(&program_id, &winner, &1_000);
+    assert_eq!(token_client.balance(&winner), 1_000);
+}
+
+#[test]
+fn test_large_payout_requires_approvals() {
+    let env = Env::default();
+    let (client, _admin, token_client, program_id) = setup_program_with_admin(&env, 10_000);
+    let (s1, s2, _s3) = setup_multisig(&env, &client, &program_id);
+    let winner = Address::generate(&env);
+
+    assert_eq!(
+        client.try_single_payout(&program_id, &winner, &5_000),
+        Err(Ok(Error::InsufficientApprovals))
+    );
+
+    client.approve_payout(&program_id, &winner, &5_000, &s1);
+    assert_eq!(env.auths()[0].0, s1);
+    assert_eq!(
+        client.try_single_payout(&program_id, &winner, &5_000),
+        Err(Ok(Error::InsufficientApprovals))
+    );
+
+    client.approve_payout(&program_id, &winner, &5_000, &s2);
+    client.single_payout(&program_id, &winner, &5_000);
+    assert_eq!(token_client.balance(&winner), 5_000);
+
+    // Approvals are consumed by the payout
+    assert_eq!(client.get_payout_approval(&program_id, &winner), None);
+    assert_eq!(
+        client.try_single_payout(&program_id, &winner, &5_000),
+        Err(Ok(Error::InsufficientApprovals))
+    );
+}
+
+#[test]
+fn test_approval_is_bound_to_amount() {
+    let env = Env::default();
+    let (client, _admin, _token_client, program_id) = setup_program_with_admin(&env, 10_000);
+    let (s1, s2, _s3) = setup_multisig(&env, &client, &program_id);
+    let winner = Address::generate(&env);
+
+    client.approve_payout(&program_id, &winner, &5_000, &s1);
+    assert_eq!(
+        client.try_approve_payout(&program_id, &winner, &6_000, &s2),
+        Err(Ok(Error::ApprovalAmountMismatch))
+    );
+
+    client.approve_payout(&program_id, &winner, &5_000, &s2);
+    assert_eq!(
+        client.try_single_payout(&program_id, &winner, &6_000),
+        Err(Ok(Error::InsufficientApprovals))
+    );
+}
+
+#[test]
+fn test_only_signers_can_approve() {
+    let env = Env::default();
+    let (client, _admin, _token_client, program_id) = setup_program_with_admin(&env, 10_000);
+    setup_multisig(&env, &client, &program_id);
+    let winner = Address::generate(&env);
+    let outsider = Address::generate(&env);
+
+    assert_eq!(
+        client.try_approve_payout(&program_id, &winner, &5_000, &outsider),
+        Err(Ok(Error::NotMultisigSigner))
+    );
+}
+
+#[test]
+fn test_revoked_and_removed_signer_approvals_do_not_count() {
+    let env = Env::default();
+    let (client, _admin, _token_client, program_id) = setup_program_with_admin(&env, 10_000);
+    let (s1, s2, s3) = setup_multisig(&env, &client, &program_id);
+    let winner = Address::generate(&env);
+
+    client.approve_payout(&program_id, &winner, &5_000, &s1);
+    client.approve_payout(&program_id, &winner, &5_000, &s2);
+    client.revoke_payout_approval(&program_id, &winner, &s2);
+    assert_eq!(
+        client
+            .get_payout_approval(&program_id, &winner)
+            .unwrap()
+            .approvals,
+        vec![&env, s1.clone()]
+    );
+
+    // s3 approves, then s1 is dropped from the signer set
+    client.approve_payout(&program_id, &winner, &5_000, &s3);
+    client.update_multisig_config(&program_id, &1_000, &vec![&env, s2, s3], &2);
+    assert_eq!(
+        client.try_single_payout(&program_id, &winner, &5_000),
+        Err(Ok(Error::InsufficientApprovals))
+    );
+}
+
+#[test]
+fn test_batch_payout_checks_each_large_entry() {
+    let env = Env::default();
+    let (client, _admin, token_client, program_id) = setup_program_with_admin(&env, 10_000);
+    let (s1, s2, _s3) = setup_multisig(&env, &client, &program_id);
+    let small = Address::generate(&env);
+    let large = Address::generate(&env);
+    let recipients = vec![&env, small.clone(), large.clone()];
+    let amounts = vec![&env, 500i128, 4_000i128];
+
+    assert_eq!(
+        client.try_batch_payout(&program_id, &recipients, &amounts),
+        Err(Ok(Error::InsufficientApprovals))
+    );
+    assert_eq!(token_client.balance(&small), 0);
+
+    client.approve_payout(&program_id, &large, &4_000, &s1);
+    client.approve_payout(&program_id, &large, &4_000, &s2);
+    client.batch_payout(&program_id, &recipients, &amounts);
+    assert_eq!(token_client.balance(&small), 500);
+    assert_eq!(token_client.balance(&large), 4_000);
+}
+
+#[test]
+fn test_split_payouts_count_towards_threshold() {
+    let env = Env::default();
+    let (client, _admin, token_client, program_id) = setup_program_with_admin(&env, 10_000);
+    let (s1, s2, _s3) = setup_multisig(&env, &client, &program_id);
+    let winner = Address::generate(&env);
+
+    // Three entries under the threshold still add up past it
+    assert_eq!(
+        client.try_batch_payout(
+            &program_id,
+            &vec![&env, winner.clone(), winner.clone(), winner.clone()],
+            &vec![&env, 500i128, 500i128, 500i128],
+        ),
+        Err(Ok(Error::InsufficientApprovals))
+    );
+    assert_eq!(token_client.balance(&winner), 0);
+
+    // So do repeated single payouts
+    client.single_payout(&program_id, &winner, &600);
+    assert_eq!(
+        client.try_single_payout(&program_id, &winner, &600),
+        Err(Ok(Error::InsufficientApprovals))
+    );
+
+    client.approve_payout(&program_id, &winner, &600, &s1);
+    client.approve_payout(&program_id, &winner, &600, &s2);
+    client.single_payout(&program_id, &winner, &600);
+    assert_eq!(token_client.balance(&winner), 1_200);
+}
+
+#[test]
+fn test_trigger_releases_skips_unapproved_large_schedules() {
+    let env = Env::default();
+    let (client, _admin, token_client, program_id) = setup_program_with_admin(&env, 10_000);
+    let (s1, s2, _s3) = setup_multisig(&env, &client, &program_id);
+    let small = Address::generate(&env);
+    let large = Address::generate(&env);
+    let release_at = env.ledger().timestamp() + 10;
+
+    client.create_program_release_schedule(&program_id, &small, &500, &release_at);
+    client.create_program_release_schedule(&program_id, &large, &4_000, &release_at);
+    env.ledger().set_timestamp(release_at);
+
+    assert_eq!(
+        client
+            .trigger_program_releases(&program_id, &ReleaseCursor::START, &50)
+            .released_count,
+        1
+    );
+    assert_eq!(token_client.balance(&small), 500);
+    assert_eq!(token_client.balance(&large), 0);
+    assert_eq!(client.get_pending_schedules(&program_id).len(), 1);
+
+    client.approve_payout(&program_id, &large, &4_000, &s1);
+    client.approve_payout(&program_id, &large, &4_000, &s2);
+    assert_eq!(
+        client
+            .trigger_program_releases(&program_id, &ReleaseCursor::START, &50)
+            .released_count,
+        1
+    );
+    assert_eq!(token_client.balance(&large), 4_000);
+}
+
+// ============================================================================
+// Vesting Schedule Tests
+// ============================================================================
+
+#[test]
+fn test_cliff_linear_vesting_withdrawals() {
+    let env = Env::default();
+    let (client, _admin, token_client, program_id) = setup_program_with_admin(&env, 10_000);
+    let grantee = Address::generate(&env);
+    let start = env.ledger().timestamp();
+
+    let schedule =
+        client.create_program_vesting_schedule(&program_id, &grantee, &1_200, &start, &300, &1_200);
+    assert_eq!(schedule.kind, ReleaseKind::CliffLinear(300, 1_200));
+
+    // Nothing before the cliff
+    env.ledger().set_timestamp(start + 299);
+    assert_eq!(
+        client.get_vested_amount(&program_id, &schedule.schedule_id),
+        0
+    );
+    assert_eq!(
+        client.try_withdraw_vested(&program_id, &schedule.schedule_id),
+        Err(Ok(Error::NothingVested))
+    );
+    assert_eq!(client.get_due_schedules(&program_id).len(), 0);
+
+    // At the cliff the linearly accrued amount unlocks
+    env.ledger().set_timestamp(start + 300);
+    assert_eq!(client.get_due_schedules(&program_id).len(), 1);
+    assert_eq!(
+        client.withdraw_vested(&program_id, &schedule.schedule_id),
+        300
+    );
+    assert_eq!(env.auths()[0].0, grantee);
+    assert_eq!(client.get_total_scheduled_amount(&program_id), 900);
+    assert_eq!(client.get_due_schedules(&program_id).len(), 0);
+
+    env.ledger().set_timestamp(start + 900);
+    assert_eq!(
+        client.withdraw_vested(&program_id, &schedule.schedule_id),
+        600
+    );
+
+    // Past the end everything is vested and the schedule completes
+    env.ledger().set_timestamp(start + 5_000);
+    assert_eq!(
+        client.withdraw_vested(&program_id, &schedule.schedule_id),
+        300
+    );
+    assert_eq!(token_client.balance(&grantee), 1_200);
+    assert_eq!(client.get_total_scheduled_amount(&program_id), 0);
+    assert_eq!(client.get_pending_schedules(&program_id).len(), 0);
+
+    let history = client
+        .get_program_release_history(&program_id, &0, &50)
+        .records;
+    assert_eq!(history.len(), 3);
+    assert_eq!(history.get(1).unwrap().amount, 600);
+    assert_eq!(client.get_remaining_balance(&program_id), 8_800);
+}
+
+#[test]
+fn test_periodic_tranches_via_trigger() {
+    let env = Env::default();
+    let (client, _admin, token_client, program_id) = setup_program_with_admin(&env, 10_000);
+    let grantee = Address::generate(&env);
+    let first = env.ledger().timestamp() + 100;
+
+    let schedule =
+        client.create_program_tranche_schedule(&program_id, &grantee, &1_000, &first, &30, &3);
+
+    env.ledger().set_timestamp(first - 1);
+    assert_eq!(
+        client
+            .trigger_program_releases(&program_id, &ReleaseCursor::START, &50)
+            .released_count,
+        0
+    );
+
+    env.ledger().set_timestamp(first);
+    assert_eq!(
+        client
+            .trigger_program_releases(&program_id, &ReleaseCursor::START, &50)
+            .released_count,
+        1
+    );
+    assert_eq!(token_client.balance(&grantee), 333);
+
+    // A second trigger in the same period releases nothing new
+    assert_eq!(
+        client
+            .trigger_program_releases(&program_id, &ReleaseCursor::START, &50)
+            .released_count,
+        0
+    );
+
+    // Skipping ahead releases every tranche that unlocked meanwhile
+    env.ledger().set_timestamp(first + 60);
+    assert_eq!(
+        client
+            .trigger_program_releases(&program_id, &ReleaseCursor::START, &50)
+            .released_count,
+        1
+    );
+    assert_eq!(token_client.balance(&grantee), 1_000);
+
+    let stored = client
+        .get_program_release_schedules(&program_id)
+        .get(0)
+        .unwrap();
+    assert_eq!(stored.schedule_id, schedule.schedule_id);
+    assert_eq!(stored.released_amount, 1_000);
+    assert!(stored.released);
+    assert_eq!(client.get_release_history_count(&program_id), 2);
+}
+
+#[test]
+fn test_withdraw_vested_requires_recipient_schedule() {
+    let env = Env::default();
+    let (client, _admin, _token_client, program_id) = setup_program_with_admin(&env, 10_000);
+    let grantee = Address::generate(&env);
+    let start = env.ledger().timestamp();
+
+    assert_eq!(
+        client.try_create_program_vesting_schedule(&program_id, &grantee, &1_000, &start, &10, &0),
+        Err(Ok(Error::InvalidSchedulePeriod))
+    );
+    assert_eq!(
+        client.try_create_program_tranche_schedule(&program_id, &grantee, &1_000, &start, &30, &0),
+        Err(Ok(Error::InvalidSchedulePeriod))
+    );
+    assert_eq!(
+        client.try_withdraw_vested(&program_id, &42),
+        Err(Ok(Error::ScheduleNotFound))
+    );
+}
+
+#[test]
+fn test_vested_withdrawal_approval_is_a_cap() {
+    let env = Env::default();
+    let (client, _admin, token_client, program_id) = setup_program_with_admin(&env, 10_000);
+    let (s1, s2, _s3) = setup_multisig(&env, &client, &program_id);
+    let grantee = Address::generate(&env);
+    let start = env.ledger().timestamp();
+
+    let schedule =
+        client.create_program_vesting_schedule(&program_id, &grantee, &4_000, &start, &0, &400);
+
+    // Signers approve the full grant; the vested amount is whatever accrued
+    client.approve_payout(&program_id, &grantee, &4_000, &s1);
+    client.approve_payout(&program_id, &grantee, &4_000, &s2);
+
+    env.ledger().set_timestamp(start + 130);
+    assert_eq!(
+        client.withdraw_vested(&program_id, &schedule.schedule_id),
+        1_300
+    );
+    assert_eq!(token_client.balance(&grantee), 1_300);
+    // The approval is used up by the withdrawal
+    assert_eq!(client.get_payout_approval(&program_id, &grantee), None);
+
+    // More than approved still needs fresh approvals
+    client.approve_payout(&program_id, &grantee, &1_000, &s1);
+    client.approve_payout(&program_id, &grantee, &1_000, &s2);
+    env.ledger().set_timestamp(start + 400);
+    assert_eq!(
+        client.try_withdraw_vested(&program_id, &schedule.schedule_id),
+        Err(Ok(Error::InsufficientApprovals))
+    );
+}
+
+#[test]
+fn test_approve_payout_unknown_program() {
+    let env = Env::default();
+    let (client, _admin, _token_client, program_id) = setup_program_with_admin(&env, 10_000);
+    let (s1, _s2, _s3) = setup_multisig(&env, &client, &program_id);
+    let winner = Address::generate(&env);
+
+    assert_eq!(
+        client.try_approve_payout(&String::from_str(&env, "missing"), &winner, &5_000, &s1),
+        Err(Ok(Error::ProgramNotFound))
+    );
+}
+
+// ============================================================================
+// Schedule Cancel/Update Tests
+// ============================================================================
+
+#[test]
+fn test_cancel_release_schedule() {
+    let env = Env::default();
+    let (client, admin, token_client, program_id) = setup_program_with_admin(&env, 10_000);
+    let winner = Address::generate(&env);
+    let release_at = env.ledger().timestamp() + 10;
+
+    let schedule =
+        client.create_program_release_schedule(&program_id, &winner, &3_000, &release_at);
+    client.create_program_release_schedule(&program_id, &winner, &1_000, &release_at);
+    assert_eq!(client.get_total_scheduled_amount(&program_id), 4_000);
+
+    let cancelled = client.cancel_program_release_schedule(&program_id, &schedule.schedule_id);
+    assert_eq!(env.auths()[0].0, admin);
+    assert!(cancelled.cancelled);
+    assert_eq!(client.get_total_scheduled_amount(&program_id), 1_000);
+    assert_eq!(client.get_pending_schedules(&program_id).len(), 1);
+
+    let mut cancel_events = 0;
+    for (_contract, topics, data) in env.events().all().iter() {
+        let topic = Symbol::try_from_val(&env, &topics.get(0).unwrap()).unwrap();
+        if topic == symbol_short!("SchCncl") {
+            cancel_events += 1;
+            assert_event_data_has_v2_tag(&env, &data);
+        }
+    }
+    assert_eq!(cancel_events, 1);
+
+    let history = client
+        .get_program_release_history(&program_id, &0, &50)
+        .records;
+    assert_eq!(history.len(), 1);
+    let entry = history.get(0).unwrap();
+    assert_eq!(entry.action, ReleaseAction::Cancelled);
+    assert_eq!(entry.amount, 3_000);
+
+    // The cancelled schedule is skipped at release time and cannot be cancelled twice
+    env.ledger().set_timestamp(release_at);
+    assert_eq!(
+        client
+            .trigger_program_releases(&program_id, &ReleaseCursor::START, &50)
+            .released_count,
+        1
+    );
+    assert_eq!(token_client.balance(&winner), 1_000);
+    assert_eq!(
+        client.try_cancel_program_release_schedule(&program_id, &schedule.schedule_id),
+        Err(Ok(Error::ScheduleNotPending))
+    );
+}
+
+#[test]
+fn test_cancel_release_schedule_checks_key_and_status() {
+    let env = Env::default();
+    let (client, _admin, _token_client, program_id) = setup_program_with_admin(&env, 10_000);
+    let winner = Address::generate(&env);
+    let release_at = env.ledger().timestamp() + 10;
+
+    let schedule =
+        client.create_program_release_schedule(&program_id, &winner, &3_000, &release_at);
+
+    client.revoke_payout_key(&program_id);
+    assert_eq!(
+        client.try_cancel_program_release_schedule(&program_id, &schedule.schedule_id),
+        Err(Ok(Error::PayoutKeyRevoked))
+    );
+
+    let new_key = Address::generate(&env);
+    client.rotate_payout_key(&program_id, &new_key, &0);
+    client.close_program(&program_id);
+    assert_eq!(
+        client.try_cancel_program_release_schedule(&program_id, &schedule.schedule_id),
+        Err(Ok(Error::ProgramNotActive))
+    );
+}
+
+#[test]
+fn test_cancel_partially_vested_schedule_keeps_released_part() {
+    let env = Env::default();
+    let (client, _admin, token_client, program_id) = setup_program_with_admin(&env, 10_000);
+    let grantee = Address::generate(&env);
+    let start = env.ledger().timestamp();
+
+    let schedule =
+        client.create_program_vesting_schedule(&program_id, &grantee, &1_000, &start, &0, &100);
+    env.ledger().set_timestamp(start + 40);
+    client.withdraw_vested(&program_id, &schedule.schedule_id);
+
+    client.cancel_program_release_schedule(&program_id, &schedule.schedule_id);
+    let history = client
+        .get_program_release_history(&program_id, &0, &50)
+        .records;
+    assert_eq!(history.get(1).unwrap().amount, 600);
+    assert_eq!(token_client.balance(&grantee), 400);
+    assert_eq!(client.get_total_scheduled_amount(&program_id), 0);
+
+    env.ledger().set_timestamp(start + 100);
+    assert!(client
+        .try_withdraw_vested(&program_id, &schedule.schedule_id)
+        .is_err());
+}
+
+#[test]
+fn test_update_release_schedule() {
+    let env = Env::default();
+    let (client, _admin, token_client, program_id) = setup_program_with_admin(&env, 10_000);
+    let disqualified = Address::generate(&env);
+    let runner_up = Address::generate(&env);
+    let release_at = env.ledger().timestamp() + 10;
+
+    let schedule =
+        client.create_program_release_schedule(&program_id, &disqualified, &2_000, &release_at);
+    let updated = client.update_program_release_schedule(
+        &program_id,
+        &schedule.schedule_id,
+        &runner_up,
+        &(release_at + 50),
+    );
+    assert_eq!(updated.recipient, runner_up);
+    assert_eq!(updated.release_timestamp, release_at + 50);
+
+    let entry = client
+        .get_program_release_history(&program_id, &0, &50)
+        .records
+        .get(0)
+        .unwrap();
+    assert_eq!(entry.action, ReleaseAction::Updated);
+    assert_eq!(entry.recipient, runner_up);
+
+    env.ledger().set_timestamp(release_at);
+    assert_eq!(
+        client
+            .trigger_program_releases(&program_id, &ReleaseCursor::START, &50)
+            .released_count,
+        0
+    );
+    env.ledger().set_timestamp(release_at + 50);
+    assert_eq!(
+        client
+            .trigger_program_releases(&program_id, &ReleaseCursor::START, &50)
+            .released_count,
+        1
+    );
+    assert_eq!(token_client.balance(&runner_up), 2_000);
+    assert_eq!(token_client.balance(&disqualified), 0);
+
+    // Released schedules can no longer be edited
+    assert!(client
+        .try_update_program_release_schedule(
+            &program_id,
+            &schedule.schedule_id,
+            &disqualified,
+            &release_at
+        )
+        .is_err());
+}
+
+// ============================================================================
+// Schedule Reservation Tests
+// ============================================================================
+
+#[test]
+fn test_schedule_reserves_funds_at_creation() {
+    let env = Env::default();
+    let (client, _admin, _token_client, program_id) = setup_program_with_admin(&env, 10_000);
+    let winner = Address::generate(&env);
+    let release_at = env.ledger().timestamp() + 10;
+
+    client.create_program_release_schedule(&program_id, &winner, &6_000, &release_at);
+    let info = client.get_program_info(&program_id);
+    assert_eq!(info.remaining_balance, 10_000);
+    assert_eq!(info.committed_balance, 6_000);
+    assert_eq!(client.get_available_balance(&program_id), 4_000);
+
+    // Over-commitment is rejected up front
+    let result =
+        client.try_create_program_release_schedule(&program_id, &winner, &4_001, &release_at);
+    assert!(result.is_err());
+    client.create_program_release_schedule(&program_id, &winner, &4_000, &release_at);
+    assert_eq!(client.get_available_balance(&program_id), 0);
+}
+
+#[test]
+fn test_direct_payouts_cannot_spend_committed_funds() {
+    let env = Env::default();
+    let (client, _admin, token_client, program_id) = setup_program_with_admin(&env, 10_000);
+    let scheduled = Address::generate(&env);
+    let winner = Address::generate(&env);
+    let release_at = env.ledger().timestamp() + 10;
+
+    client.create_program_release_schedule(&program_id, &scheduled, &7_000, &release_at);
+
+    assert!(client
+        .try_batch_payout(&program_id, &vec![&env, winner.clone()], &vec![&env, 3_001])
+        .is_err());
+    assert!(client
+        .try_single_payout(&program_id, &winner, &3_001)
+        .is_err());
+    assert!(client
+        .try_authorize_claim(&program_id, &winner, &3_001)
+        .is_err());
+
+    client.batch_payout(&program_id, &vec![&env, winner.clone()], &vec![&env, 3_000]);
+    assert_eq!(token_client.balance(&winner), 3_000);
+
+    // The schedule still releases in full
+    env.ledger().set_timestamp(release_at);
+    assert_eq!(
+        client
+            .trigger_program_releases(&program_id, &ReleaseCursor::START, &50)
+            .released_count,
+        1
+    );
+    assert_eq!(token_client.balance(&scheduled), 7_000);
+    let info = client.get_program_info(&program_id);
+    assert_eq!(info.remaining_balance, 0);
+    assert_eq!(info.committed_balance, 0);
+}
+
+#[test]
+fn test_cancel_and_partial_release_update_commitment() {
+    let env = Env::default();
+    let (client, _admin, _token_client, program_id) = setup_program_with_admin(&env, 10_000);
+    let grantee = Address::generate(&env);
+    let start = env.ledger().timestamp();
+
+    let schedule =
+        client.create_program_vesting_schedule(&program_id, &grantee, &4_000, &start, &0, &100);
+    env.ledger().set_timestamp(start + 25);
+    client.withdraw_vested(&program_id, &schedule.schedule_id);
+    assert_eq!(
+        client.get_program_info(&program_id).committed_balance,
+        3_000
+    );
+    assert_eq!(client.get_available_balance(&program_id), 6_000);
+
+    client.cancel_program_release_schedule(&program_id, &schedule.schedule_id);
+    assert_eq!(client.get_program_info(&program_id).committed_balance, 0);
+    assert_eq!(client.get_available_balance(&program_id), 9_000);
+}
+
+// ============================================================================
+// Paginated Release Tests
+// ============================================================================
+
+#[test]
+fn test_trigger_releases_in_bounded_pages() {
+    let env = Env::default();
+    let (client, _admin, token_client, program_id) = setup_program_with_admin(&env, 10_000);
+    let now = env.ledger().timestamp();
+    let mut recipients = Vec::new(&env);
+    let mut schedule_ids = Vec::new(&env);
+    for _ in 0..5 {
+        let recipient = Address::generate(&env);
+        let schedule =
+            client.create_program_release_schedule(&program_id, &recipient, &100, &(now + 10));
+        recipients.push_back(recipient);
+        schedule_ids.push_back(schedule.schedule_id);
+    }
+    env.ledger().set_timestamp(now + 10);
+
+    let first = client.trigger_program_releases(&program_id, &ReleaseCursor::START, &2);
+    assert_eq!(first.released_count, 2);
+    assert!(!first.completed);
+    // The cursor is the last entry examined
+    assert_eq!(
+        first.next_cursor,
+        ReleaseCursor {
+            unlock_at: now + 10,
+            schedule_id: schedule_ids.get(1).unwrap(),
+        }
+    );
+
+    let second = client.trigger_program_releases(&program_id, &first.next_cursor, &2);
+    assert_eq!(second.released_count, 2);
+    assert!(!second.completed);
+
+    let last = client.trigger_program_releases(&program_id, &second.next_cursor, &2);
+    assert_eq!(last.released_count, 1);
+    assert!(last.completed);
+
+    for recipient in recipients.iter() {
+        assert_eq!(token_client.balance(&recipient), 100);
+    }
+    assert_eq!(client.get_program_release_schedules(&program_id).len(), 5);
+    assert_eq!(client.get_pending_schedules(&program_id).len(), 0);
+}
+
+#[test]
+fn test_trigger_releases_follows_due_time_order() {
+    let env = Env::default();
+    let (client, _admin, token_client, program_id) = setup_program_with_admin(&env, 10_000);
+    let late = Address::generate(&env);
+    let early = Address::generate(&env);
+    let now = env.ledger().timestamp();
+
+    client.create_program_release_schedule(&program_id, &late, &100, &(now + 50));
+    client.create_program_release_schedule(&program_id, &early, &100, &(now + 10));
+
+    let due = client.get_due_schedules(&program_id);
+    assert_eq!(due.len(), 0);
+
+    env.ledger().set_timestamp(now + 50);
+    let due = client.get_due_schedules(&program_id);
+    assert_eq!(due.get(0).unwrap().recipient, early);
+
+    let result = client.trigger_program_releases(&program_id, &ReleaseCursor::START, &1);
+    assert_eq!(result.released_count, 1);
+    assert_eq!(token_client.balance(&early), 100);
+    assert_eq!(token_client.balance(&late), 0);
+}
+
+#[test]
+fn test_trigger_releases_skips_blocked_schedules_and_continues() {
+    let env = Env::default();
+    let (client, _admin, token_client, program_id) = setup_program_with_admin(&env, 10_000);
+    setup_multisig(&env, &client, &program_id);
+    let blocked = Address::generate(&env);
+    let small = Address::generate(&env);
+    let now = env.ledger().timestamp();
+
+    client.create_program_release_schedule(&program_id, &blocked, &5_000, &(now + 10));
+    let payable = client.create_program_release_schedule(&program_id, &small, &500, &(now + 10));
+    env.ledger().set_timestamp(now + 10);
+
+    let result = client.trigger_program_releases(&program_id, &ReleaseCursor::START, &10);
+    assert_eq!(result.released_count, 1);
+    assert_eq!(result.skipped_count, 1);
+    assert_eq!(
+        result.next_cursor,
+        ReleaseCursor {
+            unlock_at: now + 10,
+            schedule_id: payable.schedule_id,
+        }
+    );
+    assert!(result.completed);
+    assert_eq!(token_client.balance(&small), 500);
+    assert_eq!(client.get_pending_schedules(&program_id).len(), 1);
+}
+
+#[test]
+fn test_trigger_cursor_spans_day_buckets() {
+    let env = Env::default();
+    let (client, _admin, token_client, program_id) = setup_program_with_admin(&env, 20_000);
+    setup_multisig(&env, &client, &program_id);
+    let blocked = Address::generate(&env);
+    let winner = Address::generate(&env);
+    let now = env.ledger().timestamp();
+    let day = 86_400;
+
+    // Two schedules awaiting approval on earlier days, one payable later
+    client.create_program_release_schedule(&program_id, &blocked, &5_000, &(now + 10));
+    let second_blocked =
+        client.create_program_release_schedule(&program_id, &blocked, &6_000, &(now + day + 10));
+    let payable =
+        client.create_program_release_schedule(&program_id, &winner, &500, &(now + 3 * day));
+    env.ledger().set_timestamp(now + 3 * day);
+
+    let due = client.get_due_schedules(&program_id);
+    assert_eq!(due.len(), 3);
+    assert_eq!(due.get(2).unwrap().recipient, winner);
+
+    let first = client.trigger_program_releases(&program_id, &ReleaseCursor::START, &2);
+    assert_eq!(first.skipped_count, 2);
+    assert_eq!(
+        first.next_cursor,
+        ReleaseCursor {
+            unlock_at: now + day + 10,
+            schedule_id: second_blocked.schedule_id,
+        }
+    );
+    assert!(!first.completed);
+
+    let second = client.trigger_program_releases(&program_id, &first.next_cursor, &2);
+    assert_eq!(second.released_count, 1);
+    assert_eq!(
+        second.next_cursor,
+        ReleaseCursor {
+            unlock_at: now + 3 * day,
+            schedule_id: payable.schedule_id,
+        }
+    );
+    assert!(second.completed);
+    assert_eq!(token_client.balance(&winner), 500);
+    assert_eq!(client.get_due_schedules(&program_id).len(), 2);
+}
+
+#[test]
+fn test_trigger_cursor_survives_index_changes_between_pages() {
+    let env = Env::default();
+    let (client, _admin, token_client, program_id) = setup_program_with_admin(&env, 20_000);
+    setup_multisig(&env, &client, &program_id);
+    let blocked = Address::generate(&env);
+    let winner = Address::generate(&env);
+    let now = env.ledger().timestamp();
+
+    let cancelled =
+        client.create_program_release_schedule(&program_id, &blocked, &5_000, &(now + 10));
+    client.create_program_release_schedule(&program_id, &blocked, &6_000, &(now + 10));
+    client.create_program_release_schedule(&program_id, &winner, &500, &(now + 10));
+    env.ledger().set_timestamp(now + 10);
+
+    let first = client.trigger_program_releases(&program_id, &ReleaseCursor::START, &2);
+    assert_eq!(first.skipped_count, 2);
+    assert!(!first.completed);
+
+    // Entries leaving the index before the cursor do not shift it, and
+    // new entries behind it wait for the next sweep
+    client.cancel_program_release_schedule(&program_id, &cancelled.schedule_id);
+    client.create_program_release_schedule(&program_id, &blocked, &7_000, &now);
+
+    let second = client.trigger_program_releases(&program_id, &first.next_cursor, &2);
+    assert_eq!(second.released_count, 1);
+    assert_eq!(second.skipped_count, 0);
+    assert!(second.completed);
+    assert_eq!(token_client.balance(&winner), 500);
+}
+
+#[test]
+fn test_release_history_pages_with_cursor() {
+    let env = Env::default();
+    let (client, _admin, _token_client, program_id) = setup_program_with_admin(&env, 10_000);
+    let winner = Address::generate(&env);
+    let other = Address::generate(&env);
+    let release_at = env.ledger().timestamp() + 10;
+
+    for i in 0..5 {
+        let recipient = if i % 2 == 0 { &winner } else { &other };
+        let schedule =
+            client.create_program_release_schedule(&program_id, recipient, &100, &release_at);
+        client.cancel_program_release_schedule(&program_id, &schedule.schedule_id);
+    }
+    assert_eq!(client.get_release_history_count(&program_id), 5);
+
+    let first = client.get_program_release_history(&program_id, &0, &2);
+    assert_eq!(first.records.len(), 2);
+    assert_eq!(first.next_cursor, Some(2));
+    let last = client.get_program_release_history(&program_id, &4, &2);
+    assert_eq!(last.records.len(), 1);
+    assert_eq!(last.next_cursor, None);
+
+    // The recipient query examines `limit` entries per page
+    let page = client.query_releases_by_recipient(&program_id, &winner, &0, &3);
+    assert_eq!(page.records.len(), 2);
+    assert_eq!(page.next_cursor, Some(3));
+    let page = client.query_releases_by_recipient(&program_id, &winner, &3, &3);
+    assert_eq!(page.records.len(), 1);
+    assert_eq!(page.next_cursor, None);
+
+    assert_eq!(
+        client.try_get_program_release_history(&String::from_str(&env, "missing"), &0, &2),
+        Err(Ok(Error::ProgramNotFound))
+    );
+}
+
+// ============================================================================
+// Circuit Breaker Tests
+// ============================================================================
+
+/// Like `setup_program`, but the token issuer may deauthorize holders, which
+/// makes transfers to them fail.
+fn setup_program_with_revocable_token(
+    env: &Env,
+    initial_amount: i128,
+) -> (
+    ProgramEscrowContractClient<'static>,
+    token::Client<'static>,
+    token::StellarAssetClient<'static>,
+    String,
+) {
+    env.mock_all_auths();
+
+    let contract_id = env.register_contract(None, ProgramEscrowContract);
+    let client = ProgramEscrowContractClient::new(env, &contract_id);
+    let payout_key = Address::generate(env);
+    let sac = env.register_stellar_asset_contract_v2(Address::generate(env));
+    sac.issuer().set_flag(IssuerFlags::RevocableFlag);
+    let token_client = token::Client::new(env, &sac.address());
+    let token_admin_client = token::StellarAssetClient::new(env, &sac.address());
+
+    let program_id = String::from_str(env, "hack-2026");
+    client.init_program(&program_id, &payout_key, &sac.address());
+    token_admin_client.mint(&payout_key, &initial_amount);
+    client.lock_program_funds(&program_id, &payout_key, &initial_amount);
+
+    (client, token_client, token_admin_client, program_id)
+}
+
+#[test]
+fn test_open_circuit_rejects_payouts_with_typed_error() {
+    let env = Env::default();
+    let (client, _admin, _token_client, program_id) = setup_program_with_admin(&env, 10_000);
+    let cb_admin = Address::generate(&env);
+    let winner = Address::generate(&env);
+    client.set_circuit_admin(&cb_admin, &None);
+    client.emergency_open_circuit(&cb_admin);
+
+    assert_eq!(
+        client.try_single_payout(&program_id, &winner, &100),
+        Err(Ok(Error::CircuitOpen))
+    );
+    assert_eq!(
+        client.try_batch_payout(&program_id, &vec![&env, winner.clone()], &vec![&env, 100]),
+        Err(Ok(Error::CircuitOpen))
+    );
+    assert_eq!(
+        client.try_trigger_program_releases(&program_id, &ReleaseCursor::START, &10),
+        Err(Ok(Error::CircuitOpen))
+    );
+
+    // A reset moves the breaker to half-open and payouts flow again
+    client.reset_circuit_breaker(&cb_admin);
+    client.single_payout(&program_id, &winner, &100);
+    assert_eq!(
+        client.get_circuit_status().state,
+        error_recovery::CircuitState::Closed
+    );
+}
+
+#[test]
+fn test_failed_release_transfers_are_recorded_and_trip_breaker() {
+    let env = Env::default();
+    let (client, token_client, token_admin_client, program_id) =
+        setup_program_with_revocable_token(&env, 10_000);
+    let frozen = Address::generate(&env);
+    let healthy = Address::generate(&env);
+    let now = env.ledger().timestamp();
+
+    // Transfers to a deauthorized holder fail inside the token contract
+    token_admin_client.set_authorized(&frozen, &false);
+    client.create_program_release_schedule(&program_id, &frozen, &100, &(now + 10));
+    client.create_program_release_schedule(&program_id, &healthy, &100, &(now + 10));
+    env.ledger().set_timestamp(now + 10);
+
+    let result = client.trigger_program_releases(&program_id, &ReleaseCursor::START, &10);
+    assert_eq!(result.released_count, 1);
+    assert_eq!(result.skipped_count, 1);
+    assert_eq!(token_client.balance(&healthy), 100);
+    assert_eq!(client.get_pending_schedules(&program_id).len(), 1);
+    assert_eq!(client.get_remaining_balance(&program_id), 9_900);
+
+    let log = client.get_circuit_error_log();
+    assert_eq!(log.len(), 1);
+    let entry = log.get(0).unwrap();
+    assert_eq!(entry.program_id, program_id);
+    assert_eq!(entry.operation, symbol_short!("release"));
+    assert_eq!(entry.error_code, error_recovery::ERR_TRANSFER_FAILED);
+
+    // The healthy release reset the streak; three consecutive failures
+    // open the circuit (default threshold)
+    assert_eq!(client.get_circuit_status().failure_count, 0);
+    for _ in 0..3 {
+        client.trigger_program_releases(&program_id, &ReleaseCursor::START, &10);
+    }
+    assert_eq!(
+        client.get_circuit_status().state,
+        error_recovery::CircuitState::Open
+    );
+    assert!(client
+        .try_single_payout(&program_id, &healthy, &100)
+        .is_err());
+}
+
+#[test]
+fn test_failed_single_payouts_trip_breaker() {
+    let env = Env::default();
+    let (client, token_client, token_admin_client, program_id) =
+        setup_program_with_revocable_token(&env, 10_000);
+    let frozen = Address::generate(&env);
+    let healthy = Address::generate(&env);
+    token_admin_client.set_authorized(&frozen, &false);
+
+    // Each failure is kept and leaves the program untouched
+    for attempt in 1..3 {
+        let data = client.single_payout(&program_id, &frozen, &100);
+        assert_eq!(data.remaining_balance, 10_000);
+        assert_eq!(client.get_circuit_status().failure_count, attempt);
+    }
+    assert_eq!(client.get_payout_count(&program_id), 0);
+    assert_eq!(
+        client.get_circuit_status().state,
+        error_recovery::CircuitState::Closed
+    );
+
+    // The third consecutive failure opens the circuit (default threshold)
+    client.single_payout(&program_id, &frozen, &100);
+    assert_eq!(
+        client.get_circuit_status().state,
+        error_recovery::CircuitState::Open
+    );
+    assert_eq!(client.get_circuit_error_log().len(), 3);
+    assert_eq!(
+        client.try_single_payout(&program_id, &healthy, &100),
+        Err(Ok(Error::CircuitOpen))
+    );
+    assert_eq!(token_client.balance(&healthy), 0);
+}
+
+#[test]
+fn test_failed_payout_keeps_multisig_approval() {
+    let env = Env::default();
+    let (client, token_client, token_admin_client, program_id) =
+        setup_program_with_revocable_token(&env, 10_000);
+    client.initialize_contract(&Address::generate(&env));
+    let (s1, s2, _s3) = setup_multisig(&env, &client, &program_id);
+    let winner = Address::generate(&env);
+    client.approve_payout(&program_id, &winner, &5_000, &s1);
+    client.approve_payout(&program_id, &winner, &5_000, &s2);
+
+    // The transfer fails, so the signers' approval is still there to retry
+    token_admin_client.set_authorized(&winner, &false);
+    client.single_payout(&program_id, &winner, &5_000);
+    assert_eq!(client.get_circuit_status().failure_count, 1);
+    assert!(client.get_payout_approval(&program_id, &winner).is_some());
+
+    token_admin_client.set_authorized(&winner, &true);
+    client.single_payout(&program_id, &winner, &5_000);
+    assert_eq!(token_client.balance(&winner), 5_000);
+    assert_eq!(client.get_payout_approval(&program_id, &winner), None);
+}
+
+#[test]
+fn test_failed_batch_entries_are_skipped_and_trip_breaker() {
+    let env = Env::default();
+    let (client, token_client, token_admin_client, program_id) =
+        setup_program_with_revocable_token(&env, 10_000);
+    let f1 = Address::generate(&env);
+    let f2 = Address::generate(&env);
+    let f3 = Address::generate(&env);
+    let late = Address::generate(&env);
+    for frozen in [&f1, &f2, &f3] {
+        token_admin_client.set_authorized(frozen, &false);
+    }
+
+    let data = client.batch_payout(
+        &program_id,
+        &vec![&env, f1.clone(), f2.clone(), f3.clone(), late.clone()],
+        &vec![&env, 100, 200, 300, 400],
+    );
+
+    // Three failures open the circuit, so the last entry is not attempted
+    assert_eq!(
+        client.get_circuit_status().state,
+        error_recovery::CircuitState::Open
+    );
+    assert_eq!(client.get_circuit_error_log().len(), 3);
+    assert_eq!(token_client.balance(&late), 0);
+    // Nothing went out, so nothing was charged
+    assert_eq!(data.remaining_balance, 10_000);
+    assert_eq!(client.get_payout_count(&program_id), 0);
+
+    let mut failed_events = 0;
+    for (contract, topics, data) in env.events().all().iter() {
+        if contract != client.address {
+            continue;
+        }
+        let topic = Symbol::try_from_val(&env, &topics.get(0).unwrap()).unwrap();
+        if topic == symbol_short!("PayFail") {
+            failed_events += 1;
+            assert_event_data_has_v2_tag(&env, &data);
+        }
+    }
+    assert_eq!(failed_events, 4);
+}
+
+#[test]
+fn test_failed_claim_stays_pending() {
+    let env = Env::default();
+    let (client, token_client, token_admin_client, program_id) =
+        setup_program_with_revocable_token(&env, 10_000);
+    let winner = Address::generate(&env);
+
+    let claim_id = client.authorize_claim(&program_id, &winner, &4_000);
+    token_admin_client.set_authorized(&winner, &false);
+    client.claim(&program_id, &claim_id);
+    assert_eq!(client.get_circuit_status().failure_count, 1);
+    assert!(!client.get_pending_claim(&program_id, &claim_id).claimed);
+
+    token_admin_client.set_authorized(&winner, &true);
+    client.claim(&program_id, &claim_id);
+    assert_eq!(token_client.balance(&winner), 4_000);
+    assert_eq!(client.get_program_info(&program_id).pending_claims_total, 0);
+}
+
+#[test]
+fn test_batch_payout_charges_only_successful_transfers() {
+    let env = Env::default();
+    let (client, token_client, token_admin_client, program_id) =
+        setup_program_with_revocable_token(&env, 10_000);
+    let w1 = Address::generate(&env);
+    let frozen = Address::generate(&env);
+    token_admin_client.set_authorized(&frozen, &false);
+
+    let data = client.batch_payout(
+        &program_id,
+        &vec![&env, w1.clone(), frozen.clone()],
+        &vec![&env, 1_000, 2_000],
+    );
+    assert_eq!(token_client.balance(&w1), 1_000);
+    assert_eq!(data.remaining_balance, 9_000);
+    assert_eq!(client.get_payout_count(&program_id), 1);
+    assert_eq!(client.get_circuit_status().failure_count, 1);
+}
+
+#[test]
+fn test_close_program_refunds_go_through_breaker() {
+    let env = Env::default();
+    let (client, _admin, _token_client, program_id) = setup_program_with_admin(&env, 10_000);
+    let cb_admin = Address::generate(&env);
+    client.set_circuit_admin(&cb_admin, &None);
+    client.emergency_open_circuit(&cb_admin);
+
+    assert_eq!(
+        client.try_close_program(&program_id),
+        Err(Ok(Error::CircuitOpen))
+    );
+    assert_eq!(
+        client.get_program_info(&program_id).status,
+        ProgramStatus::Active
+    );
+}
+
+#[test]
+fn test_failed_refund_becomes_funder_claim() {
+    let env = Env::default();
+    let (client, token_client, token_admin_client, program_id) =
+        setup_program_with_revocable_token(&env, 6_000);
+    let payout_key = client.get_program_info(&program_id).authorized_payout_key;
+    let frozen_funder = Address::generate(&env);
+    token_admin_client.mint(&frozen_funder, &4_000);
+    client.lock_program_funds(&program_id, &frozen_funder, &4_000);
+    token_admin_client.set_authorized(&frozen_funder, &false);
+
+    let data = client.close_program(&program_id);
+    assert_eq!(token_client.balance(&payout_key), 6_000);
+    assert_eq!(data.total_refunded, 6_000);
+    assert_eq!(data.remaining_balance, 4_000);
+    assert_eq!(data.pending_claims_total, 4_000);
+    assert_eq!(data.status, ProgramStatus::Closed);
+    assert_eq!(client.get_circuit_status().failure_count, 1);
+
+    // The funder pulls their share once they can receive again
+    token_admin_client.set_authorized(&frozen_funder, &true);
+    client.claim(&program_id, &1);
+    assert_eq!(token_client.balance(&frozen_funder), 4_000);
+    let info = client.get_program_info(&program_id);
+    assert_eq!(info.status, ProgramStatus::Settled);
+    assert_eq!(info.remaining_balance, 0);
+}
+
+// ============================================================================
+// Partial Batch Payout Tests
+// ============================================================================
+
+#[test]
+fn test_partial_batch_pays_good_recipients_and_defers_failures() {
+    let env = Env::default();
+    let (client, token_client, token_admin_client, program_id) =
+        setup_program_with_revocable_token(&env, 10_000);
+    let w1 = Address::generate(&env);
+    let frozen = Address::generate(&env);
+    let w3 = Address::generate(&env);
+    token_admin_client.set_authorized(&frozen, &false);
+
+    let results = client.batch_payout_partial(
+        &program_id,
+        &vec![&env, w1.clone(), frozen.clone(), w3.clone()],
+        &vec![&env, 1_000, 2_000, 3_000],
+    );
+
+    assert_eq!(results.len(), 3);
+    assert!(results.get(0).unwrap().paid);
+    assert_eq!(results.get(0).unwrap().claim_id, None);
+    let failed = results.get(1).unwrap();
+    assert!(!failed.paid);
+    assert_eq!(failed.recipient, frozen);
+    assert_eq!(failed.error_code, error_recovery::ERR_TRANSFER_FAILED);
+    assert!(results.get(2).unwrap().paid);
+
+    assert_eq!(token_client.balance(&w1), 1_000);
+    assert_eq!(token_client.balance(&w3), 3_000);
+    assert_eq!(token_client.balance(&frozen), 0);
+
+    // The failed entry is reserved as a claim: it stays in the program
+    // balance, matching the tokens still held, but cannot be spent again
+    let info = client.get_program_info(&program_id);
+    assert_eq!(info.remaining_balance, 6_000);
+    assert_eq!(token_client.balance(&client.address), 6_000);
+    assert_eq!(info.pending_claims_total, 2_000);
+    assert_eq!(client.get_available_balance(&program_id), 4_000);
+    assert_eq!(client.get_payout_count(&program_id), 2);
+    let stats = client.get_program_aggregate_stats(&program_id);
+    assert_eq!(stats.total_paid_out, 4_000);
+    assert_eq!(
+        info.remaining_balance + stats.total_paid_out,
+        info.total_funds
+    );
+    assert_eq!(
+        client.try_single_payout(&program_id, &w1, &4_001),
+        Err(Ok(Error::InsufficientBalance))
+    );
+
+    // Once the account is authorized again the recipient pulls the prize
+    token_admin_client.set_authorized(&frozen, &true);
+    client.claim(&program_id, &failed.claim_id.unwrap());
+    assert_eq!(token_client.balance(&frozen), 2_000);
+    let info = client.get_program_info(&program_id);
+    assert_eq!(info.remaining_balance, 4_000);
+    assert_eq!(token_client.balance(&client.address), 4_000);
+    assert_eq!(info.pending_claims_total, 0);
+    assert_eq!(client.get_payout_count(&program_id), 3);
+    assert_eq!(
+        client
+            .get_program_aggregate_stats(&program_id)
+            .total_paid_out,
+        6_000
+    );
+}
+
+#[test]
+fn test_partial_batch_expired_claim_returns_to_balance() {
+    let env = Env::default();
+    let (client, _token_client, token_admin_client, program_id) =
+        setup_program_with_revocable_token(&env, 10_000);
+    let frozen = Address::generate(&env);
+    token_admin_client.set_authorized(&frozen, &false);
+
+    let results = client.batch_payout_partial(&program_id, &vec![&env, frozen], &vec![&env, 2_000]);
+    let claim = client.get_pending_claim(&program_id, &results.get(0).unwrap().claim_id.unwrap());
+    assert_eq!(client.get_remaining_balance(&program_id), 10_000);
+    assert_eq!(client.get_available_balance(&program_id), 8_000);
+
+    env.ledger().set_timestamp(claim.expires_at + 1);
+    client.expire_claim(&program_id, &claim.claim_id);
+    let info = client.get_program_info(&program_id);
+    assert_eq!(info.remaining_balance, 10_000);
+    assert_eq!(info.pending_claims_total, 0);
+    assert_eq!(client.get_available_balance(&program_id), 10_000);
+}
+
+#[test]
+fn test_partial_batch_validates_like_batch_payout() {
+    let env = Env::default();
+    let (client, _admin, _token_client, program_id) = setup_program_with_admin(&env, 1_000);
+    let winner = Address::generate(&env);
+
+    assert!(client
+        .try_batch_payout_partial(&program_id, &vec![&env, winner.clone()], &vec![&env, 1_001])
+        .is_err());
+    assert!(client
+        .try_batch_payout_partial(&program_id, &vec![&env, winner.clone()], &vec![&env])
+        .is_err());
+
+    let results = client.batch_payout_partial(&program_id, &vec![&env, winner], &vec![&env, 1_000]);
+    assert!(results.get(0).unwrap().paid);
+    assert_eq!(client.get_remaining_balance(&program_id), 0);
+}
+
+// ============================================================================
+// Payout Record Tests
+// ============================================================================
+
+#[test]
+fn test_payout_history_pages_with_cursor() {
+    let env = Env::default();
+    let (client, _admin, _token_client, _token_admin, program_id) = setup_program(&env, 10_000);
+    let winner = Address::generate(&env);
+
+    for amount in 1..=5 {
+        client.single_payout(&program_id, &winner, &(amount * 100));
+    }
+    assert_eq!(client.get_payout_count(&program_id), 5);
+
+    let first = client.get_payout_history(&program_id, &0, &2);
+    assert_eq!(first.records.len(), 2);
+    assert_eq!(first.records.get(0).unwrap().amount, 100);
+    assert_eq!(first.next_cursor, Some(2));
+
+    let last = client.get_payout_history(&program_id, &4, &2);
+    assert_eq!(last.records.len(), 1);
+    assert_eq!(last.records.get(0).unwrap().amount, 500);
+    assert_eq!(last.next_cursor, None);
+
+    assert_eq!(
+        client.get_program_aggregate_stats(&program_id).payout_count,
+        5
+    );
+}
+
+#[test]
+fn test_query_payouts_by_recipient_uses_index() {
+    let env = Env::default();
+    let (client, _admin, _token_client, _token_admin, program_id) = setup_program(&env, 10_000);
+    let alice = Address::generate(&env);
+    let bob = Address::generate(&env);
+
+    client.single_payout(&program_id, &alice, &100);
+    client.batch_payout(
+        &program_id,
+        &vec![&env, bob.clone(), alice.clone()],
+        &vec![&env, 200, 300],
+    );
+    client.single_payout(&program_id, &alice, &400);
+
+    let page = client.query_payouts_by_recipient(&program_id, &alice, &0, &2);
+    assert_eq!(page.records.len(), 2);
+    assert_eq!(page.records.get(0).unwrap().amount, 100);
+    assert_eq!(page.records.get(1).unwrap().amount, 300);
+    assert_eq!(page.next_cursor, Some(2));
+
+    let page = client.query_payouts_by_recipient(&program_id, &alice, &2, &2);
+    assert_eq!(page.records.len(), 1);
+    assert_eq!(page.records.get(0).unwrap().amount, 400);
+    assert_eq!(page.next_cursor, None);
+
+    let bob_payouts = client.get_payouts_by_recipient(&program_id, &bob, &0, &10);
+    assert_eq!(bob_payouts.records.len(), 1);
+    assert_eq!(bob_payouts.records.get(0).unwrap().amount, 200);
+}
+
+#[test]
+fn test_query_payouts_by_timestamp_range() {
+    let env = Env::default();
+    let (client, _admin, _token_client, _token_admin, program_id) = setup_program(&env, 10_000);
+    let winner = Address::generate(&env);
+
+    for (timestamp, amount) in [(100_u64, 1_i128), (200, 2), (200, 3), (300, 4), (400, 5)] {
+        env.ledger().set_timestamp(timestamp);
+        client.single_payout(&program_id, &winner, &amount);
+    }
+
+    let page = client.query_payouts_by_timestamp(&program_id, &150, &300, &0, &2);
+    assert_eq!(page.records.len(), 2);
+    assert_eq!(page.records.get(0).unwrap().amount, 2);
+    assert_eq!(page.records.get(1).unwrap().amount, 3);
+    assert_eq!(page.next_cursor, Some(3));
+
+    let page = client.query_payouts_by_timestamp(&program_id, &150, &300, &3, &2);
+    assert_eq!(page.records.len(), 1);
+    assert_eq!(page.records.get(0).unwrap().amount, 4);
+    assert_eq!(page.next_cursor, None);
+
+    let empty = client.query_payouts_by_timestamp(&program_id, &500, &600, &0, &10);
+    assert_eq!(empty.records.len(), 0);
+    assert_eq!(empty.next_cursor, None);
+}
+
+#[test]
+fn test_query_payouts_by_amount_scans_in_pages() {
+    let env = Env::default();
+    let (client, _admin, _token_client, _token_admin, program_id) = setup_program(&env, 10_000);
+    let winner = Address::generate(&env);
+
+    for amount in [50, 500, 60, 700] {
+        client.single_payout(&program_id, &winner, &amount);
+    }
+
+    let page = client.query_payouts_by_amount(&program_id, &100, &1_000, &0, &3);
+    assert_eq!(page.records.len(), 1);
+    assert_eq!(page.records.get(0).unwrap().amount, 500);
+    assert_eq!(page.next_cursor, Some(3));
+
+    let page = client.query_payouts_by_amount(&program_id, &100, &1_000, &3, &3);
+    assert_eq!(page.records.len(), 1);
+    assert_eq!(page.records.get(0).unwrap().amount, 700);
+    assert_eq!(page.next_cursor, None);
+}
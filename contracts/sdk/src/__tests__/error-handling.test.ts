@@ -9,6 +9,8 @@ import {
 } from '../errors';
 import { Keypair } from '@stellar/stellar-sdk';
 
+const FUNDER = 'GAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAA';
+
 describe('SDK Client Error Handling', () => {
   let client: ProgramEscrowClient;
   let mockKeypair: Keypair;
@@ -91,29 +93,41 @@ describe('SDK Client Error Handling', () => {
     describe('lockProgramFunds', () => {
       it('should throw ValidationError for zero amount', async () => {
         await expect(
-          client.lockProgramFunds(0n, mockKeypair)
+          client.lockProgramFunds('test-program', FUNDER, 0n, mockKeypair)
         ).rejects.toThrow(ValidationError);
 
         await expect(
-          client.lockProgramFunds(0n, mockKeypair)
+          client.lockProgramFunds('test-program', FUNDER, 0n, mockKeypair)
         ).rejects.toThrow('Amount must be greater than zero');
       });
 
       it('should throw ValidationError for negative amount', async () => {
         await expect(
-          client.lockProgramFunds(-100n, mockKeypair)
+          client.lockProgramFunds('test-program', FUNDER, -100n, mockKeypair)
         ).rejects.toThrow(ValidationError);
       });
+
+      it('should throw ValidationError for empty program ID', async () => {
+        await expect(
+          client.lockProgramFunds('', FUNDER, 1000n, mockKeypair)
+        ).rejects.toThrow('Program ID cannot be empty');
+      });
+
+      it('should throw ValidationError for invalid funder address', async () => {
+        await expect(
+          client.lockProgramFunds('test-program', 'not-an-address', 1000n, mockKeypair)
+        ).rejects.toThrow('funder is not a valid Stellar address');
+      });
     });
 
     describe('batchPayout', () => {
       it('should throw ValidationError for empty recipients array', async () => {
         await expect(
-          client.batchPayout([], [], mockKeypair)
+          client.batchPayout('test-program', [], [], mockKeypair)
         ).rejects.toThrow(ValidationError);
 
         await expect(
-          client.batchPayout([], [], mockKeypair)
+          client.batchPayout('test-program', [], [], mockKeypair)
         ).rejects.toThrow('Recipients array cannot be empty');
       });
 
@@ -125,11 +139,11 @@ describe('SDK Client Error Handling', () => {
         const amounts = [1000n];
 
         await expect(
-          client.batchPayout(recipients, amounts, mockKeypair)
+          client.batchPayout('test-program', recipients, amounts, mockKeypair)
         ).rejects.toThrow(ValidationError);
 
         await expect(
-          client.batchPayout(recipients, amounts, mockKeypair)
+          client.batchPayout('test-program', recipients, amounts, mockKeypair)
         ).rejects.toThrow('same length');
       });
 
@@ -141,11 +155,11 @@ describe('SDK Client Error Handling', () => {
         const amounts = [1000n, 0n];
 
         await expect(
-          client.batchPayout(recipients, amounts, mockKeypair)
+          client.batchPayout('test-program', recipients, amounts, mockKeypair)
         ).rejects.toThrow(ValidationError);
 
         await expect(
-          client.batchPayout(recipients, amounts, mockKeypair)
+          client.batchPayout('test-program', recipients, amounts, mockKeypair)
         ).rejects.toThrow('must be greater than zero');
       });
 
@@ -157,7 +171,7 @@ describe('SDK Client Error Handling', () => {
         const amounts = [1000n, 2000n];
 
         await expect(
-          client.batchPayout(recipients, amounts, mockKeypair)
+          client.batchPayout('test-program', recipients, amounts, mockKeypair)
         ).rejects.toThrow(ValidationError);
       });
     });
@@ -165,13 +179,14 @@ describe('SDK Client Error Handling', () => {
     describe('singlePayout', () => {
       it('should throw ValidationError for invalid recipient address', async () => {
         await expect(
-          client.singlePayout('invalid', 1000n, mockKeypair)
+          client.singlePayout('test-program', 'invalid', 1000n, mockKeypair)
         ).rejects.toThrow(ValidationError);
       });
 
       it('should throw ValidationError for zero amount', async () => {
         await expect(
           client.singlePayout(
+            'test-program',
             'GAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAA',
             0n,
             mockKeypair
@@ -182,6 +197,7 @@ describe('SDK Client Error Handling', () => {
       it('should throw ValidationError for negative amount', async () => {
         await expect(
           client.singlePayout(
+            'test-program',
             'GAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAA',
             -500n,
             mockKeypair
@@ -194,6 +210,7 @@ describe('SDK Client Error Handling', () => {
       it('should throw ValidationError for invalid recipient', async () => {
         await expect(
           client.createProgramReleaseSchedule(
+            'test-program',
             'bad-address',
             1000n,
             Date.now() + 86400,
@@ -205,6 +222,7 @@ describe('SDK Client Error Handling', () => {
       it('should throw ValidationError for zero amount', async () => {
         await expect(
           client.createProgramReleaseSchedule(
+            'test-program',
             'GAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAA',
             0n,
             Date.now() + 86400,
@@ -41,18 +41,18 @@ describe('Min/Max Amount Policy Enforcement', () => {
 
   describe('lockProgramFunds – amount boundary validation', () => {
     it('rejects zero amount with ValidationError before reaching the contract', async () => {
-      await expect(client.lockProgramFunds(0n, mockKeypair))
+      await expect(client.lockProgramFunds('test-program', VALID_ADDRESS_A, 0n, mockKeypair))
         .rejects.toBeInstanceOf(ValidationError);
 
-      await expect(client.lockProgramFunds(0n, mockKeypair))
+      await expect(client.lockProgramFunds('test-program', VALID_ADDRESS_A, 0n, mockKeypair))
         .rejects.toThrow('Amount must be greater than zero');
     });
 
     it('rejects negative amount with ValidationError before reaching the contract', async () => {
-      await expect(client.lockProgramFunds(-1n, mockKeypair))
+      await expect(client.lockProgramFunds('test-program', VALID_ADDRESS_A, -1n, mockKeypair))
         .rejects.toBeInstanceOf(ValidationError);
 
-      await expect(client.lockProgramFunds(-9_999n, mockKeypair))
+      await expect(client.lockProgramFunds('test-program', VALID_ADDRESS_A, -9_999n, mockKeypair))
         .rejects.toBeInstanceOf(ValidationError);
     });
 
@@ -80,12 +80,12 @@ describe('Min/Max Amount Policy Enforcement', () => {
 
   describe('singlePayout – amount boundary validation', () => {
     it('rejects zero payout amount with ValidationError', async () => {
-      await expect(client.singlePayout(VALID_ADDRESS_A, 0n, mockKeypair))
+      await expect(client.singlePayout('test-program', VALID_ADDRESS_A, 0n, mockKeypair))
         .rejects.toBeInstanceOf(ValidationError);
     });
 
     it('rejects negative payout amount with ValidationError', async () => {
-      await expect(client.singlePayout(VALID_ADDRESS_A, -500n, mockKeypair))
+      await expect(client.singlePayout('test-program', VALID_ADDRESS_A, -500n, mockKeypair))
         .rejects.toBeInstanceOf(ValidationError);
     });
 
@@ -110,6 +110,7 @@ describe('Min/Max Amount Policy Enforcement', () => {
     it('rejects a batch where any single amount is zero', async () => {
       await expect(
         client.batchPayout(
+          'test-program',
           [VALID_ADDRESS_A, VALID_ADDRESS_B],
           [1_000n, 0n],
           mockKeypair,
@@ -120,6 +121,7 @@ describe('Min/Max Amount Policy Enforcement', () => {
     it('rejects a batch where any single amount is negative', async () => {
       await expect(
         client.batchPayout(
+          'test-program',
           [VALID_ADDRESS_A, VALID_ADDRESS_B],
           [-1n, 1_000n],
           mockKeypair,
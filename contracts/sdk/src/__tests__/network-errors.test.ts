@@ -2,6 +2,8 @@ import { ProgramEscrowClient } from '../program-escrow-client';
 import { NetworkError } from '../errors';
 import { Keypair } from '@stellar/stellar-sdk';
 
+const FUNDER = 'GAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAA';
+
 describe('SDK Network Error Handling', () => {
   let mockKeypair: Keypair;
 
@@ -26,11 +28,11 @@ describe('SDK Network Error Handling', () => {
       };
 
       await expect(
-        client.getProgramInfo()
+        client.getProgramInfo('test-program')
       ).rejects.toThrow(NetworkError);
 
       await expect(
-        client.getProgramInfo()
+        client.getProgramInfo('test-program')
       ).rejects.toThrow('Failed to connect to RPC server');
     });
 
@@ -48,7 +50,7 @@ describe('SDK Network Error Handling', () => {
       };
 
       await expect(
-        client.getRemainingBalance()
+        client.getRemainingBalance('test-program')
       ).rejects.toThrow(NetworkError);
     });
 
@@ -66,7 +68,7 @@ describe('SDK Network Error Handling', () => {
       };
 
       await expect(
-        client.getProgramInfo()
+        client.getProgramInfo('test-program')
       ).rejects.toThrow();
     });
   });
@@ -86,11 +88,11 @@ describe('SDK Network Error Handling', () => {
       };
 
       await expect(
-        client.lockProgramFunds(1000n, mockKeypair)
+        client.lockProgramFunds('test-program', FUNDER, 1000n, mockKeypair)
       ).rejects.toThrow(NetworkError);
 
       try {
-        await client.lockProgramFunds(1000n, mockKeypair);
+        await client.lockProgramFunds('test-program', FUNDER, 1000n, mockKeypair);
       } catch (error: any) {
         expect(error.statusCode).toBe(400);
       }
@@ -111,6 +113,7 @@ describe('SDK Network Error Handling', () => {
 
       await expect(
         client.singlePayout(
+          'test-program',
           'GAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAA',
           1000n,
           mockKeypair
@@ -132,7 +135,7 @@ describe('SDK Network Error Handling', () => {
       };
 
       await expect(
-        client.getProgramInfo()
+        client.getProgramInfo('test-program')
       ).rejects.toThrow(NetworkError);
     });
 
@@ -150,11 +153,11 @@ describe('SDK Network Error Handling', () => {
       };
 
       await expect(
-        client.getRemainingBalance()
+        client.getRemainingBalance('test-program')
       ).rejects.toThrow(NetworkError);
 
       try {
-        await client.getRemainingBalance();
+        await client.getRemainingBalance('test-program');
       } catch (error: any) {
         expect(error.statusCode).toBe(500);
         expect(error.message).toContain('500');
@@ -196,7 +199,7 @@ describe('SDK Network Error Handling', () => {
       };
 
       try {
-        await client.getProgramInfo();
+        await client.getProgramInfo('test-program');
         fail('Should have thrown NetworkError');
       } catch (error: any) {
         expect(error).toBeInstanceOf(NetworkError);
@@ -219,7 +222,7 @@ describe('SDK Network Error Handling', () => {
       };
 
       try {
-        await client.getProgramInfo();
+        await client.getProgramInfo('test-program');
         fail('Should have thrown NetworkError');
       } catch (error: any) {
         expect(error.message).toContain(rpcUrl);
@@ -247,10 +250,10 @@ describe('SDK Network Error Handling', () => {
       };
 
       // First call fails
-      await expect(client.getRemainingBalance()).rejects.toThrow(NetworkError);
+      await expect(client.getRemainingBalance('test-program')).rejects.toThrow(NetworkError);
       
       // Second call succeeds
-      const balance = await client.getRemainingBalance();
+      const balance = await client.getRemainingBalance('test-program');
       expect(balance).toBe(5000n);
       expect(callCount).toBe(2);
     });
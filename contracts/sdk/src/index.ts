@@ -1,9 +1,11 @@
-export { ProgramEscrowClient } from './program-escrow-client';
+export { ProgramEscrowClient, RELEASE_CURSOR_START } from './program-escrow-client';
 export type { 
   ProgramEscrowConfig, 
   ProgramData, 
   PayoutRecord,
-  ProgramReleaseSchedule 
+  ProgramReleaseSchedule,
+  ReleaseCursor,
+  ReleaseRunResult
 } from './program-escrow-client';
 
 export { 
@@ -12,7 +12,6 @@ export interface ProgramData {
   total_funds: bigint;
   remaining_balance: bigint;
   authorized_payout_key: string;
-  payout_history: PayoutRecord[];
   token_address: string;
 }
 
@@ -30,6 +29,21 @@ export interface ProgramReleaseSchedule {
   released: boolean;
 }
 
+export interface ReleaseCursor {
+  unlock_at: number;
+  schedule_id: bigint;
+}
+
+/** Cursor that starts a release sweep from the first due schedule */
+export const RELEASE_CURSOR_START: ReleaseCursor = { unlock_at: 0, schedule_id: 0n };
+
+export interface ReleaseRunResult {
+  released_count: number;
+  skipped_count: number;
+  next_cursor: ReleaseCursor;
+  completed: boolean;
+}
+
 /**
  * Client for interacting with the ProgramEscrow Soroban contract
  */
@@ -63,9 +77,7 @@ export class ProgramEscrowClient {
     tokenAddress: string,
     sourceKeypair: Keypair
   ): Promise<ProgramData> {
-    if (!programId || programId.trim().length === 0) {
-      throw new ValidationError('Program ID cannot be empty', 'programId');
-    }
+    this.validateProgramId(programId);
 
     this.validateAddress(authorizedPayoutKey, 'authorizedPayoutKey');
     this.validateAddress(tokenAddress, 'tokenAddress');
@@ -83,12 +95,17 @@ export class ProgramEscrowClient {
   }
 
   /**
-   * Lock funds into the program escrow
+   * Lock funds from `funder` into the given program
    */
   async lockProgramFunds(
+    programId: string,
+    funder: string,
     amount: bigint,
     sourceKeypair: Keypair
   ): Promise<ProgramData> {
+    this.validateProgramId(programId);
+    this.validateAddress(funder, 'funder');
+
     if (amount <= 0n) {
       throw new ValidationError('Amount must be greater than zero', 'amount');
     }
@@ -96,7 +113,7 @@ export class ProgramEscrowClient {
     try {
       const result = await this.invokeContract(
         'lock_program_funds',
-        [amount],
+        [programId, funder, amount],
         sourceKeypair
       );
       return this.parseProgramData(result);
@@ -109,10 +126,13 @@ export class ProgramEscrowClient {
    * Execute batch payouts to multiple recipients
    */
   async batchPayout(
+    programId: string,
     recipients: string[],
     amounts: bigint[],
     sourceKeypair: Keypair
   ): Promise<ProgramData> {
+    this.validateProgramId(programId);
+
     if (recipients.length === 0) {
       throw new ValidationError('Recipients array cannot be empty', 'recipients');
     }
@@ -140,7 +160,7 @@ export class ProgramEscrowClient {
     try {
       const result = await this.invokeContract(
         'batch_payout',
-        [recipients, amounts],
+        [programId, recipients, amounts],
         sourceKeypair
       );
       return this.parseProgramData(result);
@@ -153,10 +173,12 @@ export class ProgramEscrowClient {
    * Execute a single payout
    */
   async singlePayout(
+    programId: string,
     recipient: string,
     amount: bigint,
     sourceKeypair: Keypair
   ): Promise<ProgramData> {
+    this.validateProgramId(programId);
     this.validateAddress(recipient, 'recipient');
     
     if (amount <= 0n) {
@@ -166,7 +188,7 @@ export class ProgramEscrowClient {
     try {
       const result = await this.invokeContract(
         'single_payout',
-        [recipient, amount],
+        [programId, recipient, amount],
         sourceKeypair
       );
       return this.parseProgramData(result);
@@ -178,9 +200,11 @@ export class ProgramEscrowClient {
   /**
    * Get program information
    */
-  async getProgramInfo(): Promise<ProgramData> {
+  async getProgramInfo(programId: string): Promise<ProgramData> {
+    this.validateProgramId(programId);
+
     try {
-      const result = await this.invokeContract('get_program_info', []);
+      const result = await this.invokeContract('get_program_info', [programId]);
       return this.parseProgramData(result);
     } catch (error) {
       throw this.handleError(error);
@@ -190,9 +214,11 @@ export class ProgramEscrowClient {
   /**
    * Get remaining balance
    */
-  async getRemainingBalance(): Promise<bigint> {
+  async getRemainingBalance(programId: string): Promise<bigint> {
+    this.validateProgramId(programId);
+
     try {
-      const result = await this.invokeContract('get_remaining_balance', []);
+      const result = await this.invokeContract('get_remaining_balance', [programId]);
       return BigInt(result);
     } catch (error) {
       throw this.handleError(error);
@@ -203,11 +229,13 @@ export class ProgramEscrowClient {
    * Create a release schedule
    */
   async createProgramReleaseSchedule(
+    programId: string,
     recipient: string,
     amount: bigint,
     releaseTimestamp: number,
     sourceKeypair: Keypair
   ): Promise<ProgramReleaseSchedule> {
+    this.validateProgramId(programId);
     this.validateAddress(recipient, 'recipient');
     
     if (amount <= 0n) {
@@ -217,7 +245,7 @@ export class ProgramEscrowClient {
     try {
       const result = await this.invokeContract(
         'create_program_release_schedule',
-        [recipient, amount, releaseTimestamp],
+        [programId, recipient, amount, releaseTimestamp],
         sourceKeypair
       );
       return this.parseReleaseSchedule(result);
@@ -227,21 +255,36 @@ export class ProgramEscrowClient {
   }
 
   /**
-   * Trigger program releases
+   * Trigger due releases for a program, examining at most `maxCount`
+   * schedules after `cursor`. Start with `RELEASE_CURSOR_START` and pass
+   * `next_cursor` back until `completed` is true.
    */
-  async triggerProgramReleases(sourceKeypair: Keypair): Promise<number> {
+  async triggerProgramReleases(
+    programId: string,
+    cursor: ReleaseCursor,
+    maxCount: number,
+    sourceKeypair: Keypair
+  ): Promise<ReleaseRunResult> {
+    this.validateProgramId(programId);
+
     try {
       const result = await this.invokeContract(
         'trigger_program_releases',
-        [],
+        [programId, cursor, maxCount],
         sourceKeypair
       );
-      return Number(result);
+      return result as ReleaseRunResult;
     } catch (error) {
       throw this.handleError(error);
     }
   }
 
+  private validateProgramId(programId: string): void {
+    if (!programId || programId.trim().length === 0) {
+      throw new ValidationError('Program ID cannot be empty', 'programId');
+    }
+  }
+
   private validateAddress(address: string, fieldName: string): void {
     if (!address || address.trim().length === 0) {
       throw new ValidationError(`${fieldName} cannot be empty`, fieldName);